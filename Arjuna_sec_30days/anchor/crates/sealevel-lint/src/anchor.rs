@@ -0,0 +1,355 @@
+//! What `#[derive(Accounts)]` structs declare: each account's type and its
+//! `#[account(..)]` constraints.
+
+use std::collections::HashSet;
+
+use proc_macro2::{Span, TokenStream, TokenTree};
+use syn::{
+    punctuated::Punctuated,
+    visit::{self, Visit},
+    BinOp, Expr, ItemStruct, Token, Type, UnOp,
+};
+
+use crate::{
+    consts::Consts,
+    scope::{self, last_segment, FnScope},
+};
+
+pub(crate) struct AccountsStruct<'ast> {
+    pub name: String,
+    pub fields: Vec<AccountField<'ast>>,
+}
+
+pub(crate) struct AccountField<'ast> {
+    pub name: String,
+    pub ty: &'ast Type,
+    pub constraints: Vec<Constraint>,
+    pub span: Span,
+}
+
+/// One comma-separated entry of `#[account(..)]`, by name: `mut`, `address`,
+/// `token::mint`...
+pub(crate) struct Constraint {
+    pub name: String,
+    /// Where the name starts.
+    pub span: Span,
+    /// Empty for flags such as `mut`.
+    pub value: TokenStream,
+}
+
+impl AccountField<'_> {
+    /// The account type, `UncheckedAccount` for `UncheckedAccount<'info>`.
+    pub fn kind(&self) -> String {
+        last_segment(self.ty).unwrap_or_default()
+    }
+
+    pub fn constraint(&self, name: &str) -> Option<&Constraint> {
+        self.constraints
+            .iter()
+            .find(|constraint| constraint.name == name)
+    }
+
+    /// Whether Anchor deserializes nothing and checks nothing about it unless
+    /// a constraint says so.
+    pub fn is_raw(&self) -> bool {
+        matches!(self.kind().as_str(), "UncheckedAccount" | "AccountInfo")
+    }
+}
+
+/// Every `#[derive(Accounts)]` struct in `file`.
+pub(crate) fn accounts_structs(file: &syn::File) -> Vec<AccountsStruct<'_>> {
+    struct Structs<'ast>(Vec<AccountsStruct<'ast>>);
+    impl<'ast> Visit<'ast> for Structs<'ast> {
+        fn visit_item_struct(&mut self, item: &'ast ItemStruct) {
+            if derives_accounts(item) {
+                self.0.push(AccountsStruct {
+                    name: item.ident.to_string(),
+                    fields: item
+                        .fields
+                        .iter()
+                        .filter_map(|field| {
+                            Some(AccountField {
+                                name: field.ident.as_ref()?.to_string(),
+                                ty: &field.ty,
+                                constraints: field
+                                    .attrs
+                                    .iter()
+                                    .filter(|attr| attr.path().is_ident("account"))
+                                    .flat_map(constraints)
+                                    .collect(),
+                                span: field.ident.as_ref()?.span(),
+                            })
+                        })
+                        .collect(),
+                });
+            }
+            visit::visit_item_struct(self, item);
+        }
+    }
+    let mut structs = Structs(Vec::new());
+    structs.visit_file(file);
+    structs.0
+}
+
+/// The declarations of the account field `name` that `function` can reach:
+/// the one in its `Context<_>` or `self` type if that is in the file, every
+/// field called `name` otherwise.
+pub(crate) fn fields<'a, 'ast>(
+    structs: &'a [AccountsStruct<'ast>],
+    function: &FnScope,
+    name: &str,
+) -> Vec<&'a AccountField<'ast>> {
+    let named = |s: &'a AccountsStruct<'ast>| s.fields.iter().filter(move |f| f.name == name);
+    let owner = function
+        .accounts_struct()
+        .or_else(|| function.self_ty.clone());
+    if let Some(owner) = structs.iter().find(|s| Some(&s.name) == owner.as_ref()) {
+        return named(owner).collect();
+    }
+    structs.iter().flat_map(named).collect()
+}
+
+/// Whether `a` and `b` cannot be the same account: their struct's
+/// constraints keep them apart, or every handler using it compares their keys.
+pub(crate) fn kept_apart(
+    consts: &Consts,
+    accounts: &AccountsStruct,
+    handlers: &[&FnScope],
+    a: &AccountField,
+    b: &AccountField,
+) -> bool {
+    distinct(consts, accounts, a, b)
+        || (!handlers.is_empty() && handlers.iter().all(|handler| compares(handler, a, b)))
+}
+
+/// Whether the struct's constraints keep `a` and `b` apart.
+fn distinct(
+    consts: &Consts,
+    accounts: &AccountsStruct,
+    a: &AccountField,
+    b: &AccountField,
+) -> bool {
+    if let (Some(a), Some(b)) = (a.constraint("seeds"), b.constraint("seeds")) {
+        if distinct_seeds(consts, &a.value, &b.value) {
+            return true;
+        }
+    }
+    accounts
+        .fields
+        .iter()
+        .flat_map(|field| &field.constraints)
+        .filter(|constraint| constraint.name == "constraint")
+        .filter_map(|constraint| syn::parse2::<Expr>(without_error(&constraint.value)).ok())
+        .any(|expr| {
+            let mut keys = Keys::new(a, b);
+            keys.apart(&expr, true);
+            keys.found
+        })
+}
+
+/// Whether two `seeds = [..]` lists always derive different addresses: they
+/// have as many components, and one of them is a literal or a constant in
+/// both lists and differs. Seeds that only differ in a key, such as
+/// `[b"v", a.key().as_ref()]` and `[b"v", b.key().as_ref()]`, can still be
+/// the same account.
+fn distinct_seeds(consts: &Consts, a: &TokenStream, b: &TokenStream) -> bool {
+    let (Some(a), Some(b)) = (seeds(a), seeds(b)) else {
+        return false;
+    };
+    a.len() == b.len()
+        && a.iter().zip(&b).any(|(a, b)| {
+            match (seed_constant(consts, a, 0), seed_constant(consts, b, 0)) {
+                (Some(SeedConstant::Bytes(a)), Some(SeedConstant::Bytes(b))) => a != b,
+                (Some(SeedConstant::Named(a)), Some(SeedConstant::Named(b))) => a != b,
+                _ => false,
+            }
+        })
+}
+
+/// The components of `seeds = [..]`.
+fn seeds(value: &TokenStream) -> Option<Vec<Expr>> {
+    match syn::parse2::<Expr>(value.clone()).ok()? {
+        Expr::Array(array) => Some(array.elems.into_iter().collect()),
+        _ => None,
+    }
+}
+
+/// A seed component that is the same for every account.
+enum SeedConstant {
+    /// A string or byte string literal, or a `const` of this file holding one.
+    Bytes(Vec<u8>),
+    /// A `SCREAMING_CASE` constant defined elsewhere.
+    Named(String),
+}
+
+fn seed_constant(consts: &Consts, expr: &Expr, depth: usize) -> Option<SeedConstant> {
+    if depth > 8 {
+        return None;
+    }
+    match scope::strip(expr) {
+        Expr::MethodCall(call)
+            if call.args.is_empty()
+                && matches!(call.method.to_string().as_str(), "as_ref" | "as_bytes") =>
+        {
+            seed_constant(consts, &call.receiver, depth + 1)
+        }
+        Expr::Lit(lit) => match &lit.lit {
+            syn::Lit::ByteStr(bytes) => Some(SeedConstant::Bytes(bytes.value())),
+            syn::Lit::Str(string) => Some(SeedConstant::Bytes(string.value().into_bytes())),
+            _ => None,
+        },
+        Expr::Path(path) => {
+            let name = path.path.segments.last()?.ident.to_string();
+            if let Some(value) = consts.get(&name) {
+                return seed_constant(consts, value, depth + 1);
+            }
+            let screaming = name
+                .chars()
+                .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_');
+            screaming.then_some(SeedConstant::Named(name))
+        }
+        _ => None,
+    }
+}
+
+/// A constraint expression without its `@ Error` suffix.
+fn without_error(value: &TokenStream) -> TokenStream {
+    value
+        .clone()
+        .into_iter()
+        .take_while(|token| !matches!(token, TokenTree::Punct(punct) if punct.as_char() == '@'))
+        .collect()
+}
+
+/// Whether `handler` compares the keys of `a` and `b`.
+fn compares(handler: &FnScope, a: &AccountField, b: &AccountField) -> bool {
+    let mut keys = Keys::new(a, b);
+    keys.visit_block(handler.block);
+    keys.found
+}
+
+/// Looks for a comparison between the keys of two accounts: in a handler,
+/// `==` or `!=`, or a `require_keys_neq!`/`assert_ne!`-style macro; in a
+/// constraint, see [`Keys::apart`].
+struct Keys {
+    names: HashSet<String>,
+    found: bool,
+}
+
+impl Keys {
+    fn new(a: &AccountField, b: &AccountField) -> Self {
+        Keys {
+            names: HashSet::from([a.name.clone(), b.name.clone()]),
+            found: false,
+        }
+    }
+
+    fn compare(&mut self, left: &Expr, right: &Expr) {
+        if let (Some(left), Some(right)) = (scope::key_of(left), scope::key_of(right)) {
+            if left.name() != right.name()
+                && self.names.contains(left.name())
+                && self.names.contains(right.name())
+            {
+                self.found = true;
+            }
+        }
+    }
+
+    /// Looks for the keys being different whenever `cond` evaluates to
+    /// `holds`, as a constraint that has to hold: `a.key() != b.key()` or
+    /// `!(a.key() == b.key())`.
+    fn apart(&mut self, cond: &Expr, holds: bool) {
+        match scope::strip(cond) {
+            Expr::Unary(unary) if matches!(unary.op, UnOp::Not(_)) => {
+                self.apart(&unary.expr, !holds)
+            }
+            Expr::Binary(binary) => match (&binary.op, holds) {
+                (BinOp::Ne(_), true) | (BinOp::Eq(_), false) => {
+                    self.compare(&binary.left, &binary.right)
+                }
+                (BinOp::And(_), true) | (BinOp::Or(_), false) => {
+                    self.apart(&binary.left, holds);
+                    self.apart(&binary.right, holds);
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for Keys {
+    fn visit_expr_binary(&mut self, binary: &'ast syn::ExprBinary) {
+        if matches!(binary.op, BinOp::Eq(_) | BinOp::Ne(_)) {
+            self.compare(&binary.left, &binary.right);
+        }
+        visit::visit_expr_binary(self, binary);
+    }
+
+    fn visit_macro(&mut self, mac: &'ast syn::Macro) {
+        let Ok(args) = mac.parse_body_with(Punctuated::<Expr, Token![,]>::parse_terminated) else {
+            return;
+        };
+        let name = mac.path.segments.last().map(|s| s.ident.to_string());
+        if name.is_some_and(|name| name.ends_with("_neq") || name.ends_with("_ne"))
+            && args.len() >= 2
+        {
+            self.compare(&args[0], &args[1]);
+        }
+        args.iter().for_each(|arg| self.visit_expr(arg));
+    }
+}
+
+fn derives_accounts(item: &ItemStruct) -> bool {
+    item.attrs.iter().any(|attr| {
+        attr.path().is_ident("derive")
+            && attr.meta.require_list().is_ok_and(|list| {
+                list.tokens
+                    .clone()
+                    .into_iter()
+                    .any(|token| matches!(token, TokenTree::Ident(ident) if ident == "Accounts"))
+            })
+    })
+}
+
+/// The entries of `#[account(..)]`, split at its top-level commas. A name is
+/// the leading path of an entry, `token::mint` in `token::mint = mint`, and
+/// the value is what follows the `=`.
+fn constraints(attr: &syn::Attribute) -> Vec<Constraint> {
+    let Ok(list) = attr.meta.require_list() else {
+        return Vec::new();
+    };
+    let mut constraints = Vec::new();
+    let mut current: Option<Constraint> = None;
+    let mut in_name = true;
+    for token in list.tokens.clone() {
+        match &token {
+            TokenTree::Punct(punct) if punct.as_char() == ',' => {
+                constraints.extend(current.take());
+                in_name = true;
+            }
+            TokenTree::Ident(ident) if in_name => {
+                let constraint = current.get_or_insert_with(|| Constraint {
+                    name: String::new(),
+                    span: ident.span(),
+                    value: TokenStream::new(),
+                });
+                constraint.name.push_str(&ident.to_string());
+            }
+            TokenTree::Punct(punct) if in_name && punct.as_char() == ':' => {
+                if let Some(constraint) = &mut current {
+                    constraint.name.push(':');
+                }
+            }
+            TokenTree::Punct(punct) if in_name && punct.as_char() == '=' => in_name = false,
+            _ => {
+                in_name = false;
+                if let Some(constraint) = &mut current {
+                    constraint.value.extend([token]);
+                }
+            }
+        }
+    }
+    constraints.extend(current);
+    constraints
+}
@@ -0,0 +1,85 @@
+//! Integer values known from the syntax alone: literals, `+ - *` over them,
+//! the `const` items of the same file, and the integer constants of the rest
+//! of the crate as [`Context::consts`] has them.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use syn::{visit::Visit, BinOp, Expr, Lit};
+
+use crate::Context;
+
+/// `const` items of a file by name, associated ones included.
+pub(crate) struct Consts<'ast> {
+    items: HashMap<String, &'ast Expr>,
+    /// Values of the crate's other constants, for names the file does not declare.
+    outside: BTreeMap<String, usize>,
+}
+
+impl<'ast> Consts<'ast> {
+    pub fn new(file: &'ast syn::File, cx: &Context) -> Self {
+        let mut consts = Consts {
+            items: HashMap::new(),
+            outside: cx.consts.clone(),
+        };
+        consts.visit_file(file);
+        consts
+    }
+
+    /// Every `const` of the file whose value can be worked out, by name.
+    pub fn values(&self) -> impl Iterator<Item = (&str, usize)> + '_ {
+        self.items
+            .iter()
+            .filter_map(|(name, expr)| Some((name.as_str(), self.eval(expr)?)))
+    }
+
+    /// The expression the `const` called `name` is defined as.
+    pub fn get(&self, name: &str) -> Option<&'ast Expr> {
+        self.items.get(name).copied()
+    }
+
+    /// The value of `expr`, if it can be worked out.
+    pub fn eval(&self, expr: &Expr) -> Option<usize> {
+        self.eval_in(expr, &mut HashSet::new())
+    }
+
+    fn eval_in(&self, expr: &Expr, seen: &mut HashSet<String>) -> Option<usize> {
+        match expr {
+            Expr::Lit(lit) => match &lit.lit {
+                Lit::Int(int) => int.base10_parse().ok(),
+                _ => None,
+            },
+            Expr::Paren(paren) => self.eval_in(&paren.expr, seen),
+            Expr::Cast(cast) => self.eval_in(&cast.expr, seen),
+            Expr::Binary(binary) => {
+                let left = self.eval_in(&binary.left, seen)?;
+                let right = self.eval_in(&binary.right, seen)?;
+                match binary.op {
+                    BinOp::Add(_) => left.checked_add(right),
+                    BinOp::Sub(_) => left.checked_sub(right),
+                    BinOp::Mul(_) => left.checked_mul(right),
+                    _ => None,
+                }
+            }
+            Expr::Path(path) => {
+                let name = path.path.segments.last()?.ident.to_string();
+                let Some(value) = self.items.get(&name) else {
+                    return self.outside.get(&name).copied();
+                };
+                seen.insert(name)
+                    .then(|| self.eval_in(value, seen))
+                    .flatten()
+            }
+            _ => None,
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for Consts<'ast> {
+    fn visit_item_const(&mut self, item: &'ast syn::ItemConst) {
+        self.items.insert(item.ident.to_string(), &item.expr);
+    }
+
+    fn visit_impl_item_const(&mut self, item: &'ast syn::ImplItemConst) {
+        self.items.insert(item.ident.to_string(), &item.expr);
+    }
+}
@@ -0,0 +1,273 @@
+//! Which native accounts a function has checked at each point, for rules about
+//! a check that has to come before something else.
+//!
+//! A check is a statement that reads the checked field, `is_signer` or
+//! `owner`, of an account so that the code after it only runs when the check
+//! passed: an `if` whose branch for a failed check [`diverges`], a
+//! `require!`/`assert!`-style macro, or a call to a helper named after the
+//! check such as `assert_signer(a)?`. It covers the statements after it in its
+//! block. The condition of any `if` also covers the branch it proves checked,
+//! so `if !a.is_signer { .. }` covers its `else` and not its body.
+
+use std::collections::HashSet;
+
+use syn::{
+    punctuated::Punctuated,
+    visit::{self, Visit},
+    BinOp, Block, Expr, Lit, Stmt, Token, UnOp,
+};
+
+use crate::scope::{self, AccountRef, FnScope};
+
+/// Locals holding an account from `next_account_info`.
+pub(crate) fn native_accounts(function: &FnScope) -> HashSet<String> {
+    function
+        .defs
+        .iter()
+        .filter(|(_, defs)| {
+            defs.iter()
+                .any(|def| scope::called_fn(def).as_deref() == Some("next_account_info"))
+        })
+        .map(|(name, _)| name.clone())
+        .collect()
+}
+
+/// The native account `expr` refers to.
+pub(crate) fn native_account(accounts: &HashSet<String>, expr: &Expr) -> Option<String> {
+    match scope::account(expr)? {
+        AccountRef::Local(name) if accounts.contains(&name) => Some(name),
+        _ => None,
+    }
+}
+
+/// Whether `block` always leaves the function or panics: it holds a `return`,
+/// an `Err(..)?`, or a `panic!`-style macro.
+pub(crate) fn diverges(block: &Block) -> bool {
+    block.stmts.iter().any(|stmt| match stmt {
+        Stmt::Expr(expr, _) => match scope::strip(expr) {
+            Expr::Return(_) => true,
+            Expr::Try(expr) => scope::called_fn(&expr.expr).as_deref() == Some("Err"),
+            Expr::Macro(mac) => panics(&mac.mac),
+            Expr::Block(inner) => diverges(&inner.block),
+            _ => false,
+        },
+        Stmt::Macro(mac) => panics(&mac.mac),
+        _ => false,
+    })
+}
+
+fn panics(mac: &syn::Macro) -> bool {
+    mac.path.segments.last().is_some_and(|segment| {
+        matches!(
+            segment.ident.to_string().as_str(),
+            "panic" | "unreachable" | "unimplemented" | "todo"
+        )
+    })
+}
+
+/// The value of a `true` or `false` literal.
+fn boolean(expr: &Expr) -> Option<bool> {
+    match scope::strip(expr) {
+        Expr::Lit(lit) => match &lit.lit {
+            Lit::Bool(value) => Some(value.value),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Whether `mac` is a `require!`/`assert!`-style check.
+fn asserts(mac: &syn::Macro) -> bool {
+    mac.path.segments.last().is_some_and(|segment| {
+        let name = segment.ident.to_string();
+        name.starts_with("require") || name.starts_with("assert")
+    })
+}
+
+/// Calls `on_expr` with every expression of `function` and the accounts
+/// checked for `field` where it runs.
+pub(crate) fn walk<'ast>(
+    function: &FnScope<'ast>,
+    accounts: &HashSet<String>,
+    field: &'static str,
+    on_expr: impl FnMut(&'ast Expr, &HashSet<String>),
+) {
+    let mut walk = Walk {
+        accounts,
+        field,
+        checked: vec![HashSet::new()],
+        on_expr,
+    };
+    walk.visit_block(function.block);
+}
+
+struct Walk<'a, F> {
+    accounts: &'a HashSet<String>,
+    field: &'static str,
+    /// Accounts checked so far, one set per enclosing block.
+    checked: Vec<HashSet<String>>,
+    on_expr: F,
+}
+
+impl<F> Walk<'_, F> {
+    /// Accounts whose checked field `expr` reads.
+    fn reads(&self, expr: &Expr) -> Vec<String> {
+        struct Reads<'w, 'a, F>(&'w Walk<'a, F>, Vec<String>);
+        impl<'ast, F> Visit<'ast> for Reads<'_, '_, F> {
+            fn visit_expr_field(&mut self, field: &'ast syn::ExprField) {
+                if matches!(&field.member, syn::Member::Named(name) if name == self.0.field) {
+                    self.1.extend(native_account(self.0.accounts, &field.base));
+                }
+                visit::visit_expr_field(self, field);
+            }
+
+            fn visit_macro(&mut self, mac: &'ast syn::Macro) {
+                if let Ok(args) =
+                    mac.parse_body_with(Punctuated::<Expr, Token![,]>::parse_terminated)
+                {
+                    args.iter().for_each(|arg| self.visit_expr(arg));
+                }
+            }
+        }
+        let mut reads = Reads(self, Vec::new());
+        reads.visit_expr(expr);
+        reads.1
+    }
+
+    /// Accounts `cond` proves checked when it evaluates to `holds`: `a.is_signer`
+    /// when true, `a.owner != program_id` when false.
+    fn proves(&self, cond: &Expr, holds: bool) -> Vec<String> {
+        match scope::strip(cond) {
+            Expr::Unary(unary) if matches!(unary.op, UnOp::Not(_)) => {
+                self.proves(&unary.expr, !holds)
+            }
+            Expr::Binary(binary) => match (&binary.op, holds) {
+                (BinOp::And(_), true) | (BinOp::Or(_), false) => {
+                    let mut proven = self.proves(&binary.left, holds);
+                    proven.extend(self.proves(&binary.right, holds));
+                    proven
+                }
+                // Either side may be the one that decided it
+                (BinOp::And(_), false) | (BinOp::Or(_), true) => {
+                    let right = self.proves(&binary.right, holds);
+                    self.proves(&binary.left, holds)
+                        .into_iter()
+                        .filter(|account| right.contains(account))
+                        .collect()
+                }
+                (BinOp::Eq(_) | BinOp::Ne(_), _) => {
+                    let equal = matches!(binary.op, BinOp::Eq(_)) == holds;
+                    // `a.is_signer == false` is `!a.is_signer`
+                    match (boolean(&binary.left), boolean(&binary.right)) {
+                        (Some(value), _) => self.proves(&binary.right, value == equal),
+                        (_, Some(value)) => self.proves(&binary.left, value == equal),
+                        _ if equal => self.reads(cond),
+                        _ => Vec::new(),
+                    }
+                }
+                _ => Vec::new(),
+            },
+            _ if holds => self.reads(cond),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Accounts a `require!`/`assert!`-style macro checks.
+    fn asserted(&self, mac: &syn::Macro) -> Vec<String> {
+        let Ok(args) = mac.parse_body_with(Punctuated::<Expr, Token![,]>::parse_terminated) else {
+            return Vec::new();
+        };
+        let name = mac.path.segments.last().map(|s| s.ident.to_string());
+        match name.as_deref() {
+            Some(name) if name.ends_with("_neq") || name.ends_with("_ne") => Vec::new(),
+            Some(name) if name.ends_with("_eq") => {
+                args.iter().flat_map(|arg| self.reads(arg)).collect()
+            }
+            _ => args
+                .first()
+                .map(|cond| self.proves(cond, true))
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Accounts `stmt` checks for everything after it.
+    fn checks(&self, stmt: &Stmt) -> Vec<String> {
+        let expr = match stmt {
+            Stmt::Expr(expr, _) => expr,
+            Stmt::Local(local) => match &local.init {
+                Some(init) => &*init.expr,
+                None => return Vec::new(),
+            },
+            Stmt::Macro(mac) if asserts(&mac.mac) => return self.asserted(&mac.mac),
+            Stmt::Macro(_) | Stmt::Item(_) => return Vec::new(),
+        };
+        let call = match scope::strip(expr) {
+            Expr::If(branch) if diverges(&branch.then_branch) => {
+                return self.proves(&branch.cond, false)
+            }
+            Expr::If(branch) => {
+                return match branch.else_branch.as_ref().map(|(_, e)| &**e) {
+                    Some(Expr::Block(otherwise)) if diverges(&otherwise.block) => {
+                        self.proves(&branch.cond, true)
+                    }
+                    _ => Vec::new(),
+                }
+            }
+            Expr::Macro(mac) if asserts(&mac.mac) => return self.asserted(&mac.mac),
+            Expr::Macro(_) => return Vec::new(),
+            Expr::Try(expr) => &*expr.expr,
+            call => call,
+        };
+        // `assert_signer(authority)?`, `check_owner(vault, program_id)`
+        let helper = self.field.trim_start_matches("is_");
+        let named = scope::called_fn(call).is_some_and(|name| name.contains(helper));
+        match call {
+            Expr::Call(call) if named => call
+                .args
+                .iter()
+                .filter_map(|arg| native_account(self.accounts, arg))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl<'ast, F: FnMut(&'ast Expr, &HashSet<String>)> Visit<'ast> for Walk<'_, F> {
+    fn visit_block(&mut self, block: &'ast Block) {
+        let outer = self.checked.last().cloned().unwrap_or_default();
+        self.checked.push(outer);
+        for stmt in &block.stmts {
+            self.visit_stmt(stmt);
+            let checked = self.checks(stmt);
+            self.checked
+                .last_mut()
+                .expect("pushed above")
+                .extend(checked);
+        }
+        self.checked.pop();
+    }
+
+    fn visit_expr_if(&mut self, branch: &'ast syn::ExprIf) {
+        self.visit_expr(&branch.cond);
+        let mut checked = self.checked.last().cloned().unwrap_or_default();
+        checked.extend(self.proves(&branch.cond, true));
+        self.checked.push(checked);
+        self.visit_block(&branch.then_branch);
+        self.checked.pop();
+        if let Some((_, otherwise)) = &branch.else_branch {
+            let mut checked = self.checked.last().cloned().unwrap_or_default();
+            checked.extend(self.proves(&branch.cond, false));
+            self.checked.push(checked);
+            self.visit_expr(otherwise);
+            self.checked.pop();
+        }
+    }
+
+    fn visit_expr(&mut self, expr: &'ast Expr) {
+        let checked = self.checked.last().expect("never empty");
+        (self.on_expr)(expr, checked);
+        visit::visit_expr(self, expr);
+    }
+
+    fn visit_item_fn(&mut self, _: &'ast syn::ItemFn) {}
+}
@@ -0,0 +1,76 @@
+//! Names brought into scope with `use`, so a call can be matched on the path it
+//! was imported from rather than the name it is called by.
+
+use std::collections::HashMap;
+
+use syn::{visit::Visit, UseTree};
+
+/// Every name the `use` items of a file bind, with the path it stands for.
+/// Glob imports bind nothing; a name bound twice keeps its first path.
+pub(crate) struct Imports(HashMap<String, Vec<String>>);
+
+impl Imports {
+    pub fn new(file: &syn::File) -> Self {
+        let mut imports = Imports(HashMap::new());
+        imports.visit_file(file);
+        imports
+    }
+
+    /// The segments of `path` with an imported first segment expanded, so
+    /// `transfer` after `use spl_token::instruction::transfer` and
+    /// `instruction::transfer` after `use spl_token::instruction` both give
+    /// `spl_token::instruction::transfer`.
+    pub fn resolve(&self, path: &syn::Path) -> Vec<String> {
+        let mut segments: Vec<_> = path
+            .segments
+            .iter()
+            .map(|segment| segment.ident.to_string())
+            .collect();
+        if path.leading_colon.is_none() {
+            if let Some(full) = segments.first().and_then(|first| self.0.get(first)) {
+                segments.splice(..1, full.iter().cloned());
+            }
+        }
+        segments
+    }
+
+    fn bind(&mut self, tree: &UseTree, prefix: &mut Vec<String>) {
+        match tree {
+            UseTree::Path(path) => {
+                prefix.push(path.ident.to_string());
+                self.bind(&path.tree, prefix);
+                prefix.pop();
+            }
+            // `use spl_token::instruction::{self, transfer}`
+            UseTree::Name(name) if name.ident == "self" => {
+                if let Some(last) = prefix.last() {
+                    self.0.entry(last.clone()).or_insert_with(|| prefix.clone());
+                }
+            }
+            UseTree::Name(name) => {
+                let mut full = prefix.clone();
+                full.push(name.ident.to_string());
+                self.0.entry(name.ident.to_string()).or_insert(full);
+            }
+            UseTree::Rename(rename) => {
+                let mut full = prefix.clone();
+                if rename.ident != "self" {
+                    full.push(rename.ident.to_string());
+                }
+                self.0.entry(rename.rename.to_string()).or_insert(full);
+            }
+            UseTree::Glob(_) => {}
+            UseTree::Group(group) => {
+                for tree in &group.items {
+                    self.bind(tree, prefix);
+                }
+            }
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for Imports {
+    fn visit_item_use(&mut self, item: &'ast syn::ItemUse) {
+        self.bind(&item.tree, &mut Vec::new());
+    }
+}
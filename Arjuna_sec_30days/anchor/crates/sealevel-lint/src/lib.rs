@@ -0,0 +1,262 @@
+//! `sealevel-lint`: parses program sources with `syn` and flags the patterns
+//! behind the lab's vulnerability classes.
+//!
+//! Every check is a [`Rule`] in [`rules`]. A rule only sees one file's syntax
+//! tree, so it reasons about names and local data flow, not types; findings
+//! are leads for a reviewer, not proof.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use proc_macro2::Span;
+
+mod anchor;
+mod consts;
+mod guard;
+mod imports;
+pub mod rules;
+mod scope;
+
+pub use rules::Rule;
+
+/// Something a [`Rule`] found, before it is tied to a file.
+#[derive(Debug)]
+pub struct Diagnostic {
+    pub span: Span,
+    pub message: String,
+}
+
+/// How much a finding should hold up a review.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    /// Worth a look, but something else already mitigates it.
+    Info,
+    Warning,
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Level::Info => "info",
+            Level::Warning => "warning",
+        })
+    }
+}
+
+/// What rules know about the build a file is part of.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Context {
+    /// `[profile.release] overflow-checks = true` in the workspace manifest.
+    pub overflow_checks: bool,
+    /// Integer `const`s declared in the crate's sources, by name, so a file
+    /// can use one declared elsewhere: `Vault::SIZE` from the crate root.
+    /// Names declared twice with different values are left out.
+    pub consts: BTreeMap<String, usize>,
+}
+
+impl Context {
+    /// The settings in `root`'s `Cargo.toml`. A missing or unreadable manifest
+    /// gives the defaults.
+    pub fn for_workspace(root: &Path) -> Self {
+        fs::read_to_string(root.join("Cargo.toml"))
+            .ok()
+            .and_then(|manifest| manifest.parse::<toml::Table>().ok())
+            .map(|manifest| Context::from_manifest(&manifest))
+            .unwrap_or_default()
+    }
+
+    /// The settings of the cargo workspace `path` is in: the nearest
+    /// `Cargo.toml` with a `[workspace]` table, or else the nearest one. The
+    /// constants are those of the `src/` directory next to the nearest one.
+    /// Missing or unreadable manifests give the defaults.
+    pub fn for_path(path: &Path) -> Self {
+        let Ok(path) = std::path::absolute(path) else {
+            return Context::default();
+        };
+        let mut nearest = None;
+        let mut crate_dir = None;
+        let mut workspace = None;
+        for dir in path.ancestors() {
+            let Ok(manifest) = fs::read_to_string(dir.join("Cargo.toml")) else {
+                continue;
+            };
+            crate_dir.get_or_insert(dir);
+            let Ok(manifest) = manifest.parse::<toml::Table>() else {
+                continue;
+            };
+            if manifest.contains_key("workspace") {
+                workspace = Some(manifest);
+                break;
+            }
+            nearest.get_or_insert(manifest);
+        }
+        let mut cx = workspace
+            .or(nearest)
+            .map(|manifest| Context::from_manifest(&manifest))
+            .unwrap_or_default();
+        if let Some(dir) = crate_dir {
+            cx.consts = crate_consts(&dir.join("src"));
+        }
+        cx
+    }
+
+    fn from_manifest(manifest: &toml::Table) -> Self {
+        let overflow_checks = manifest
+            .get("profile")
+            .and_then(|profile| profile.get("release"))
+            .and_then(|release| release.get("overflow-checks"))
+            .and_then(toml::Value::as_bool)
+            .unwrap_or(false);
+        Context {
+            overflow_checks,
+            consts: BTreeMap::new(),
+        }
+    }
+}
+
+/// The integer constants declared in the `.rs` files under `src`. Files that
+/// do not parse are skipped; their errors surface when they are linted.
+fn crate_consts(src: &Path) -> BTreeMap<String, usize> {
+    let mut values: BTreeMap<String, Option<usize>> = BTreeMap::new();
+    for file in rust_files(src).unwrap_or_default() {
+        let Some(file) = fs::read_to_string(&file)
+            .ok()
+            .and_then(|source| syn::parse_file(&source).ok())
+        else {
+            continue;
+        };
+        for (name, value) in consts::Consts::new(&file, &Context::default()).values() {
+            values
+                .entry(name.to_string())
+                .and_modify(|known| {
+                    if *known != Some(value) {
+                        *known = None;
+                    }
+                })
+                .or_insert(Some(value));
+        }
+    }
+    values
+        .into_iter()
+        .filter_map(|(name, value)| Some((name, value?)))
+        .collect()
+}
+
+/// A [`Diagnostic`] with its rule and location.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    pub rule: &'static str,
+    pub level: Level,
+    pub path: PathBuf,
+    pub line: usize,
+    /// 1-based, in characters.
+    pub column: usize,
+    pub message: String,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("{}: {source}", .path.display())]
+    Io { path: PathBuf, source: io::Error },
+    #[error("{}:{}:{}: {message}", .path.display(), .line, .column)]
+    Parse {
+        path: PathBuf,
+        line: usize,
+        column: usize,
+        message: String,
+    },
+}
+
+/// Runs `rules` over `source`, reporting findings against `path`, with the
+/// default [`Context`]. `path` is only used in the findings.
+pub fn lint_source(source: &str, path: &Path, rules: &[&dyn Rule]) -> Result<Vec<Finding>, Error> {
+    lint_source_with(source, path, &Context::default(), rules)
+}
+
+/// Runs `rules` over `source` with the build settings in `cx`, reporting
+/// findings against `path`.
+pub fn lint_source_with(
+    source: &str,
+    path: &Path,
+    cx: &Context,
+    rules: &[&dyn Rule],
+) -> Result<Vec<Finding>, Error> {
+    let file = syn::parse_file(source).map_err(|err| {
+        let start = err.span().start();
+        Error::Parse {
+            path: path.to_path_buf(),
+            line: start.line,
+            column: start.column + 1,
+            message: err.to_string(),
+        }
+    })?;
+
+    let mut findings = Vec::new();
+    for rule in rules {
+        let level = rule.level(cx);
+        for diagnostic in rule.check(&file, cx) {
+            let start = diagnostic.span.start();
+            findings.push(Finding {
+                rule: rule.name(),
+                level,
+                path: path.to_path_buf(),
+                line: start.line,
+                column: start.column + 1,
+                message: diagnostic.message,
+            });
+        }
+    }
+    findings.sort_by(|a, b| (a.line, a.column, a.rule).cmp(&(b.line, b.column, b.rule)));
+    Ok(findings)
+}
+
+/// Runs `rules` over the `.rs` file at `path`, or every one under it if it is a
+/// directory, each with the [`Context`] of the workspace it is in. `target/`
+/// directories are skipped.
+pub fn lint_path(path: &Path, rules: &[&dyn Rule]) -> Result<Vec<Finding>, Error> {
+    let mut findings = Vec::new();
+    let mut contexts = HashMap::new();
+    for file in rust_files(path)? {
+        let source = fs::read_to_string(&file).map_err(|source| Error::Io {
+            path: file.clone(),
+            source,
+        })?;
+        let dir = file.parent().unwrap_or(Path::new("")).to_path_buf();
+        let cx = contexts
+            .entry(dir)
+            .or_insert_with(|| Context::for_path(&file));
+        findings.extend(lint_source_with(&source, &file, cx, rules)?);
+    }
+    Ok(findings)
+}
+
+fn rust_files(path: &Path) -> Result<Vec<PathBuf>, Error> {
+    if !path.is_dir() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+    let io_error = |source| Error::Io {
+        path: path.to_path_buf(),
+        source,
+    };
+    let mut entries: Vec<_> = fs::read_dir(path)
+        .map_err(io_error)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<_>>()
+        .map_err(io_error)?;
+    entries.sort();
+
+    let mut files = Vec::new();
+    for entry in entries {
+        if entry.is_dir() {
+            if entry.file_name().is_some_and(|name| name != "target") {
+                files.extend(rust_files(&entry)?);
+            }
+        } else if entry.extension().is_some_and(|ext| ext == "rs") {
+            files.push(entry);
+        }
+    }
+    Ok(files)
+}
@@ -0,0 +1,80 @@
+use std::{path::PathBuf, process::ExitCode};
+
+use clap::Parser;
+use sealevel_lint::{lint_path, rules, Level, Rule};
+
+/// Flags Solana program patterns behind known vulnerability classes.
+#[derive(Parser)]
+#[command(name = "sealevel-lint", version)]
+struct Cli {
+    /// Files or directories to lint
+    #[arg(default_value = ".")]
+    paths: Vec<PathBuf>,
+    /// Only run these rules, or rule groups
+    #[arg(long = "rule", value_name = "RULE")]
+    rules: Vec<String>,
+    /// List the rules and exit
+    #[arg(long)]
+    list: bool,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    if cli.list {
+        for rule in rules::RULES {
+            let group = rule.group().unwrap_or("");
+            println!("{:<24}{group:<10}{}", rule.name(), rule.description());
+        }
+        return ExitCode::SUCCESS;
+    }
+
+    let mut selected: Vec<&dyn Rule> = Vec::new();
+    if cli.rules.is_empty() {
+        selected.extend(rules::RULES);
+    }
+    for name in &cli.rules {
+        match rules::select(name).as_slice() {
+            [] => {
+                eprintln!("error: no rule or group called `{name}`, see --list");
+                return ExitCode::from(2);
+            }
+            rules => selected.extend(rules),
+        }
+    }
+
+    let (mut warnings, mut infos) = (0, 0);
+    for path in &cli.paths {
+        match lint_path(path, &selected) {
+            Ok(findings) => {
+                for finding in &findings {
+                    println!(
+                        "{}:{}:{}: {}: {}: {}",
+                        finding.path.display(),
+                        finding.line,
+                        finding.column,
+                        finding.level,
+                        finding.rule,
+                        finding.message
+                    );
+                    match finding.level {
+                        Level::Warning => warnings += 1,
+                        Level::Info => infos += 1,
+                    }
+                }
+            }
+            Err(err) => {
+                eprintln!("error: {err}");
+                return ExitCode::from(2);
+            }
+        }
+    }
+    if warnings + infos > 0 {
+        eprintln!("{warnings} warning(s), {infos} info");
+    }
+    // Info findings are already mitigated, they do not fail the run
+    if warnings == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
@@ -0,0 +1,50 @@
+//! `ata-init`: an associated token account created with `init`.
+//!
+//! An ATA's address only depends on its wallet and mint, and the Associated
+//! Token program creates it for anyone who pays. Once someone has, every
+//! `init` of it fails, so the instruction can be blocked for good by creating
+//! the account first.
+
+use crate::{anchor, Context, Diagnostic, Rule};
+
+pub struct AtaInit;
+
+impl Rule for AtaInit {
+    fn name(&self) -> &'static str {
+        "ata-init"
+    }
+
+    fn description(&self) -> &'static str {
+        "associated token account created with init, which anyone can block"
+    }
+
+    fn check(&self, file: &syn::File, _cx: &Context) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        for accounts in anchor::accounts_structs(file) {
+            for field in &accounts.fields {
+                let Some(init) = field.constraint("init") else {
+                    continue;
+                };
+                let associated = field
+                    .constraints
+                    .iter()
+                    .any(|constraint| constraint.name.starts_with("associated_token::"));
+                if !associated {
+                    continue;
+                }
+                diagnostics.push(Diagnostic {
+                    span: init.span,
+                    message: format!(
+                        "`{}` in `{}` is an associated token account created with `init`; \
+                         anyone can create it first and make this instruction fail for good. \
+                         Use `init_if_needed` (anchor-lang feature `init-if-needed`), and since \
+                         the account may then already exist, do not rely on it being fresh: \
+                         check its state instead of assuming it was just initialized",
+                        field.name, accounts.name
+                    ),
+                });
+            }
+        }
+        diagnostics
+    }
+}
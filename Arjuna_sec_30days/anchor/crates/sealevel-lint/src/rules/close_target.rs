@@ -0,0 +1,80 @@
+//! `close-target`: a `close = x` constraint whose target may be the closed
+//! account itself.
+//!
+//! Anchor's close moves the lamports to the target, then zeroes the closed
+//! account's. If both are the same account the lamports are moved onto the
+//! account and then wiped, which the runtime rejects, so the account can
+//! never be closed; a target of another type could still be the same account
+//! when nothing requires it to differ.
+
+use crate::{anchor, consts::Consts, scope, Context, Diagnostic, Rule};
+
+pub struct CloseTarget;
+
+impl Rule for CloseTarget {
+    fn name(&self) -> &'static str {
+        "close-target"
+    }
+
+    fn description(&self) -> &'static str {
+        "close = target that may be the account being closed"
+    }
+
+    fn check(&self, file: &syn::File, cx: &Context) -> Vec<Diagnostic> {
+        let functions = scope::functions(file);
+        let consts = Consts::new(file, cx);
+        let mut diagnostics = Vec::new();
+        for accounts in anchor::accounts_structs(file) {
+            let handlers: Vec<_> = functions
+                .iter()
+                .filter(|function| {
+                    function.program && function.accounts_struct().as_ref() == Some(&accounts.name)
+                })
+                .collect();
+            for closed in &accounts.fields {
+                let Some(close) = closed.constraint("close") else {
+                    continue;
+                };
+                // `close = receiver`, or `close = ctx.accounts.receiver`
+                let Some(target_name) = syn::parse2::<syn::Expr>(close.value.clone())
+                    .ok()
+                    .and_then(|target| scope::account(&target))
+                    .map(|target| target.name().to_string())
+                else {
+                    continue;
+                };
+                let message = if target_name == closed.name {
+                    format!(
+                        "`{}` in `{}` is closed into itself; Anchor wipes the lamports it \
+                         just moved and the close fails. Close it into another account",
+                        closed.name, accounts.name
+                    )
+                } else {
+                    let Some(target) = accounts.fields.iter().find(|f| f.name == target_name)
+                    else {
+                        continue;
+                    };
+                    // Anchor checks both types' owners, so only the same
+                    // type or an unchecked account can be the same account
+                    let may_alias = target.ty == closed.ty || target.is_raw();
+                    if !may_alias
+                        || anchor::kept_apart(&consts, &accounts, &handlers, closed, target)
+                    {
+                        continue;
+                    }
+                    format!(
+                        "`{}` in `{}` is closed into `{target_name}`, which may be the same \
+                         account; then the close fails and the account stays open. Add \
+                         `constraint = {}.key() != {target_name}.key()`",
+                        closed.name, accounts.name, closed.name
+                    )
+                };
+                diagnostics.push(Diagnostic {
+                    span: close.span,
+                    message,
+                });
+            }
+        }
+        diagnostics
+    }
+}
@@ -0,0 +1,332 @@
+//! `cpi-target`: an instruction sent to whatever program the caller passed.
+//!
+//! The program id of a CPI has to be pinned: an account typed as
+//! `Program<'info, T>`, an `address` constraint, or a comparison against a
+//! known id before the instruction is built. An `AccountInfo` or
+//! `UncheckedAccount` used as-is lets the caller substitute their own program,
+//! which then runs with every signature the CPI carries.
+//!
+//! Builders are matched on the path they were imported from, so a bare
+//! `transfer(..)` after `use spl_token::instruction::transfer` counts. A key
+//! check only counts when it comes before the instruction in the source and
+//! leaves the function when the key is wrong: an `if` comparing the key whose
+//! branch for a mismatch [diverges](guard::diverges), a `require_keys_eq!` or
+//! `require!`-style macro, or `check_program_account(..)?`. Accounts are
+//! followed through locals, including ones taken out of the accounts slice.
+
+use std::collections::{HashMap, HashSet};
+
+use proc_macro2::Span;
+use syn::{
+    punctuated::Punctuated,
+    spanned::Spanned,
+    visit::{self, Visit},
+    BinOp, Expr, Token, UnOp,
+};
+
+use crate::{
+    anchor::{self, AccountsStruct},
+    guard,
+    imports::Imports,
+    scope::{self, FnScope},
+    Context, Diagnostic, Rule,
+};
+
+pub struct CpiTarget;
+
+impl Rule for CpiTarget {
+    fn name(&self) -> &'static str {
+        "cpi-target"
+    }
+
+    fn description(&self) -> &'static str {
+        "CPI whose program id is an account never checked against a known program"
+    }
+
+    fn check(&self, file: &syn::File, _cx: &Context) -> Vec<Diagnostic> {
+        let structs = anchor::accounts_structs(file);
+        let imports = Imports::new(file);
+        let mut diagnostics = Vec::new();
+        for function in scope::functions(file) {
+            let mut targets = Targets {
+                imports: &imports,
+                found: Vec::new(),
+            };
+            targets.visit_block(function.block);
+            let mut checks = Checks {
+                function: &function,
+                found: HashMap::new(),
+            };
+            checks.visit_block(function.block);
+
+            for target in targets.found {
+                let Some(account) = program_account(&function, target) else {
+                    continue;
+                };
+                if checks.before(&account, target) {
+                    continue;
+                }
+                if let Some(kind) = unchecked(&structs, &function, &account) {
+                    diagnostics.push(Diagnostic {
+                        span: target.span(),
+                        message: format!(
+                            "CPI program id comes from `{account}`, {kind}, and its key is never \
+                             checked against a known program id; declare it as \
+                             `Program<'info, T>` or check its key first"
+                        ),
+                    });
+                }
+            }
+        }
+        diagnostics
+    }
+}
+
+/// The program id expression of every instruction built in one function: the
+/// `program_id` of an `Instruction { .. }`, the first argument of
+/// `Instruction::new_with_*` and of the `spl_token::instruction` builders.
+struct Targets<'ast, 'i> {
+    imports: &'i Imports,
+    found: Vec<&'ast Expr>,
+}
+
+impl<'ast> Visit<'ast> for Targets<'ast, '_> {
+    fn visit_expr(&mut self, expr: &'ast Expr) {
+        match expr {
+            Expr::Struct(literal)
+                if literal
+                    .path
+                    .segments
+                    .last()
+                    .is_some_and(|s| s.ident == "Instruction") =>
+            {
+                let program_id = literal.fields.iter().find(|field| {
+                    matches!(&field.member, syn::Member::Named(name) if name == "program_id")
+                });
+                self.found.extend(program_id.map(|field| &field.expr));
+            }
+            Expr::Call(call) => {
+                if let Expr::Path(path) = &*call.func {
+                    let segments = self.imports.resolve(&path.path);
+                    let builder = match segments.as_slice() {
+                        [.., ty, function] if ty == "Instruction" => {
+                            function.starts_with("new_with_")
+                        }
+                        [.., module, _] if module == "instruction" => {
+                            segments.iter().any(|segment| {
+                                matches!(segment.as_str(), "spl_token" | "spl_token_2022")
+                            })
+                        }
+                        _ => false,
+                    };
+                    if builder {
+                        self.found.extend(call.args.first());
+                    }
+                }
+            }
+            _ => {}
+        }
+        visit::visit_expr(self, expr);
+    }
+
+    fn visit_item_fn(&mut self, _: &'ast syn::ItemFn) {}
+}
+
+/// Accounts whose key is checked against something that is not another
+/// account's key, by a check that leaves the function when it fails. Each is
+/// kept with the source position of its first check.
+struct Checks<'a, 'ast> {
+    function: &'a FnScope<'ast>,
+    found: HashMap<String, (usize, usize)>,
+}
+
+impl Checks<'_, '_> {
+    fn insert(&mut self, accounts: Vec<String>, at: Span) {
+        let at = position(at);
+        for account in accounts {
+            self.found
+                .entry(account)
+                .and_modify(|first| *first = (*first).min(at))
+                .or_insert(at);
+        }
+    }
+
+    /// The account whose key `left == right` compares to a known id.
+    fn compares(&self, left: &Expr, right: &Expr) -> Vec<String> {
+        match (key_of(self.function, left), key_of(self.function, right)) {
+            (Some(account), None) | (None, Some(account)) => vec![account],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Accounts whose key `cond` evaluating to `value` proves is a known id.
+    fn proves(&self, cond: &Expr, value: bool) -> Vec<String> {
+        match scope::strip(cond) {
+            Expr::Unary(unary) if matches!(unary.op, UnOp::Not(_)) => {
+                self.proves(&unary.expr, !value)
+            }
+            Expr::Binary(binary) => match (&binary.op, value) {
+                (BinOp::Eq(_), true) | (BinOp::Ne(_), false) => {
+                    self.compares(&binary.left, &binary.right)
+                }
+                (BinOp::And(_), true) | (BinOp::Or(_), false) => {
+                    let mut accounts = self.proves(&binary.left, value);
+                    accounts.extend(self.proves(&binary.right, value));
+                    accounts
+                }
+                _ => Vec::new(),
+            },
+            // `spl_token::check_id(token_program.key)`
+            Expr::Call(call)
+                if value && scope::called_fn(scope::strip(cond)).as_deref() == Some("check_id") =>
+            {
+                call.args
+                    .iter()
+                    .filter_map(|arg| key_of(self.function, arg))
+                    .collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Whether `account` is checked somewhere before `target`.
+    fn before(&self, account: &str, target: &Expr) -> bool {
+        self.found
+            .get(account)
+            .is_some_and(|&at| at < position(target.span()))
+    }
+}
+
+fn position(span: Span) -> (usize, usize) {
+    let start = span.start();
+    (start.line, start.column)
+}
+
+impl<'ast> Visit<'ast> for Checks<'_, '_> {
+    fn visit_expr_if(&mut self, branch: &'ast syn::ExprIf) {
+        let otherwise_diverges = matches!(
+            branch.else_branch.as_ref().map(|(_, otherwise)| &**otherwise),
+            Some(Expr::Block(otherwise)) if guard::diverges(&otherwise.block)
+        );
+        let mut accounts = Vec::new();
+        if guard::diverges(&branch.then_branch) {
+            accounts.extend(self.proves(&branch.cond, false));
+        }
+        if otherwise_diverges {
+            accounts.extend(self.proves(&branch.cond, true));
+        }
+        self.insert(accounts, branch.span());
+        visit::visit_expr_if(self, branch);
+    }
+
+    fn visit_macro(&mut self, mac: &'ast syn::Macro) {
+        let Ok(args) = mac.parse_body_with(Punctuated::<Expr, Token![,]>::parse_terminated) else {
+            return;
+        };
+        let name = mac
+            .path
+            .segments
+            .last()
+            .map(|s| s.ident.to_string())
+            .unwrap_or_default();
+        // `require_keys_eq!(a, b)`, `require!(a == b)`
+        let accounts = if name.ends_with("_eq") && args.len() >= 2 {
+            self.compares(&args[0], &args[1])
+        } else if name.starts_with("require") || name.starts_with("assert") {
+            args.first()
+                .map(|cond| self.proves(cond, true))
+                .unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+        self.insert(accounts, mac.span());
+        args.iter().for_each(|arg| self.visit_expr(arg));
+    }
+
+    // `spl_token::check_program_account(token_program.key)?`
+    fn visit_expr_try(&mut self, expr: &'ast syn::ExprTry) {
+        if let Expr::Call(call) = &*expr.expr {
+            if scope::called_fn(&expr.expr).as_deref() == Some("check_program_account") {
+                let accounts = call
+                    .args
+                    .iter()
+                    .filter_map(|arg| key_of(self.function, arg))
+                    .collect();
+                self.insert(accounts, expr.span());
+            }
+        }
+        visit::visit_expr_try(self, expr);
+    }
+
+    fn visit_item_fn(&mut self, _: &'ast syn::ItemFn) {}
+}
+
+/// The account whose key `expr` reads, locals followed back to the account
+/// they were made from.
+fn key_of(function: &FnScope, expr: &Expr) -> Option<String> {
+    match scope::strip(expr) {
+        Expr::Field(field) if matches!(&field.member, syn::Member::Named(name) if name == "key") => {
+            function.account(&field.base)
+        }
+        Expr::MethodCall(call) if call.method == "key" && call.args.is_empty() => {
+            function.account(&call.receiver)
+        }
+        _ => None,
+    }
+}
+
+/// The account a program id expression reads the key of, following locals
+/// such as `let program = *token_program.key`.
+fn program_account(function: &FnScope, expr: &Expr) -> Option<String> {
+    let mut seen = HashSet::new();
+    let mut pending = vec![expr];
+    while let Some(expr) = pending.pop() {
+        if let Some(account) = key_of(function, expr) {
+            return Some(account);
+        }
+        if let Some(name) = scope::ident(scope::strip(expr)) {
+            if seen.insert(name.clone()) {
+                pending.extend(function.defs.get(&name).into_iter().flatten());
+            }
+        }
+    }
+    None
+}
+
+/// How `account` is declared, if that declaration checks nothing about it:
+/// a raw `AccountInfo` or an `UncheckedAccount` without an `address` constraint.
+fn unchecked(structs: &[AccountsStruct], function: &FnScope, account: &str) -> Option<String> {
+    if let Some(ty) = function.param(account) {
+        let kind = scope::last_segment(ty)?;
+        return matches!(kind.as_str(), "AccountInfo" | "UncheckedAccount")
+            .then(|| format!("an `{kind}`"));
+    }
+    // A local `FnScope::account` stopped at: one from `next_account_info` or
+    // out of the accounts slice
+    if let Some(defs) = function.defs.get(account) {
+        let native = defs.iter().any(|def| {
+            scope::called_fn(def).as_deref() == Some("next_account_info")
+                || from_account_slice(function, def)
+        });
+        return native.then(|| "an `AccountInfo`".to_string());
+    }
+
+    let fields = anchor::fields(structs, function, account);
+    let unchecked = !fields.is_empty()
+        && fields
+            .iter()
+            .all(|field| field.is_raw() && field.constraint("address").is_none());
+    unchecked.then(|| format!("an `{}` without an `address` constraint", fields[0].kind()))
+}
+
+/// Whether `def` takes an account out of an `&[AccountInfo]` parameter:
+/// `&accounts[2]`, or `accounts` itself destructured by `let [a, ..] = accounts`.
+fn from_account_slice(function: &FnScope, def: &Expr) -> bool {
+    let slice = match scope::strip(def) {
+        Expr::Index(index) => &*index.expr,
+        expr => expr,
+    };
+    scope::ident(scope::strip(slice))
+        .and_then(|name| function.param(&name))
+        .is_some_and(scope::is_account_slice)
+}
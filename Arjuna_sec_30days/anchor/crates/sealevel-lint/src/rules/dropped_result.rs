@@ -0,0 +1,230 @@
+//! `dropped-result`: a `Result` computed as a statement and thrown away.
+//!
+//! On-chain, an error only counts if it is returned from the instruction. A
+//! dropped `Err(..)` or an `invoke(..)` without `?` lets the instruction
+//! succeed after the check or the CPI failed. The rule has no type
+//! information, so it knows a call returns a `Result` by its name: the CPI
+//! functions, the Anchor CPI helpers listed in [`RESULT_HELPERS`], `try_*`
+//! methods, and the functions in the same file declared to return `Result` or
+//! `ProgramResult`. Paths are resolved through the file's `use` items first.
+//!
+//! A result is dropped when it is a statement of its own, bound to `_`, or
+//! thrown away with `.ok()` or `drop(..)`.
+
+use std::collections::HashSet;
+
+use syn::{
+    spanned::Spanned,
+    visit::{self, Visit},
+    Block, Expr, Pat, ReturnType, Signature, Stmt,
+};
+
+use crate::{imports::Imports, scope, Context, Diagnostic, Rule};
+
+/// Functions that return a `Result` wherever they come from.
+const RESULT_FNS: &[&str] = &[
+    "invoke",
+    "invoke_signed",
+    "invoke_unchecked",
+    "invoke_signed_unchecked",
+];
+
+/// Anchor's CPI helpers, which return a `Result`, by the module they live in.
+/// A call matches on its last two path segments, so `token::transfer(..)` and
+/// `transfer(..)` after `use anchor_spl::token::transfer` both do.
+const RESULT_HELPERS: &[(&str, &[&str])] = &[
+    ("associated_token", &["create", "create_idempotent"]),
+    ("system_program", SYSTEM_HELPERS),
+    ("token", TOKEN_HELPERS),
+    ("token_2022", TOKEN_HELPERS),
+    ("token_interface", TOKEN_HELPERS),
+];
+
+const SYSTEM_HELPERS: &[&str] = &[
+    "advance_nonce_account",
+    "allocate",
+    "allocate_with_seed",
+    "assign",
+    "assign_with_seed",
+    "authorize_nonce_account",
+    "create_account",
+    "create_account_with_seed",
+    "create_nonce_account",
+    "transfer",
+    "transfer_with_seed",
+    "withdraw_nonce_account",
+];
+
+const TOKEN_HELPERS: &[&str] = &[
+    "approve",
+    "approve_checked",
+    "burn",
+    "burn_checked",
+    "close_account",
+    "freeze_account",
+    "initialize_account",
+    "initialize_account3",
+    "initialize_mint",
+    "initialize_mint2",
+    "mint_to",
+    "mint_to_checked",
+    "revoke",
+    "set_authority",
+    "sync_native",
+    "thaw_account",
+    "transfer",
+    "transfer_checked",
+];
+
+/// Methods that return a `Result`, besides any `try_*`.
+const RESULT_METHODS: &[&str] = &["close", "exit", "realloc", "reload", "serialize"];
+
+pub struct DroppedResult;
+
+impl Rule for DroppedResult {
+    fn name(&self) -> &'static str {
+        "dropped-result"
+    }
+
+    fn description(&self) -> &'static str {
+        "Result value of a statement dropped, including Err(..) followed by Ok(())"
+    }
+
+    fn check(&self, file: &syn::File, _cx: &Context) -> Vec<Diagnostic> {
+        let mut signatures = Signatures(HashSet::new());
+        signatures.visit_file(file);
+        let imports = Imports::new(file);
+        let mut blocks = Blocks {
+            imports: &imports,
+            result_fns: signatures.0,
+            diagnostics: Vec::new(),
+        };
+        blocks.visit_file(file);
+        blocks.diagnostics
+    }
+}
+
+/// Names of the functions in the file that return a `Result`.
+struct Signatures(HashSet<String>);
+
+impl<'ast> Visit<'ast> for Signatures {
+    fn visit_signature(&mut self, sig: &'ast Signature) {
+        if let ReturnType::Type(_, ty) = &sig.output {
+            if matches!(
+                scope::last_segment(ty).as_deref(),
+                Some("Result" | "ProgramResult")
+            ) {
+                self.0.insert(sig.ident.to_string());
+            }
+        }
+    }
+}
+
+struct Blocks<'i> {
+    imports: &'i Imports,
+    result_fns: HashSet<String>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Blocks<'_> {
+    /// What `expr` is, if it evaluates to a `Result`: `Err(..)`, `Ok(..)`, or
+    /// the name of the function called.
+    fn result(&self, expr: &Expr) -> Option<String> {
+        match expr {
+            Expr::Call(call) => {
+                let Expr::Path(path) = &*call.func else {
+                    return None;
+                };
+                let segments = self.imports.resolve(&path.path);
+                let (name, modules) = segments.split_last()?;
+                let helper = modules.last().is_some_and(|module| {
+                    RESULT_HELPERS
+                        .iter()
+                        .any(|(helpers, fns)| module == helpers && fns.contains(&name.as_str()))
+                });
+                let known = matches!(name.as_str(), "Ok" | "Err")
+                    || RESULT_FNS.contains(&name.as_str())
+                    || self.result_fns.contains(name)
+                    || helper;
+                known.then(|| name.clone())
+            }
+            Expr::MethodCall(call) => {
+                let name = call.method.to_string();
+                let known = name.starts_with("try_")
+                    || RESULT_METHODS.contains(&name.as_str())
+                    || self.result_fns.contains(&name);
+                known.then_some(name)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for Blocks<'_> {
+    fn visit_block(&mut self, block: &'ast Block) {
+        for (index, stmt) in block.stmts.iter().enumerate() {
+            let Some(expr) = dropped(stmt) else {
+                continue;
+            };
+            let Some(name) = self.result(expr) else {
+                continue;
+            };
+            let message = match name.as_str() {
+                "Err" if block.stmts.get(index + 1).is_some_and(returns_ok) => {
+                    "`Err(..)` is dropped and `Ok(())` returned right after it, so the \
+                     failure becomes success; return the error instead"
+                        .to_string()
+                }
+                "Err" | "Ok" => format!("`{name}(..)` is built and dropped; return it"),
+                _ => format!(
+                    "result of `{name}` is dropped, so a failure goes unnoticed; propagate \
+                     it with `?`"
+                ),
+            };
+            self.diagnostics.push(Diagnostic {
+                span: expr.span(),
+                message,
+            });
+        }
+        visit::visit_block(self, block);
+    }
+}
+
+/// The value `stmt` throws away: the whole expression of `expr;`, the
+/// initializer of `let _ = expr;`, or what `.ok()` or `drop(..)` is given.
+fn dropped(stmt: &Stmt) -> Option<&Expr> {
+    let expr = match stmt {
+        Stmt::Expr(expr, Some(_)) => expr,
+        Stmt::Local(local) if matches!(local.pat, Pat::Wild(_)) => {
+            let init = local.init.as_ref()?;
+            return init.diverge.is_none().then_some(&*init.expr);
+        }
+        _ => return None,
+    };
+    Some(match expr {
+        Expr::MethodCall(call) if call.method == "ok" && call.args.is_empty() => &call.receiver,
+        Expr::Call(call)
+            if call.args.len() == 1
+                && matches!(&*call.func, Expr::Path(path) if path.path.is_ident("drop")) =>
+        {
+            &call.args[0]
+        }
+        expr => expr,
+    })
+}
+
+/// Whether `stmt` returns `Ok(..)`, as the tail expression or with `return`.
+fn returns_ok(stmt: &Stmt) -> bool {
+    let expr = match stmt {
+        Stmt::Expr(Expr::Return(ret), _) => match &ret.expr {
+            Some(expr) => &**expr,
+            None => return false,
+        },
+        Stmt::Expr(expr, None) => expr,
+        _ => return false,
+    };
+    matches!(expr, Expr::Call(call) if matches!(
+        &*call.func,
+        Expr::Path(path) if path.path.is_ident("Ok")
+    ))
+}
@@ -0,0 +1,107 @@
+//! `duplicate-mutable`: two mutable accounts of the same type that may be the
+//! same account.
+//!
+//! Anchor deserializes each field on its own, so passing one account for both
+//! gives the handler two copies of it; the last one written back wins, and
+//! logic such as "credit one, debit the other" happens twice to the same
+//! balance. Fields are told apart by a `constraint = a.key() != b.key()`,
+//! `seeds` that differ in a literal or constant component, or a key
+//! comparison in every handler of the `#[program]` module using the struct.
+//! Helpers that are handed the `Context` are not handlers.
+
+use syn::Type;
+
+use crate::{anchor, consts::Consts, scope, Context, Diagnostic, Rule};
+
+/// Account types that are never the same account twice by accident.
+const SINGLETONS: &[&str] = &["Program", "Interface", "Sysvar"];
+
+pub struct DuplicateMutable;
+
+impl Rule for DuplicateMutable {
+    fn name(&self) -> &'static str {
+        "duplicate-mutable"
+    }
+
+    fn description(&self) -> &'static str {
+        "two mutable accounts of the same type with nothing requiring them to differ"
+    }
+
+    fn check(&self, file: &syn::File, cx: &Context) -> Vec<Diagnostic> {
+        let functions = scope::functions(file);
+        let consts = Consts::new(file, cx);
+        let mut diagnostics = Vec::new();
+        for accounts in anchor::accounts_structs(file) {
+            let handlers: Vec<_> = functions
+                .iter()
+                .filter(|function| {
+                    function.program && function.accounts_struct().as_ref() == Some(&accounts.name)
+                })
+                .collect();
+            let mutable: Vec<_> = accounts
+                .fields
+                .iter()
+                .filter(|field| field.constraint("mut").is_some())
+                .filter(|field| !SINGLETONS.contains(&field.kind().as_str()))
+                .collect();
+
+            for (index, a) in mutable.iter().enumerate() {
+                for b in &mutable[index + 1..] {
+                    if a.ty != b.ty || anchor::kept_apart(&consts, &accounts, &handlers, a, b) {
+                        continue;
+                    }
+                    let used_by = match handlers.as_slice() {
+                        [] => "no handler in this file uses it".to_string(),
+                        handlers => {
+                            let names: Vec<_> = handlers
+                                .iter()
+                                .map(|handler| format!("`{}`", handler.name))
+                                .collect();
+                            format!("used by {}", names.join(", "))
+                        }
+                    };
+                    diagnostics.push(Diagnostic {
+                        span: a.span,
+                        message: format!(
+                            "`{}` and `{}` in `{}` ({used_by}) are both mutable `{}` and \
+                             nothing requires them to differ; the same account can be passed \
+                             for both. Add `constraint = {}.key() != {}.key()`",
+                            a.name,
+                            b.name,
+                            accounts.name,
+                            describe(a.ty),
+                            a.name,
+                            b.name
+                        ),
+                    });
+                }
+            }
+        }
+        diagnostics
+    }
+}
+
+/// `Account<RewardAccount>` for `Account<'info, RewardAccount>`.
+fn describe(ty: &Type) -> String {
+    let kind = scope::last_segment(ty).unwrap_or_default();
+    let Type::Path(path) = ty else {
+        return kind;
+    };
+    let inner = path
+        .path
+        .segments
+        .last()
+        .and_then(|segment| match &segment.arguments {
+            syn::PathArguments::AngleBracketed(args) => {
+                args.args.iter().find_map(|arg| match arg {
+                    syn::GenericArgument::Type(ty) => scope::last_segment(ty),
+                    _ => None,
+                })
+            }
+            _ => None,
+        });
+    match inner {
+        Some(inner) => format!("{kind}<{inner}>"),
+        None => kind,
+    }
+}
@@ -0,0 +1,166 @@
+//! `missing-owner`: account data deserialized before the account's owner is
+//! checked.
+//!
+//! Anyone can create an account with the same layout as the program's state,
+//! so bytes that parse are not yet the program's. Only after `owner` is
+//! compared to the program id (or the owner the layout belongs to) does the
+//! parsed state mean anything.
+
+use std::collections::HashSet;
+
+use syn::{
+    spanned::Spanned,
+    visit::{self, Visit},
+    Expr,
+};
+
+use crate::{
+    guard::{self, native_account},
+    scope::{self, FnScope},
+    Context, Diagnostic, Rule,
+};
+
+/// Functions that turn account bytes into a typed value, besides the
+/// `unpack*` of `Pack` and hand-rolled state types.
+const DESERIALIZERS: &[&str] = &[
+    // Borsh
+    "try_from_slice",
+    "deserialize",
+    // Anchor's `AccountDeserialize`
+    "try_deserialize",
+    "try_deserialize_unchecked",
+    // bytemuck
+    "from_bytes",
+    "from_bytes_mut",
+    "try_from_bytes",
+    "try_from_bytes_mut",
+    "pod_from_bytes",
+    "cast_ref",
+    "cast_slice",
+    "try_cast_slice",
+];
+
+pub struct MissingOwner;
+
+impl Rule for MissingOwner {
+    fn name(&self) -> &'static str {
+        "missing-owner"
+    }
+
+    fn description(&self) -> &'static str {
+        "account data deserialized before its owner is compared to the program id"
+    }
+
+    fn check(&self, file: &syn::File, _cx: &Context) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        for function in scope::functions(file) {
+            let accounts = guard::native_accounts(&function);
+            if accounts.is_empty() {
+                continue;
+            }
+            let mut reported = HashSet::new();
+            guard::walk(&function, &accounts, "owner", |expr, owned| {
+                let Some(deserializer) = deserializer(expr) else {
+                    return;
+                };
+                let Expr::Call(call) = expr else {
+                    return;
+                };
+                let account = call
+                    .args
+                    .iter()
+                    .find_map(|arg| data_of(&function, &accounts, arg, &mut HashSet::new()));
+                let Some(account) = account else {
+                    return;
+                };
+                if !owned.contains(&account) && reported.insert(account.clone()) {
+                    diagnostics.push(Diagnostic {
+                        span: expr.span(),
+                        message: format!(
+                            "`{account}`'s data is deserialized with `{deserializer}` in `{}` \
+                             before its `owner` is checked; an account someone else owns \
+                             can carry the same bytes",
+                            function.name
+                        ),
+                    });
+                }
+            });
+        }
+        diagnostics
+    }
+}
+
+/// The path of the deserializing function `expr` calls, e.g.
+/// `ProgramState::unpack`.
+fn deserializer(expr: &Expr) -> Option<String> {
+    let Expr::Call(call) = expr else {
+        return None;
+    };
+    let Expr::Path(path) = &*call.func else {
+        return None;
+    };
+    let name = path.path.segments.last()?.ident.to_string();
+    if !name.starts_with("unpack") && !DESERIALIZERS.contains(&name.as_str()) {
+        return None;
+    }
+    Some(
+        path.path
+            .segments
+            .iter()
+            .map(|segment| segment.ident.to_string())
+            .collect::<Vec<_>>()
+            .join("::"),
+    )
+}
+
+/// The native account whose data `expr` is, directly (`&vault.data.borrow()`,
+/// `vault.try_borrow_data()?`) or through locals.
+fn data_of(
+    function: &FnScope,
+    accounts: &HashSet<String>,
+    expr: &Expr,
+    seen: &mut HashSet<String>,
+) -> Option<String> {
+    struct Data<'a>(&'a HashSet<String>, Option<String>);
+    impl<'ast> Visit<'ast> for Data<'_> {
+        fn visit_expr_field(&mut self, field: &'ast syn::ExprField) {
+            if matches!(&field.member, syn::Member::Named(name) if name == "data") {
+                self.1 = self
+                    .1
+                    .take()
+                    .or_else(|| native_account(self.0, &field.base));
+            }
+            visit::visit_expr_field(self, field);
+        }
+
+        fn visit_expr_method_call(&mut self, call: &'ast syn::ExprMethodCall) {
+            if matches!(
+                call.method.to_string().as_str(),
+                "try_borrow_data" | "try_borrow_mut_data"
+            ) {
+                self.1 = self
+                    .1
+                    .take()
+                    .or_else(|| native_account(self.0, &call.receiver));
+            }
+            visit::visit_expr_method_call(self, call);
+        }
+    }
+    let mut data = Data(accounts, None);
+    data.visit_expr(expr);
+    if data.1.is_some() {
+        return data.1;
+    }
+
+    for name in scope::idents(expr) {
+        if accounts.contains(&name) || !seen.insert(name.clone()) {
+            continue;
+        }
+        for def in function.defs.get(&name).into_iter().flatten() {
+            if let Some(account) = data_of(function, accounts, def, seen) {
+                return Some(account);
+            }
+        }
+    }
+    None
+}
@@ -0,0 +1,384 @@
+//! `missing-reload`: an account read after a CPI changed it, without a
+//! `reload()` in between.
+//!
+//! Anchor deserializes `Account<'info, T>` once, before the handler runs. A
+//! CPI that writes the account changes its data on chain but not the copy in
+//! `ctx.accounts`, so reading a field afterwards gives the value from before
+//! the CPI until `ctx.accounts.x.reload()?` is called.
+//!
+//! The walk follows every path through `if`, `match` and loops: a read is
+//! stale if a CPI comes before it on any path without a reload on that same
+//! path, so a reload in only one arm of an `if` does not cover what follows
+//! the `if`. A loop body is walked twice, so a CPI at the end of one iteration
+//! reaches the reads at the start of the next. Early returns are not
+//! followed, which errs on the side of reporting.
+
+use std::collections::{HashMap, HashSet};
+
+use proc_macro2::Span;
+use syn::{
+    punctuated::Punctuated,
+    spanned::Spanned,
+    visit::{self, Visit},
+    Expr, Token,
+};
+
+use crate::{
+    anchor::{self, AccountsStruct},
+    scope::{self, FnScope},
+    Context, Diagnostic, Rule,
+};
+
+/// Account types whose data Anchor deserializes up front.
+const DESERIALIZED: &[&str] = &["Account", "InterfaceAccount"];
+
+pub struct MissingReload;
+
+impl Rule for MissingReload {
+    fn name(&self) -> &'static str {
+        "missing-reload"
+    }
+
+    fn description(&self) -> &'static str {
+        "account field read after a CPI that was passed the account, with no reload()"
+    }
+
+    fn check(&self, file: &syn::File, _cx: &Context) -> Vec<Diagnostic> {
+        let structs = anchor::accounts_structs(file);
+        let functions = scope::functions(file);
+
+        // Methods such as `fn transfer_context(&self) -> CpiContext<..>`, with
+        // the fields they put in the context
+        let mut helpers = HashMap::new();
+        for function in &functions {
+            if function.self_ty.is_some() && mentions_cpi_context(function) {
+                helpers.insert(function.name.clone(), fields_in(function.block));
+            }
+        }
+
+        let mut diagnostics = Vec::new();
+        for function in functions.iter().filter(|function| !function.test) {
+            let mut walk = Walk {
+                structs: &structs,
+                function,
+                helpers: &helpers,
+                aliases: HashMap::new(),
+                carries: HashMap::new(),
+                contexts: HashSet::new(),
+                stale: HashMap::new(),
+                reported: HashSet::new(),
+                diagnostics: &mut diagnostics,
+            };
+            walk.visit_block(function.block);
+        }
+        diagnostics
+    }
+}
+
+/// Follows one function in source order.
+struct Walk<'a, 'ast> {
+    structs: &'a [AccountsStruct<'ast>],
+    function: &'a FnScope<'ast>,
+    helpers: &'a HashMap<String, HashSet<String>>,
+    /// Locals that are an account field, `vault` for `let vault = &mut ctx.accounts.vault`.
+    aliases: HashMap<String, String>,
+    /// Locals built from account fields, such as a CPI accounts struct.
+    carries: HashMap<String, HashSet<String>>,
+    /// Locals holding a `CpiContext`.
+    contexts: HashSet<String>,
+    /// Fields a CPI was passed, with the line of the CPI.
+    stale: HashMap<String, usize>,
+    /// Where stale reads were reported, so a loop walked twice reports each once.
+    reported: HashSet<(usize, usize)>,
+    diagnostics: &'a mut Vec<Diagnostic>,
+}
+
+impl Walk<'_, '_> {
+    /// The accounts struct field `expr` is: `ctx.accounts.x`, `self.x`, or an
+    /// alias of one.
+    fn field(&self, expr: &Expr) -> Option<String> {
+        match scope::strip(expr) {
+            Expr::Field(field) => {
+                let syn::Member::Named(name) = &field.member else {
+                    return None;
+                };
+                let on_accounts = match scope::strip(&field.base) {
+                    Expr::Field(base) => {
+                        matches!(&base.member, syn::Member::Named(base) if base == "accounts")
+                    }
+                    Expr::Path(path) => path.path.is_ident("self"),
+                    _ => false,
+                };
+                on_accounts.then(|| name.to_string())
+            }
+            expr => self.aliases.get(&scope::ident(expr)?).cloned(),
+        }
+    }
+
+    /// Whether `name` is an account Anchor deserialized for this function.
+    fn deserialized(&self, name: &str) -> bool {
+        anchor::fields(self.structs, self.function, name)
+            .iter()
+            .any(|field| DESERIALIZED.contains(&field.kind().as_str()))
+    }
+
+    /// The fields `expr` hands over, directly or through locals.
+    fn passed(&self, expr: &Expr) -> HashSet<String> {
+        let mut passed = HashSet::new();
+        let mut mentions = Mentions(Vec::new());
+        mentions.visit_expr(expr);
+        for expr in mentions.0 {
+            if let Some(field) = self.field(expr) {
+                passed.insert(field);
+            } else if let Some(carried) =
+                scope::ident(expr).and_then(|name| self.carries.get(&name))
+            {
+                passed.extend(carried.iter().cloned());
+            } else if let Some(helper) = self.helper(expr) {
+                passed.extend(helper.iter().cloned());
+            }
+        }
+        passed
+    }
+
+    /// The fields of a `ctx.accounts.transfer_context()` call.
+    fn helper(&self, expr: &Expr) -> Option<&HashSet<String>> {
+        let Expr::MethodCall(call) = scope::strip(expr) else {
+            return None;
+        };
+        self.helpers.get(&call.method.to_string())
+    }
+
+    /// Whether `expr` is a `CpiContext`.
+    fn is_context(&self, expr: &Expr) -> bool {
+        match scope::strip(expr) {
+            Expr::Call(call) => matches!(
+                &*call.func,
+                Expr::Path(path) if path.path.segments.iter().any(|s| s.ident == "CpiContext")
+            ),
+            Expr::MethodCall(call) if call.method.to_string().starts_with("with_") => {
+                self.is_context(&call.receiver)
+            }
+            expr @ Expr::MethodCall(_) => self.helper(expr).is_some(),
+            expr => scope::ident(expr).is_some_and(|name| self.contexts.contains(&name)),
+        }
+    }
+
+    /// Marks what a call passes as changed, if the call is a CPI.
+    fn call<'e>(
+        &mut self,
+        name: Option<String>,
+        span: Span,
+        args: impl Iterator<Item = &'e Expr> + Clone,
+    ) {
+        let invoke = matches!(
+            name.as_deref(),
+            Some("invoke" | "invoke_signed" | "invoke_unchecked" | "invoke_signed_unchecked")
+        );
+        if !invoke && !args.clone().any(|arg| self.is_context(arg)) {
+            return;
+        }
+        let line = span.start().line;
+        for arg in args {
+            for field in self.passed(arg) {
+                if self.deserialized(&field) {
+                    self.stale.insert(field, line);
+                }
+            }
+        }
+    }
+
+    fn read(&mut self, field: &str, span: Span) {
+        let Some(&line) = self.stale.get(field) else {
+            return;
+        };
+        let start = span.start();
+        if !self.reported.insert((start.line, start.column)) {
+            return;
+        }
+        self.diagnostics.push(Diagnostic {
+            span,
+            message: format!(
+                "`{field}` is read in `{}` after the CPI on line {line} was passed it, and \
+                 Anchor does not refresh it; this is the value from before the CPI. Call \
+                 `{field}.reload()?` first",
+                self.function.name
+            ),
+        });
+    }
+
+    /// Walks a loop: the body may run zero, one or more times, so it is walked
+    /// once more from where the first pass left off.
+    fn repeat(&mut self, mut walk: impl FnMut(&mut Self)) {
+        let before = self.stale.clone();
+        walk(self);
+        merge(&mut self.stale, before.clone());
+        walk(self);
+        merge(&mut self.stale, before);
+    }
+}
+
+/// Adds the stale fields of another path to `into`.
+fn merge(into: &mut HashMap<String, usize>, from: HashMap<String, usize>) {
+    for (field, line) in from {
+        into.entry(field).or_insert(line);
+    }
+}
+
+// Macro arguments are parsed while walking, so nothing visited is kept
+impl<'v> Visit<'v> for Walk<'_, '_> {
+    fn visit_local(&mut self, local: &'v syn::Local) {
+        let Some(init) = &local.init else {
+            return;
+        };
+        self.visit_expr(&init.expr);
+        for name in scope::bindings(&local.pat) {
+            if let Some(field) = self.field(&init.expr) {
+                self.aliases.insert(name.clone(), field);
+            }
+            if self.is_context(&init.expr) {
+                self.contexts.insert(name.clone());
+            }
+            let passed = self.passed(&init.expr);
+            if !passed.is_empty() {
+                self.carries.insert(name, passed);
+            }
+        }
+    }
+
+    fn visit_expr_call(&mut self, call: &'v syn::ExprCall) {
+        visit::visit_expr_call(self, call);
+        let name = match &*call.func {
+            Expr::Path(path) => path.path.segments.last().map(|s| s.ident.to_string()),
+            _ => None,
+        };
+        self.call(name, call.span(), call.args.iter());
+    }
+
+    fn visit_expr_method_call(&mut self, call: &'v syn::ExprMethodCall) {
+        if call.method == "reload" {
+            if let Some(field) = self.field(&call.receiver) {
+                self.stale.remove(&field);
+                return;
+            }
+        }
+        visit::visit_expr_method_call(self, call);
+        self.call(Some(call.method.to_string()), call.span(), call.args.iter());
+    }
+
+    fn visit_expr_if(&mut self, branch: &'v syn::ExprIf) {
+        self.visit_expr(&branch.cond);
+        let before = self.stale.clone();
+        self.visit_block(&branch.then_branch);
+        let then = std::mem::replace(&mut self.stale, before);
+        if let Some((_, otherwise)) = &branch.else_branch {
+            self.visit_expr(otherwise);
+        }
+        merge(&mut self.stale, then);
+    }
+
+    fn visit_expr_match(&mut self, expr: &'v syn::ExprMatch) {
+        self.visit_expr(&expr.expr);
+        let before = self.stale.clone();
+        let mut after = HashMap::new();
+        for arm in &expr.arms {
+            self.stale = before.clone();
+            if let Some((_, guard)) = &arm.guard {
+                self.visit_expr(guard);
+            }
+            self.visit_expr(&arm.body);
+            merge(&mut after, std::mem::take(&mut self.stale));
+        }
+        self.stale = if expr.arms.is_empty() { before } else { after };
+    }
+
+    fn visit_expr_for_loop(&mut self, expr: &'v syn::ExprForLoop) {
+        self.visit_expr(&expr.expr);
+        self.repeat(|walk| walk.visit_block(&expr.body));
+    }
+
+    fn visit_expr_while(&mut self, expr: &'v syn::ExprWhile) {
+        self.repeat(|walk| {
+            walk.visit_expr(&expr.cond);
+            walk.visit_block(&expr.body);
+        });
+    }
+
+    fn visit_expr_loop(&mut self, expr: &'v syn::ExprLoop) {
+        self.repeat(|walk| walk.visit_block(&expr.body));
+    }
+
+    fn visit_expr_field(&mut self, field: &'v syn::ExprField) {
+        match self.field(&field.base) {
+            Some(account) => self.read(&account, field.member.span()),
+            None => visit::visit_expr_field(self, field),
+        }
+    }
+
+    fn visit_expr_assign(&mut self, assign: &'v syn::ExprAssign) {
+        // Overwriting a field is not reading it
+        self.visit_expr(&assign.right);
+        if let Expr::Field(field) = &*assign.left {
+            if self.field(&field.base).is_some() {
+                return;
+            }
+        }
+        self.visit_expr(&assign.left);
+    }
+
+    fn visit_macro(&mut self, mac: &'v syn::Macro) {
+        if let Ok(args) = mac.parse_body_with(Punctuated::<Expr, Token![,]>::parse_terminated) {
+            args.iter().for_each(|arg| self.visit_expr(arg));
+        }
+    }
+
+    fn visit_item_fn(&mut self, _: &'v syn::ItemFn) {}
+}
+
+/// Every field access, path and method call in an expression, outermost
+/// first, without looking inside the ones found.
+struct Mentions<'ast>(Vec<&'ast Expr>);
+
+impl<'ast> Visit<'ast> for Mentions<'ast> {
+    fn visit_expr(&mut self, expr: &'ast Expr) {
+        match expr {
+            Expr::Field(_) | Expr::Path(_) => self.0.push(expr),
+            Expr::MethodCall(call) => {
+                self.0.push(expr);
+                visit::visit_expr_method_call(self, call);
+            }
+            expr => visit::visit_expr(self, expr),
+        }
+    }
+}
+
+fn mentions_cpi_context(function: &FnScope) -> bool {
+    struct Finder(bool);
+    impl<'ast> Visit<'ast> for Finder {
+        fn visit_path(&mut self, path: &'ast syn::Path) {
+            self.0 |= path.segments.iter().any(|s| s.ident == "CpiContext");
+            visit::visit_path(self, path);
+        }
+    }
+    let mut finder = Finder(false);
+    finder.visit_block(function.block);
+    finder.0
+}
+
+/// The `self.x` fields a method body mentions.
+fn fields_in(block: &syn::Block) -> HashSet<String> {
+    struct Fields(HashSet<String>);
+    impl<'ast> Visit<'ast> for Fields {
+        fn visit_expr_field(&mut self, field: &'ast syn::ExprField) {
+            if let (Expr::Path(base), syn::Member::Named(name)) = (&*field.base, &field.member) {
+                if base.path.is_ident("self") {
+                    self.0.insert(name.to_string());
+                }
+            }
+            visit::visit_expr_field(self, field);
+        }
+    }
+    let mut fields = Fields(HashSet::new());
+    fields.visit_block(block);
+    fields.0
+}
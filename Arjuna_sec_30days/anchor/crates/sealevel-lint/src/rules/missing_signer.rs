@@ -0,0 +1,157 @@
+//! `missing-signer`: a native processor that writes account data or lamports
+//! before checking that anyone signed.
+//!
+//! Accounts come from `next_account_info`, and nothing about them is checked
+//! for the program. If no account's `is_signer` is checked on the way to a
+//! write, anyone can pass the authority's pubkey and have the write go through.
+//!
+//! An account whose key is compared to a pubkey kept in state, such as
+//! `vault.admin != *authority.key`, is an authority. Each authority has to have
+//! its own `is_signer` checked before a write, so checking another account's
+//! signature does not cover it.
+
+use std::collections::HashSet;
+
+use syn::{
+    punctuated::Punctuated,
+    spanned::Spanned,
+    visit::{self, Visit},
+    BinOp, Expr, Token,
+};
+
+use crate::{
+    guard::{self, native_account},
+    scope::{self, AccountRef},
+    Context, Diagnostic, Rule,
+};
+
+pub struct MissingSigner;
+
+impl Rule for MissingSigner {
+    fn name(&self) -> &'static str {
+        "missing-signer"
+    }
+
+    fn description(&self) -> &'static str {
+        "native processor writes account data or lamports with no is_signer check before it"
+    }
+
+    fn check(&self, file: &syn::File, _cx: &Context) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        for function in scope::functions(file) {
+            let accounts = guard::native_accounts(&function);
+            if accounts.is_empty() {
+                continue;
+            }
+            let mut authorities = Authorities {
+                accounts: &accounts,
+                found: Vec::new(),
+            };
+            authorities.visit_block(function.block);
+
+            let mut reported = HashSet::new();
+            guard::walk(&function, &accounts, "is_signer", |expr, signed| {
+                let Some((account, what)) = write(&accounts, expr) else {
+                    return;
+                };
+                for authority in &authorities.found {
+                    if !signed.contains(authority) && reported.insert(authority.clone()) {
+                        diagnostics.push(Diagnostic {
+                            span: expr.span(),
+                            message: format!(
+                                "`{account}`'s {what} is written in `{}` before `{authority}`'s \
+                                 `is_signer` is checked; its key is compared as an authority, \
+                                 but anyone can pass that pubkey without its signature",
+                                function.name
+                            ),
+                        });
+                    }
+                }
+                if authorities.found.is_empty()
+                    && signed.is_empty()
+                    && reported.insert(account.clone())
+                {
+                    diagnostics.push(Diagnostic {
+                        span: expr.span(),
+                        message: format!(
+                            "`{account}`'s {what} is written in `{}` before any account's \
+                             `is_signer` is checked; anyone can send this instruction",
+                            function.name
+                        ),
+                    });
+                }
+            });
+        }
+        diagnostics
+    }
+}
+
+/// Native accounts whose key is compared to a named field, `==`/`!=` or a
+/// `*_eq!` macro, in order of first comparison.
+struct Authorities<'a> {
+    accounts: &'a HashSet<String>,
+    found: Vec<String>,
+}
+
+impl Authorities<'_> {
+    fn compare(&mut self, left: &Expr, right: &Expr) {
+        for (key, stored) in [(left, right), (right, left)] {
+            let Some(AccountRef::Local(name)) = scope::key_of(key) else {
+                continue;
+            };
+            let named = matches!(
+                scope::strip(stored),
+                Expr::Field(field) if matches!(field.member, syn::Member::Named(_))
+            );
+            let authority = named && scope::key_of(stored).is_none();
+            if authority && self.accounts.contains(&name) && !self.found.contains(&name) {
+                self.found.push(name);
+            }
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for Authorities<'_> {
+    fn visit_expr_binary(&mut self, binary: &'ast syn::ExprBinary) {
+        if matches!(binary.op, BinOp::Eq(_) | BinOp::Ne(_)) {
+            self.compare(&binary.left, &binary.right);
+        }
+        visit::visit_expr_binary(self, binary);
+    }
+
+    fn visit_macro(&mut self, mac: &'ast syn::Macro) {
+        let Ok(args) = mac.parse_body_with(Punctuated::<Expr, Token![,]>::parse_terminated) else {
+            return;
+        };
+        let name = mac.path.segments.last().map(|s| s.ident.to_string());
+        if name.is_some_and(|name| name.ends_with("_eq")) && args.len() >= 2 {
+            self.compare(&args[0], &args[1]);
+        }
+        args.iter().for_each(|arg| self.visit_expr(arg));
+    }
+
+    fn visit_item_fn(&mut self, _: &'ast syn::ItemFn) {}
+}
+
+/// The account and what of it `expr` borrows for writing.
+fn write(accounts: &HashSet<String>, expr: &Expr) -> Option<(String, &'static str)> {
+    let Expr::MethodCall(call) = expr else {
+        return None;
+    };
+    match call.method.to_string().as_str() {
+        "try_borrow_mut_data" => Some((native_account(accounts, &call.receiver)?, "data")),
+        "try_borrow_mut_lamports" => Some((native_account(accounts, &call.receiver)?, "lamports")),
+        "borrow_mut" | "try_borrow_mut" => {
+            let Expr::Field(field) = scope::strip(&call.receiver) else {
+                return None;
+            };
+            let what = match &field.member {
+                syn::Member::Named(name) if name == "data" => "data",
+                syn::Member::Named(name) if name == "lamports" => "lamports",
+                _ => return None,
+            };
+            Some((native_account(accounts, &field.base)?, what))
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,85 @@
+//! Every rule, one module each.
+//!
+//! To add a rule, implement [`Rule`] in a new module and add it to [`RULES`].
+
+use crate::{Context, Diagnostic, Level};
+
+mod ata_init;
+mod close_target;
+mod cpi_target;
+mod dropped_result;
+mod duplicate_mutable;
+mod missing_owner;
+mod missing_reload;
+mod missing_signer;
+mod panicking_unwrap;
+mod pubkey_new;
+mod sysvar_address;
+mod type_cosplay;
+mod unchecked_arithmetic;
+mod unchecked_slice;
+mod unsafe_close;
+mod unsafe_realloc;
+mod user_bump;
+
+pub trait Rule: Sync {
+    /// Kebab-case name, shown with every finding and used to pick rules.
+    fn name(&self) -> &'static str;
+
+    /// One line on what the rule flags.
+    fn description(&self) -> &'static str;
+
+    /// The group the rule can also be selected by, e.g. `panics`.
+    fn group(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// Findings in `file`, a file of the build `cx` describes.
+    fn check(&self, file: &syn::File, cx: &Context) -> Vec<Diagnostic>;
+
+    /// The level of this rule's findings in a workspace.
+    fn level(&self, _cx: &Context) -> Level {
+        Level::Warning
+    }
+}
+
+/// Group of the rules for code that aborts the program on malformed input,
+/// where a typed error would say what was wrong.
+pub const PANICS: &str = "panics";
+
+pub static RULES: &[&dyn Rule] = &[
+    &user_bump::UserBump,
+    &cpi_target::CpiTarget,
+    &missing_signer::MissingSigner,
+    &missing_owner::MissingOwner,
+    &dropped_result::DroppedResult,
+    &unchecked_slice::UncheckedSlice,
+    &unchecked_arithmetic::UncheckedArithmetic,
+    &ata_init::AtaInit,
+    &panicking_unwrap::PanickingUnwrap,
+    &pubkey_new::PubkeyNew,
+    &duplicate_mutable::DuplicateMutable,
+    &missing_reload::MissingReload,
+    &unsafe_close::UnsafeClose,
+    &close_target::CloseTarget,
+    &sysvar_address::SysvarAddress,
+    &type_cosplay::TypeCosplay,
+    &unsafe_realloc::UnsafeRealloc,
+];
+
+/// The rule called `name`.
+pub fn find(name: &str) -> Option<&'static dyn Rule> {
+    RULES.iter().copied().find(|rule| rule.name() == name)
+}
+
+/// The rule called `name`, or every rule in the group called `name`.
+pub fn select(name: &str) -> Vec<&'static dyn Rule> {
+    match find(name) {
+        Some(rule) => vec![rule],
+        None => RULES
+            .iter()
+            .copied()
+            .filter(|rule| rule.group() == Some(name))
+            .collect(),
+    }
+}
@@ -0,0 +1,124 @@
+//! `panicking-unwrap`: `.unwrap()` and `.expect(..)` in program code.
+//!
+//! On-chain a panic aborts the transaction with an opaque "program failed"
+//! and no error code, so a client cannot tell bad input from a bug. The usual
+//! offender is `data[8..].try_into().unwrap()` on instruction data.
+//!
+//! Only instruction handlers and the functions of the same file they call are
+//! checked. Converting a slice with constant bounds, `data[0..8]`, is left
+//! alone: its length is fixed, and whether the indexing itself is guarded is
+//! up to `unchecked-slice`.
+
+use syn::{
+    visit::{self, Visit},
+    Expr,
+};
+
+use crate::{consts::Consts, scope, Context, Diagnostic, Rule};
+
+pub struct PanickingUnwrap;
+
+impl Rule for PanickingUnwrap {
+    fn name(&self) -> &'static str {
+        "panicking-unwrap"
+    }
+
+    fn description(&self) -> &'static str {
+        "unwrap or expect in program code, where a typed error belongs"
+    }
+
+    fn group(&self) -> Option<&'static str> {
+        Some(super::PANICS)
+    }
+
+    fn check(&self, file: &syn::File, cx: &Context) -> Vec<Diagnostic> {
+        let functions = scope::functions(file);
+        let mut unwraps = Unwraps {
+            consts: Consts::new(file, cx),
+            diagnostics: Vec::new(),
+        };
+        for function in scope::reachable(&functions) {
+            unwraps.visit_block(function.block);
+        }
+        unwraps.diagnostics
+    }
+}
+
+struct Unwraps<'ast> {
+    consts: Consts<'ast>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Unwraps<'_> {
+    /// Whether `expr` is a slice of constant length, `&data[0..8]`.
+    fn fixed_len(&self, expr: &Expr) -> bool {
+        let Expr::Index(index) = scope::strip(expr) else {
+            return false;
+        };
+        let Expr::Range(range) = &*index.index else {
+            return false;
+        };
+        let start = range
+            .start
+            .as_ref()
+            .is_none_or(|start| self.consts.eval(start).is_some());
+        let end = range
+            .end
+            .as_ref()
+            .is_some_and(|end| self.consts.eval(end).is_some());
+        start && end
+    }
+}
+
+impl<'ast> Visit<'ast> for Unwraps<'_> {
+    fn visit_expr_method_call(&mut self, call: &'ast syn::ExprMethodCall) {
+        let method = call.method.to_string();
+        let shown = match method.as_str() {
+            "unwrap" => Some("unwrap()"),
+            "expect" => Some("expect(..)"),
+            _ => None,
+        };
+        if let Some(shown) = shown {
+            let (conversion, converted) = match &*call.receiver {
+                Expr::MethodCall(inner) if inner.method == "try_into" => {
+                    (Some("try_into()"), Some(&*inner.receiver))
+                }
+                Expr::Call(inner) => match &*inner.func {
+                    Expr::Path(path)
+                        if path
+                            .path
+                            .segments
+                            .last()
+                            .is_some_and(|s| s.ident == "try_from") =>
+                    {
+                        (Some("try_from(..)"), inner.args.first())
+                    }
+                    _ => (None, None),
+                },
+                _ => (None, None),
+            };
+            if converted.is_some_and(|converted| self.fixed_len(converted)) {
+                return visit::visit_expr_method_call(self, call);
+            }
+            let message = match conversion {
+                Some(conversion) => format!(
+                    "`{conversion}.{shown}` aborts the program on malformed input; map the \
+                     error instead, e.g. `{conversion}.map_err(|_| \
+                     ProgramError::InvalidInstructionData)?`"
+                ),
+                None => format!(
+                    "`.{shown}` aborts the program with no error code; return a typed error \
+                     with `ok_or(..)?` or `map_err(..)?`"
+                ),
+            };
+            self.diagnostics.push(Diagnostic {
+                span: call.method.span(),
+                message,
+            });
+        }
+        visit::visit_expr_method_call(self, call);
+    }
+
+    // Nested functions are visited as their own scope
+    fn visit_item_fn(&mut self, _: &'ast syn::ItemFn) {}
+}
@@ -0,0 +1,61 @@
+//! `pubkey-new`: the deprecated `Pubkey::new(&bytes)`.
+//!
+//! It panics unless the slice is exactly 32 bytes, which for bytes taken from
+//! instruction or account data is up to the caller. Only instruction handlers
+//! and the functions of the same file they call are checked.
+
+use syn::{
+    spanned::Spanned,
+    visit::{self, Visit},
+    Expr,
+};
+
+use crate::{scope, Context, Diagnostic, Rule};
+
+pub struct PubkeyNew;
+
+impl Rule for PubkeyNew {
+    fn name(&self) -> &'static str {
+        "pubkey-new"
+    }
+
+    fn description(&self) -> &'static str {
+        "deprecated Pubkey::new, which panics on a slice that is not 32 bytes"
+    }
+
+    fn group(&self) -> Option<&'static str> {
+        Some(super::PANICS)
+    }
+
+    fn check(&self, file: &syn::File, _cx: &Context) -> Vec<Diagnostic> {
+        let mut calls = Calls(Vec::new());
+        let functions = scope::functions(file);
+        for function in scope::reachable(&functions) {
+            calls.visit_block(function.block);
+        }
+        calls.0
+    }
+}
+
+struct Calls(Vec<Diagnostic>);
+
+impl<'ast> Visit<'ast> for Calls {
+    fn visit_expr_call(&mut self, call: &'ast syn::ExprCall) {
+        if let Expr::Path(path) = &*call.func {
+            let segments: Vec<_> = path.path.segments.iter().map(|s| &s.ident).collect();
+            if let [.., ty, function] = segments.as_slice() {
+                if *ty == "Pubkey" && *function == "new" {
+                    self.0.push(Diagnostic {
+                        span: call.span(),
+                        message: "`Pubkey::new` panics unless the slice is exactly 32 bytes; \
+                                  use `Pubkey::try_from(slice)` and map the error"
+                            .to_string(),
+                    });
+                }
+            }
+        }
+        visit::visit_expr_call(self, call);
+    }
+
+    fn visit_item_fn(&mut self, _: &'ast syn::ItemFn) {}
+}
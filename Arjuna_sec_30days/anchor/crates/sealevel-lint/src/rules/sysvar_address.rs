@@ -0,0 +1,349 @@
+//! `sysvar-address`: a sysvar read from an account whose key nothing compares
+//! with the sysvar's id.
+//!
+//! A sysvar is just an account the runtime writes, so an instruction can pass
+//! any account with the same layout in its place. `Sysvar<'info, T>`,
+//! `from_account_info` and the `_checked` instruction-sysvar loaders compare
+//! the key themselves; `load_instruction_at` and `load_current_index` take
+//! raw bytes and do not, and neither does an `AccountInfo` field.
+//!
+//! A key check counts when the code after it only runs for the right account:
+//! an `if` that [diverges](guard::diverges) when the key differs, a
+//! `require!`/`assert!` of the keys being equal, or a `require_keys_eq!`-style
+//! macro. The id has to be the one of the sysvar the account stands for, going
+//! by its name: `rent` is checked against `sysvar::rent::ID` or `Rent::id()`.
+
+use syn::{
+    punctuated::Punctuated,
+    spanned::Spanned,
+    visit::{self, Visit},
+    BinOp, Expr, Token, UnOp,
+};
+
+use crate::{
+    anchor, guard,
+    imports::Imports,
+    scope::{self, FnScope},
+    Context, Diagnostic, Rule,
+};
+
+/// Loaders of the instructions sysvar that take its data, not its account.
+const UNCHECKED_LOADERS: &[&str] = &["load_instruction_at", "load_current_index"];
+
+/// The sysvars, by module and type: `sysvar::rent::ID`, `Rent::id()`.
+const SYSVARS: &[(&str, &str)] = &[
+    ("rent", "Rent"),
+    ("clock", "Clock"),
+    ("instructions", "Instructions"),
+    ("slot_hashes", "SlotHashes"),
+    ("stake_history", "StakeHistory"),
+    ("epoch_schedule", "EpochSchedule"),
+    ("epoch_rewards", "EpochRewards"),
+    ("fees", "Fees"),
+    ("recent_blockhashes", "RecentBlockhashes"),
+    ("last_restart_slot", "LastRestartSlot"),
+];
+
+/// The instructions sysvar, which the unchecked loaders read.
+const INSTRUCTIONS: (&str, &str) = ("instructions", "Instructions");
+
+pub struct SysvarAddress;
+
+impl Rule for SysvarAddress {
+    fn name(&self) -> &'static str {
+        "sysvar-address"
+    }
+
+    fn description(&self) -> &'static str {
+        "sysvar read from an account whose key is never compared with the sysvar id"
+    }
+
+    fn check(&self, file: &syn::File, _cx: &Context) -> Vec<Diagnostic> {
+        let functions = scope::functions(file);
+        let imports = Imports::new(file);
+        let mut diagnostics = Vec::new();
+
+        let structs = anchor::accounts_structs(file);
+        for accounts in &structs {
+            let handlers: Vec<_> = functions
+                .iter()
+                .filter(|function| {
+                    function.program && function.accounts_struct().as_ref() == Some(&accounts.name)
+                })
+                .collect();
+            for field in &accounts.fields {
+                if !field.is_raw()
+                    || !is_sysvar_name(&field.name)
+                    || field.constraint("address").is_some()
+                {
+                    continue;
+                }
+                let sysvar = sysvar_of(&field.name);
+                if !handlers.is_empty()
+                    && handlers
+                        .iter()
+                        .all(|handler| verifies(handler, &imports, &field.name, sysvar))
+                {
+                    continue;
+                }
+                diagnostics.push(Diagnostic {
+                    span: field.span,
+                    message: format!(
+                        "`{}` in `{}` is an unchecked `{}` standing for a sysvar, and nothing \
+                         compares its key with the sysvar id; any account can be passed. Use \
+                         `Sysvar<'info, _>` or `#[account(address = ..)]`",
+                        field.name,
+                        accounts.name,
+                        field.kind()
+                    ),
+                });
+            }
+        }
+
+        for function in functions.iter().filter(|function| !function.test) {
+            let mut loads = Loads(Vec::new());
+            loads.visit_block(function.block);
+            for (loader, span, args) in loads.0 {
+                let Some(account) = args.iter().find_map(|arg| data_of(function, arg)) else {
+                    continue;
+                };
+                let constrained = anchor::fields(&structs, function, &account)
+                    .iter()
+                    .any(|field| !field.is_raw() || field.constraint("address").is_some());
+                if constrained || verifies(function, &imports, &account, Some(INSTRUCTIONS)) {
+                    continue;
+                }
+                diagnostics.push(Diagnostic {
+                    span,
+                    message: format!(
+                        "`{loader}` in `{}` reads `{account}` as the instructions sysvar, but \
+                         its key is never compared with `sysvar::instructions::ID`; a forged \
+                         account can be passed. Use `{loader}_checked`",
+                        function.name
+                    ),
+                });
+            }
+        }
+        diagnostics
+    }
+}
+
+/// Whether an account called `name` stands for a sysvar.
+fn is_sysvar_name(name: &str) -> bool {
+    name.contains("sysvar") || SYSVARS.iter().any(|(module, _)| name == *module)
+}
+
+/// The sysvar an account called `name` stands for, if the name says which:
+/// `clock` for `clock_sysvar`, none for `sysvar_account`.
+fn sysvar_of(name: &str) -> Option<(&'static str, &'static str)> {
+    SYSVARS
+        .iter()
+        .find(|(module, _)| name.contains(module.trim_end_matches('s')))
+        .copied()
+}
+
+/// Calls to the unchecked loaders, with their arguments.
+struct Loads(Vec<(&'static str, proc_macro2::Span, Vec<Expr>)>);
+
+// Macro arguments are parsed while walking, so nothing visited is kept
+impl<'v> Visit<'v> for Loads {
+    fn visit_expr_call(&mut self, call: &'v syn::ExprCall) {
+        if let Expr::Path(path) = &*call.func {
+            let name = path.path.segments.last().map(|s| s.ident.to_string());
+            if let Some(loader) = UNCHECKED_LOADERS
+                .iter()
+                .find(|l| name.as_deref() == Some(l))
+            {
+                self.0
+                    .push((loader, call.span(), call.args.iter().cloned().collect()));
+            }
+        }
+        visit::visit_expr_call(self, call);
+    }
+
+    fn visit_macro(&mut self, mac: &'v syn::Macro) {
+        if let Ok(args) = mac.parse_body_with(Punctuated::<Expr, Token![,]>::parse_terminated) {
+            args.iter().for_each(|arg| self.visit_expr(arg));
+        }
+    }
+
+    fn visit_item_fn(&mut self, _: &'v syn::ItemFn) {}
+}
+
+/// The account whose data `expr` is: `&ix.try_borrow_data()?`,
+/// `&ix.data.borrow()`, or a local holding one of those.
+fn data_of(function: &FnScope, expr: &Expr) -> Option<String> {
+    let mut expr = scope::strip(expr);
+    for _ in 0..8 {
+        if let Expr::Try(tried) = expr {
+            expr = scope::strip(&tried.expr);
+        }
+        match expr {
+            Expr::MethodCall(call)
+                if matches!(
+                    call.method.to_string().as_str(),
+                    "try_borrow_data" | "try_borrow_mut_data"
+                ) =>
+            {
+                return function.account(&call.receiver)
+            }
+            Expr::MethodCall(call)
+                if matches!(call.method.to_string().as_str(), "borrow" | "borrow_mut") =>
+            {
+                return match scope::strip(&call.receiver) {
+                    Expr::Field(field) if matches!(&field.member, syn::Member::Named(name) if name == "data") => {
+                        function.account(&field.base)
+                    }
+                    _ => None,
+                };
+            }
+            Expr::Index(index) => expr = scope::strip(&index.expr),
+            expr => {
+                let def = function.defs.get(&scope::ident(expr)?)?.first()?;
+                return data_of(function, def);
+            }
+        }
+    }
+    None
+}
+
+/// Whether `function` only goes on once the key of `account` is known to be
+/// the id of `sysvar`, or of any sysvar if it is `None`.
+fn verifies(
+    function: &FnScope,
+    imports: &Imports,
+    account: &str,
+    sysvar: Option<(&str, &str)>,
+) -> bool {
+    let mut verified = Verified {
+        function,
+        imports,
+        account,
+        sysvar,
+        found: false,
+    };
+    verified.visit_block(function.block);
+    verified.found
+}
+
+struct Verified<'a, 'ast> {
+    function: &'a FnScope<'ast>,
+    imports: &'a Imports,
+    account: &'a str,
+    sysvar: Option<(&'a str, &'a str)>,
+    found: bool,
+}
+
+impl Verified<'_, '_> {
+    fn is_key(&self, expr: &Expr) -> bool {
+        let key = match scope::strip(expr) {
+            Expr::Field(field) if matches!(&field.member, syn::Member::Named(name) if name == "key") => {
+                self.function.account(&field.base)
+            }
+            Expr::MethodCall(call) if call.method == "key" && call.args.is_empty() => {
+                self.function.account(&call.receiver)
+            }
+            _ => None,
+        };
+        key.as_deref() == Some(self.account)
+    }
+
+    /// Whether `path`, once imports are expanded, names the sysvar.
+    fn names_sysvar(&self, path: &syn::Path) -> bool {
+        self.imports
+            .resolve(path)
+            .iter()
+            .any(|segment| match self.sysvar {
+                Some((module, ty)) => segment == module || segment == ty,
+                None => {
+                    segment == "sysvar"
+                        || SYSVARS
+                            .iter()
+                            .any(|(module, ty)| segment == module || segment == ty)
+                }
+            })
+    }
+
+    /// Whether `expr` mentions the sysvar's id.
+    fn is_id(&self, expr: &Expr) -> bool {
+        struct Paths<'v, 'a, 'ast>(&'v Verified<'a, 'ast>, bool);
+        impl<'ast> Visit<'ast> for Paths<'_, '_, '_> {
+            fn visit_path(&mut self, path: &'ast syn::Path) {
+                self.1 |= self.0.names_sysvar(path);
+                visit::visit_path(self, path);
+            }
+        }
+        let mut paths = Paths(self, false);
+        paths.visit_expr(expr);
+        paths.1
+    }
+
+    fn compares(&self, left: &Expr, right: &Expr) -> bool {
+        (self.is_key(left) && self.is_id(right)) || (self.is_key(right) && self.is_id(left))
+    }
+
+    /// Whether `cond` evaluating to `value` means the key is the sysvar's id.
+    fn proves(&self, cond: &Expr, value: bool) -> bool {
+        match scope::strip(cond) {
+            Expr::Unary(unary) if matches!(unary.op, UnOp::Not(_)) => {
+                self.proves(&unary.expr, !value)
+            }
+            Expr::Binary(binary) => match (&binary.op, value) {
+                (BinOp::Eq(_), true) | (BinOp::Ne(_), false) => {
+                    self.compares(&binary.left, &binary.right)
+                }
+                (BinOp::And(_), true) | (BinOp::Or(_), false) => {
+                    self.proves(&binary.left, value) || self.proves(&binary.right, value)
+                }
+                _ => false,
+            },
+            // `sysvar::instructions::check_id(ix.key)`
+            Expr::Call(call) if value => match &*call.func {
+                Expr::Path(path) => {
+                    path.path
+                        .segments
+                        .last()
+                        .is_some_and(|s| s.ident == "check_id")
+                        && self.names_sysvar(&path.path)
+                        && call.args.iter().any(|arg| self.is_key(arg))
+                }
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+}
+
+// Macro arguments are parsed while walking, so nothing visited is kept
+impl<'v> Visit<'v> for Verified<'_, '_> {
+    fn visit_expr_if(&mut self, branch: &'v syn::ExprIf) {
+        let otherwise_diverges = matches!(
+            branch.else_branch.as_ref().map(|(_, otherwise)| &**otherwise),
+            Some(Expr::Block(otherwise)) if guard::diverges(&otherwise.block)
+        );
+        self.found |= (guard::diverges(&branch.then_branch) && self.proves(&branch.cond, false))
+            || (otherwise_diverges && self.proves(&branch.cond, true));
+        visit::visit_expr_if(self, branch);
+    }
+
+    fn visit_macro(&mut self, mac: &'v syn::Macro) {
+        let Ok(args) = mac.parse_body_with(Punctuated::<Expr, Token![,]>::parse_terminated) else {
+            return;
+        };
+        let name = mac
+            .path
+            .segments
+            .last()
+            .map(|s| s.ident.to_string())
+            .unwrap_or_default();
+        // `require_keys_eq!(a, b)`, `require!(a == b)`
+        if name.ends_with("_eq") && args.len() >= 2 {
+            self.found |= self.compares(&args[0], &args[1]);
+        } else if name == "require" || name == "assert" {
+            self.found |= args.first().is_some_and(|cond| self.proves(cond, true));
+        }
+        args.iter().for_each(|arg| self.visit_expr(arg));
+    }
+
+    fn visit_item_fn(&mut self, _: &'v syn::ItemFn) {}
+}
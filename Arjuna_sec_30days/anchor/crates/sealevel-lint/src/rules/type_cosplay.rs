@@ -0,0 +1,255 @@
+//! `type-cosplay`: native account layouts with no type tag in front.
+//!
+//! Without a discriminator, the only thing telling one account type from
+//! another is its length. Any account the program owns that has the right
+//! size, say one holding a different struct of the same `LEN`, unpacks as the
+//! type and its bytes are taken at face value. Anchor's `#[account]` writes an
+//! eight-byte discriminator and checks it; a native layout has to start with
+//! its own tag and compare it when unpacking.
+//!
+//! A struct counts as an account layout if it has a `LEN`-style constant,
+//! implements `Pack`, or is unpacked or deserialized in the same file from
+//! bytes that trace back to an account's data. Instruction arguments unpacked
+//! from the instruction data are not layouts.
+
+use std::collections::{HashMap, HashSet};
+
+use syn::{
+    visit::{self, Visit},
+    Expr, ItemStruct,
+};
+
+use crate::{
+    consts::Consts,
+    scope::{self, FnScope},
+    Context, Diagnostic, Rule,
+};
+
+/// Names of the constants that give a layout's size.
+const LENGTHS: &[&str] = &["LEN", "SIZE", "SPACE", "LENGTH"];
+
+/// Traits only account layouts implement.
+const LAYOUT_TRAITS: &[&str] = &["Pack", "Sealed", "IsInitialized"];
+
+/// Functions that read a struct out of bytes.
+const DESERIALIZERS: &[&str] = &[
+    "try_from_slice",
+    "deserialize",
+    "from_bytes",
+    "try_from_bytes",
+];
+
+/// Words in the name of a leading field that make it a tag: `discriminator`,
+/// `account_type`.
+const TAG_WORDS: &[&str] = &[
+    "discriminator",
+    "discriminant",
+    "disc",
+    "tag",
+    "kind",
+    "type",
+];
+
+/// Endings of the type of a leading field that make it a tag: `AccountKind`.
+const TAG_TYPES: &[&str] = &["Discriminator", "Discriminant", "Kind", "Tag", "Type"];
+
+pub struct TypeCosplay;
+
+impl Rule for TypeCosplay {
+    fn name(&self) -> &'static str {
+        "type-cosplay"
+    }
+
+    fn description(&self) -> &'static str {
+        "native account layout without a leading discriminator, or two of the same length"
+    }
+
+    fn check(&self, file: &syn::File, cx: &Context) -> Vec<Diagnostic> {
+        let mut items = Items::default();
+        items.visit_file(file);
+        for function in scope::functions(file) {
+            let mut unpacked = Unpacked {
+                function: &function,
+                layouts: &mut items.layouts,
+            };
+            unpacked.visit_block(function.block);
+        }
+        let consts = Consts::new(file, cx);
+
+        let mut diagnostics = Vec::new();
+        let mut lengths: Vec<(String, usize, bool)> = Vec::new();
+        for item in &items.structs {
+            let name = item.ident.to_string();
+            let len = items.lengths.get(&name);
+            if len.is_none() && !items.layouts.contains(&name) {
+                continue;
+            }
+            let tagged = tagged(item, &items.enums);
+            if !tagged {
+                diagnostics.push(Diagnostic {
+                    span: item.ident.span(),
+                    message: format!(
+                        "`{name}` is an account layout with no discriminator in front; any \
+                         account the program owns with the same length unpacks as one. Start \
+                         it with a type tag, such as an enum of account kinds, and check the \
+                         tag when unpacking"
+                    ),
+                });
+            }
+
+            let Some(len) = len.and_then(|len| consts.eval(len)) else {
+                continue;
+            };
+            for (other, other_len, other_tagged) in &lengths {
+                if *other_len == len && !(tagged && *other_tagged) {
+                    diagnostics.push(Diagnostic {
+                        span: item.ident.span(),
+                        message: format!(
+                            "`{name}` and `{other}` are both {len} bytes and not both tagged; \
+                             an account holding one can be passed as the other"
+                        ),
+                    });
+                }
+            }
+            lengths.push((name, len, tagged));
+        }
+        diagnostics
+    }
+}
+
+/// Whether the first field of `item` is a type tag.
+fn tagged(item: &ItemStruct, enums: &HashSet<String>) -> bool {
+    let Some(first) = item.fields.iter().next() else {
+        return false;
+    };
+    let name = first
+        .ident
+        .as_ref()
+        .map(|ident| ident.to_string())
+        .unwrap_or_default();
+    let ty = scope::last_segment(&first.ty).unwrap_or_default();
+    enums.contains(&ty)
+        || TAG_TYPES.iter().any(|tag| ty.ends_with(tag))
+        || name
+            .trim_start_matches("r#")
+            .split('_')
+            .any(|word| TAG_WORDS.contains(&word))
+}
+
+/// What the file says about its structs.
+#[derive(Default)]
+struct Items<'ast> {
+    /// Structs with named fields that are not Anchor accounts.
+    structs: Vec<&'ast ItemStruct>,
+    enums: HashSet<String>,
+    /// `const LEN` and the like, by the type they are on.
+    lengths: HashMap<String, &'ast Expr>,
+    /// Types that implement a layout trait or are deserialized from account
+    /// data.
+    layouts: HashSet<String>,
+}
+
+impl<'ast> Visit<'ast> for Items<'ast> {
+    fn visit_item_struct(&mut self, item: &'ast ItemStruct) {
+        // `#[account]` and `#[derive(Accounts)]` are Anchor's
+        let anchor = item.attrs.iter().any(|attr| {
+            attr.path().is_ident("account")
+                || (attr.path().is_ident("derive")
+                    && attr
+                        .meta
+                        .require_list()
+                        .is_ok_and(|list| list.tokens.to_string().contains("Accounts")))
+        });
+        if !anchor && matches!(item.fields, syn::Fields::Named(_)) {
+            self.structs.push(item);
+        }
+        visit::visit_item_struct(self, item);
+    }
+
+    fn visit_item_enum(&mut self, item: &'ast syn::ItemEnum) {
+        self.enums.insert(item.ident.to_string());
+        visit::visit_item_enum(self, item);
+    }
+
+    fn visit_item_impl(&mut self, item: &'ast syn::ItemImpl) {
+        let Some(ty) = scope::last_segment(&item.self_ty) else {
+            return;
+        };
+        let layout_trait = item.trait_.as_ref().is_some_and(|(_, path, _)| {
+            path.segments
+                .last()
+                .is_some_and(|segment| LAYOUT_TRAITS.iter().any(|t| segment.ident == t))
+        });
+        if layout_trait {
+            self.layouts.insert(ty.clone());
+        }
+        for impl_item in &item.items {
+            match impl_item {
+                syn::ImplItem::Const(item) if LENGTHS.iter().any(|len| item.ident == len) => {
+                    self.lengths.insert(ty.clone(), &item.expr);
+                }
+                _ => {}
+            }
+        }
+        visit::visit_item_impl(self, item);
+    }
+}
+
+/// Types one function deserializes from account data.
+struct Unpacked<'a, 'ast> {
+    function: &'a FnScope<'ast>,
+    layouts: &'a mut HashSet<String>,
+}
+
+impl<'ast> Visit<'ast> for Unpacked<'_, 'ast> {
+    fn visit_expr_call(&mut self, call: &'ast syn::ExprCall) {
+        // `User::try_from_slice(&ctx.accounts.user.data.borrow())`
+        if let Expr::Path(path) = &*call.func {
+            let segments: Vec<_> = path.path.segments.iter().collect();
+            if let [.., ty, function] = segments.as_slice() {
+                let function = function.ident.to_string();
+                let deserializes =
+                    function.starts_with("unpack") || DESERIALIZERS.contains(&function.as_str());
+                let from_account = call
+                    .args
+                    .iter()
+                    .any(|arg| account_data(self.function, arg, &mut HashSet::new()));
+                if deserializes && from_account {
+                    self.layouts.insert(ty.ident.to_string());
+                }
+            }
+        }
+        visit::visit_expr_call(self, call);
+    }
+
+    fn visit_item_fn(&mut self, _: &'ast syn::ItemFn) {}
+}
+
+/// Whether `expr` is an account's data: `x.data.borrow()`,
+/// `x.try_borrow_data()?`, a slice of either, or a local holding one.
+fn account_data(function: &FnScope, expr: &Expr, seen: &mut HashSet<String>) -> bool {
+    match scope::strip(expr) {
+        Expr::Try(expr) => account_data(function, &expr.expr, seen),
+        Expr::Index(index) => account_data(function, &index.expr, seen),
+        Expr::MethodCall(call) => match call.method.to_string().as_str() {
+            "try_borrow_data" | "try_borrow_mut_data" => function.account(&call.receiver).is_some(),
+            "borrow" | "borrow_mut" => match scope::strip(&call.receiver) {
+                Expr::Field(field) => {
+                    matches!(&field.member, syn::Member::Named(name) if name == "data")
+                        && function.account(&field.base).is_some()
+                }
+                _ => false,
+            },
+            _ => false,
+        },
+        expr => scope::ident(expr).is_some_and(|name| {
+            seen.insert(name.clone())
+                && function
+                    .defs
+                    .get(&name)
+                    .into_iter()
+                    .flatten()
+                    .any(|def| account_data(function, def, seen))
+        }),
+    }
+}
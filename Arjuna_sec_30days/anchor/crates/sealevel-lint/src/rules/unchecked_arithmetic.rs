@@ -0,0 +1,191 @@
+//! `unchecked-arithmetic`: `+`, `-`, `*` and their assignments on lamports and
+//! token amounts.
+//!
+//! Wrapping lamports or balances mints or burns value out of thin air. Release
+//! builds only trap on overflow with `overflow-checks = true`, and that is a
+//! profile setting, not a property of the code: findings in a workspace that
+//! sets it are reported as info rather than dropped.
+
+use std::collections::HashSet;
+
+use syn::{
+    spanned::Spanned,
+    visit::{self, Visit},
+    BinOp, Expr, Type,
+};
+
+use crate::{
+    scope::{self, FnScope},
+    Context, Diagnostic, Level, Rule,
+};
+
+/// Integer types whose values are treated as amounts: lamports and token
+/// amounts are `u64`. Narrower types are mostly bumps, tags and counters, and
+/// `usize` lengths and offsets.
+const AMOUNT_TYPES: &[&str] = &["u64"];
+
+pub struct UncheckedArithmetic;
+
+impl Rule for UncheckedArithmetic {
+    fn name(&self) -> &'static str {
+        "unchecked-arithmetic"
+    }
+
+    fn description(&self) -> &'static str {
+        "unchecked +, - or * on lamports or integer amounts (info with overflow-checks on)"
+    }
+
+    fn check(&self, file: &syn::File, _cx: &Context) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        for function in scope::functions(file) {
+            let amounts = amounts(&function);
+            let mut walk = Walk {
+                function: &function,
+                amounts: &amounts,
+                diagnostics: &mut diagnostics,
+            };
+            walk.visit_block(function.block);
+        }
+        diagnostics
+    }
+
+    fn level(&self, cx: &Context) -> Level {
+        if cx.overflow_checks {
+            Level::Info
+        } else {
+            Level::Warning
+        }
+    }
+}
+
+/// Parameters and locals that hold an amount: declared as `u64`, or read from
+/// `u64::from_le_bytes`, a token account's `amount` or an account's
+/// `lamports()`.
+fn amounts(function: &FnScope) -> HashSet<String> {
+    struct Typed(HashSet<String>);
+    impl<'ast> Visit<'ast> for Typed {
+        fn visit_local(&mut self, local: &'ast syn::Local) {
+            if let syn::Pat::Type(pat) = &local.pat {
+                if is_amount_type(&pat.ty) {
+                    self.0.extend(scope::bindings(&pat.pat));
+                }
+            }
+            visit::visit_local(self, local);
+        }
+
+        fn visit_item_fn(&mut self, _: &'ast syn::ItemFn) {}
+    }
+    let mut typed = Typed(HashSet::new());
+    typed.visit_block(function.block);
+
+    let params = function
+        .params
+        .iter()
+        .filter(|(_, ty)| is_amount_type(ty))
+        .map(|(name, _)| name.clone());
+    let read = function
+        .defs
+        .iter()
+        .filter(|(_, defs)| defs.iter().any(|def| reads_amount(def)))
+        .map(|(name, _)| name.clone());
+    typed.0.into_iter().chain(params).chain(read).collect()
+}
+
+fn is_amount_type(ty: &Type) -> bool {
+    scope::last_segment(ty).is_some_and(|ty| AMOUNT_TYPES.contains(&ty.as_str()))
+}
+
+/// Whether `expr` reads an amount out of an account or raw bytes.
+fn reads_amount(expr: &Expr) -> bool {
+    match expr {
+        Expr::Try(expr) => reads_amount(&expr.expr),
+        Expr::Field(field) => matches!(&field.member, syn::Member::Named(name) if name == "amount"),
+        Expr::MethodCall(call) => call.method == "lamports",
+        Expr::Call(call) => match &*call.func {
+            Expr::Path(path) => {
+                let segments: Vec<_> = path.path.segments.iter().collect();
+                matches!(segments.as_slice(), [ty, function]
+                    if AMOUNT_TYPES.contains(&ty.ident.to_string().as_str())
+                        && function.ident.to_string().starts_with("from_"))
+            }
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+struct Walk<'a, 'ast> {
+    function: &'a FnScope<'ast>,
+    amounts: &'a HashSet<String>,
+    diagnostics: &'a mut Vec<Diagnostic>,
+}
+
+impl Walk<'_, '_> {
+    /// What amount `expr` is, for the message: `lamports of \`vault\``, or
+    /// `\`amount\``.
+    fn amount(&self, expr: &Expr) -> Option<String> {
+        if let Some(account) = lamports_of(expr) {
+            return Some(format!("the lamports of `{account}`"));
+        }
+        match scope::strip(expr) {
+            Expr::Paren(paren) => self.amount(&paren.expr),
+            expr if reads_amount(expr) => Some("a token amount".to_string()),
+            expr => scope::ident(expr)
+                .filter(|name| self.amounts.contains(name))
+                .map(|name| format!("`{name}`")),
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for Walk<'_, 'ast> {
+    fn visit_expr_binary(&mut self, binary: &'ast syn::ExprBinary) {
+        let (op, checked) = match binary.op {
+            BinOp::Add(_) => ("+", "checked_add"),
+            BinOp::Sub(_) => ("-", "checked_sub"),
+            BinOp::Mul(_) => ("*", "checked_mul"),
+            BinOp::AddAssign(_) => ("+=", "checked_add"),
+            BinOp::SubAssign(_) => ("-=", "checked_sub"),
+            BinOp::MulAssign(_) => ("*=", "checked_mul"),
+            _ => return visit::visit_expr_binary(self, binary),
+        };
+        let amount = self
+            .amount(&binary.left)
+            .or_else(|| self.amount(&binary.right));
+        if let Some(amount) = amount {
+            self.diagnostics.push(Diagnostic {
+                span: binary.span(),
+                message: format!(
+                    "unchecked `{op}` on {amount} in `{}` can wrap around; use `{checked}` \
+                     and return an error on `None`",
+                    self.function.name
+                ),
+            });
+        }
+        visit::visit_expr_binary(self, binary);
+    }
+
+    fn visit_item_fn(&mut self, _: &'ast syn::ItemFn) {}
+}
+
+/// The account in `**account.try_borrow_mut_lamports()?` or
+/// `**account.lamports.borrow_mut()`.
+fn lamports_of(expr: &Expr) -> Option<String> {
+    let expr = match scope::strip(expr) {
+        Expr::Try(expr) => &*expr.expr,
+        expr => expr,
+    };
+    let Expr::MethodCall(call) = expr else {
+        return None;
+    };
+    let receiver = match call.method.to_string().as_str() {
+        "try_borrow_mut_lamports" | "try_borrow_lamports" => &call.receiver,
+        "borrow_mut" | "borrow" => match scope::strip(&call.receiver) {
+            Expr::Field(field) if matches!(&field.member, syn::Member::Named(name) if name == "lamports") => {
+                &field.base
+            }
+            _ => return None,
+        },
+        _ => return None,
+    };
+    scope::account(receiver).map(|account| account.name().to_string())
+}
@@ -0,0 +1,375 @@
+//! `unchecked-slice`: indexing instruction data or account data past the
+//! length a guard has established.
+//!
+//! Both buffers are whatever the caller sent, and an out-of-range index
+//! panics the program. A guard is a length comparison or `is_empty()` whose
+//! failing branch leaves the function (`if data.len() < 9 { return .. }`, or
+//! an `else` that does), is asserted (`require!`), or is the condition of the
+//! `if` or `&&` the access is under. Rebinding a name forgets what was known
+//! about it. Bounds are followed through literals and the crate's `const`s. A
+//! bound that cannot be evaluated, such as `data[offset..offset + 8]`, is only
+//! covered by a guard comparing the length to that same expression.
+
+use std::collections::{HashMap, HashSet};
+
+use syn::{
+    punctuated::Punctuated,
+    spanned::Spanned,
+    visit::{self, Visit},
+    BinOp, Block, Expr, RangeLimits, Stmt, Token, UnOp,
+};
+
+use crate::{
+    consts::Consts,
+    guard,
+    scope::{self, FnScope},
+    Context, Diagnostic, Rule,
+};
+
+/// A length a buffer is known to have, or that an index needs.
+#[derive(Clone)]
+enum Bound {
+    /// At least this many bytes.
+    Len(usize),
+    /// At least the value of an expression that could not be evaluated, one
+    /// more if `strict`: `data.len() > end` for `data[..=end]`.
+    Expr { expr: Box<Expr>, strict: bool },
+}
+
+/// What the guards so far establish about one buffer.
+#[derive(Clone, Default)]
+struct Known {
+    min: usize,
+    exprs: Vec<(Expr, bool)>,
+}
+
+impl Known {
+    fn covers(&self, needed: &Bound) -> bool {
+        match needed {
+            Bound::Len(len) => self.min >= *len,
+            Bound::Expr { expr, strict } => self
+                .exprs
+                .iter()
+                .any(|(known, known_strict)| known == &**expr && (*known_strict || !strict)),
+        }
+    }
+}
+
+pub struct UncheckedSlice;
+
+impl Rule for UncheckedSlice {
+    fn name(&self) -> &'static str {
+        "unchecked-slice"
+    }
+
+    fn description(&self) -> &'static str {
+        "instruction or account data indexed past the length its guards establish"
+    }
+
+    fn group(&self) -> Option<&'static str> {
+        Some(super::PANICS)
+    }
+
+    fn check(&self, file: &syn::File, cx: &Context) -> Vec<Diagnostic> {
+        let consts = Consts::new(file, cx);
+
+        let functions = scope::functions(file);
+        let mut diagnostics = Vec::new();
+        for function in scope::reachable(&functions) {
+            let buffers = buffers(function);
+            if buffers.is_empty() {
+                continue;
+            }
+            let mut walk = Walk {
+                consts: &consts,
+                buffers: &buffers,
+                known: vec![HashMap::new()],
+                diagnostics: &mut diagnostics,
+            };
+            walk.visit_block(function.block);
+        }
+        diagnostics
+    }
+}
+
+/// The byte buffers of a function: `&[u8]` parameters, and locals borrowing
+/// an account's data.
+fn buffers(function: &FnScope) -> HashSet<String> {
+    let params = function
+        .params
+        .iter()
+        .filter(|(_, ty)| scope::is_byte_slice(ty))
+        .map(|(name, _)| name.clone());
+    let data = function
+        .defs
+        .iter()
+        .filter(|(_, defs)| defs.iter().any(|def| borrows_data(def)))
+        .map(|(name, _)| name.clone());
+    params.chain(data).collect()
+}
+
+fn borrows_data(expr: &Expr) -> bool {
+    match expr {
+        Expr::Try(expr) => borrows_data(&expr.expr),
+        Expr::MethodCall(call) => match call.method.to_string().as_str() {
+            "try_borrow_data" | "try_borrow_mut_data" => true,
+            "borrow" | "borrow_mut" => matches!(
+                scope::strip(&call.receiver),
+                Expr::Field(field) if matches!(&field.member, syn::Member::Named(name) if name == "data")
+            ),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+struct Walk<'a, 'ast> {
+    consts: &'a Consts<'ast>,
+    buffers: &'a HashSet<String>,
+    /// What is established about each buffer so far, one map per scope.
+    known: Vec<HashMap<String, Known>>,
+    diagnostics: &'a mut Vec<Diagnostic>,
+}
+
+impl Walk<'_, '_> {
+    /// `expr` as a bound, one more if `strict`.
+    fn bound(&self, expr: &Expr, strict: bool) -> Option<Bound> {
+        let expr = scope::strip(expr);
+        Some(match self.consts.eval(expr) {
+            Some(len) if strict => Bound::Len(len.checked_add(1)?),
+            Some(len) => Bound::Len(len),
+            None => Bound::Expr {
+                expr: Box::new(expr.clone()),
+                strict,
+            },
+        })
+    }
+
+    /// The buffer `expr` is, if it is one of the tracked ones.
+    fn buffer(&self, expr: &Expr) -> Option<String> {
+        scope::ident(scope::strip(expr)).filter(|name| self.buffers.contains(name))
+    }
+
+    /// The buffer whose length `expr` reads, `data` for `data.len()`.
+    fn len_of(&self, expr: &Expr) -> Option<String> {
+        match scope::strip(expr) {
+            Expr::MethodCall(call) if call.method == "len" && call.args.is_empty() => {
+                self.buffer(&call.receiver)
+            }
+            _ => None,
+        }
+    }
+
+    /// Minimum lengths `cond` establishes when it is `holds`.
+    fn facts(&self, cond: &Expr, holds: bool) -> Vec<(String, Bound)> {
+        match scope::strip(cond) {
+            Expr::Unary(unary) if matches!(unary.op, UnOp::Not(_)) => {
+                self.facts(&unary.expr, !holds)
+            }
+            Expr::Binary(binary) => match (&binary.op, holds) {
+                (BinOp::And(_), true) | (BinOp::Or(_), false) => {
+                    let mut facts = self.facts(&binary.left, holds);
+                    facts.extend(self.facts(&binary.right, holds));
+                    facts
+                }
+                _ => self.comparison(binary, holds).into_iter().collect(),
+            },
+            Expr::MethodCall(call) if call.method == "is_empty" && !holds => self
+                .buffer(&call.receiver)
+                .map(|name| (name, Bound::Len(1)))
+                .into_iter()
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// `data.len() >= 9` and its variants, with the length on either side.
+    fn comparison(&self, binary: &syn::ExprBinary, holds: bool) -> Option<(String, Bound)> {
+        // Normalized to `len OP bound`
+        let (name, bound, flipped) = match (self.len_of(&binary.left), self.len_of(&binary.right)) {
+            (Some(name), None) => (name, &*binary.right, false),
+            (None, Some(name)) => (name, &*binary.left, true),
+            _ => return None,
+        };
+        let strict = match (&binary.op, flipped, holds) {
+            (BinOp::Ge(_), false, true) | (BinOp::Le(_), true, true) => false,
+            (BinOp::Gt(_), false, true) | (BinOp::Lt(_), true, true) => true,
+            (BinOp::Lt(_), false, false) | (BinOp::Gt(_), true, false) => false,
+            (BinOp::Le(_), false, false) | (BinOp::Ge(_), true, false) => true,
+            (BinOp::Eq(_), _, true) | (BinOp::Ne(_), _, false) => false,
+            _ => return None,
+        };
+        Some((name, self.bound(bound, strict)?))
+    }
+
+    fn assume(&mut self, facts: Vec<(String, Bound)>) {
+        let known = self.known.last_mut().expect("never empty");
+        for (name, bound) in facts {
+            let entry = known.entry(name).or_default();
+            match bound {
+                Bound::Len(len) => entry.min = entry.min.max(len),
+                Bound::Expr { expr, strict } => entry.exprs.push((*expr, strict)),
+            }
+        }
+    }
+
+    fn scoped(&mut self, facts: Vec<(String, Bound)>, visit: impl FnOnce(&mut Self)) {
+        self.known
+            .push(self.known.last().cloned().unwrap_or_default());
+        self.assume(facts);
+        visit(self);
+        self.known.pop();
+    }
+
+    /// What a statement establishes for the statements after it.
+    fn established(&self, stmt: &Stmt) -> Vec<(String, Bound)> {
+        let expr = match stmt {
+            Stmt::Expr(expr, _) => expr,
+            Stmt::Macro(mac) => return self.asserted(&mac.mac),
+            _ => return Vec::new(),
+        };
+        match expr {
+            Expr::If(branch) if guard::diverges(&branch.then_branch) => {
+                self.facts(&branch.cond, false)
+            }
+            Expr::If(branch) => match branch.else_branch.as_ref().map(|(_, e)| &**e) {
+                Some(Expr::Block(otherwise)) if guard::diverges(&otherwise.block) => {
+                    self.facts(&branch.cond, true)
+                }
+                _ => Vec::new(),
+            },
+            Expr::Macro(mac) => self.asserted(&mac.mac),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Forgets what is known about `names` and any bound that uses them, in
+    /// the innermost scope, or in every scope for an assignment, which
+    /// outlives the block it is in.
+    fn forget(&mut self, names: &[String], everywhere: bool) {
+        let depth = if everywhere { self.known.len() } else { 1 };
+        for known in self.known.iter_mut().rev().take(depth) {
+            known.retain(|name, _| !names.contains(name));
+            for entry in known.values_mut() {
+                entry
+                    .exprs
+                    .retain(|(expr, _)| !scope::idents(expr).iter().any(|id| names.contains(id)));
+            }
+        }
+    }
+
+    /// The condition of a `require!`/`assert!`-style macro, as facts.
+    fn asserted(&self, mac: &syn::Macro) -> Vec<(String, Bound)> {
+        let name = mac.path.segments.last().map(|s| s.ident.to_string());
+        if !name.is_some_and(|name| name.starts_with("require") || name.starts_with("assert")) {
+            return Vec::new();
+        }
+        match mac.parse_body_with(Punctuated::<Expr, Token![,]>::parse_terminated) {
+            Ok(args) => args
+                .first()
+                .map(|cond| self.facts(cond, true))
+                .unwrap_or_default(),
+            Err(_) => Vec::new(),
+        }
+    }
+
+    /// The length indexing `expr` needs, e.g. 9 for `data[1..9]`.
+    fn needed(&self, index: &Expr) -> Option<Bound> {
+        match index {
+            Expr::Range(range) => match (&range.start, &range.end, &range.limits) {
+                (_, Some(end), RangeLimits::HalfOpen(_)) => self.bound(end, false),
+                (_, Some(end), RangeLimits::Closed(_)) => self.bound(end, true),
+                (Some(start), None, _) => self.bound(start, false),
+                (None, None, _) => None,
+            },
+            index => self.bound(index, true),
+        }
+    }
+}
+
+// Macro arguments are parsed on the fly, so the walk is not tied to the file's lifetime
+impl<'v> Visit<'v> for Walk<'_, '_> {
+    fn visit_block(&mut self, block: &'v Block) {
+        self.scoped(Vec::new(), |walk| {
+            for stmt in &block.stmts {
+                walk.visit_stmt(stmt);
+                let facts = walk.established(stmt);
+                walk.assume(facts);
+            }
+        });
+    }
+
+    fn visit_expr_if(&mut self, branch: &'v syn::ExprIf) {
+        self.visit_expr(&branch.cond);
+        let facts = self.facts(&branch.cond, true);
+        self.scoped(facts, |walk| walk.visit_block(&branch.then_branch));
+        if let Some((_, otherwise)) = &branch.else_branch {
+            let facts = self.facts(&branch.cond, false);
+            self.scoped(facts, |walk| walk.visit_expr(otherwise));
+        }
+    }
+
+    fn visit_local(&mut self, local: &'v syn::Local) {
+        visit::visit_local(self, local);
+        self.forget(&scope::bindings(&local.pat), false);
+    }
+
+    fn visit_expr_assign(&mut self, assign: &'v syn::ExprAssign) {
+        visit::visit_expr_assign(self, assign);
+        if let Some(name) = scope::ident(scope::strip(&assign.left)) {
+            self.forget(&[name], true);
+        }
+    }
+
+    fn visit_expr_binary(&mut self, binary: &'v syn::ExprBinary) {
+        // The right side of `&&` only runs once the left side held
+        let holds = match binary.op {
+            BinOp::And(_) => true,
+            BinOp::Or(_) => false,
+            _ => return visit::visit_expr_binary(self, binary),
+        };
+        self.visit_expr(&binary.left);
+        let facts = self.facts(&binary.left, holds);
+        self.scoped(facts, |walk| walk.visit_expr(&binary.right));
+    }
+
+    fn visit_expr_index(&mut self, index: &'v syn::ExprIndex) {
+        if let (Some(name), Some(needed)) = (self.buffer(&index.expr), self.needed(&index.index)) {
+            let known = self
+                .known
+                .last()
+                .and_then(|known| known.get(&name))
+                .cloned()
+                .unwrap_or_default();
+            if !known.covers(&needed) {
+                let checked = match known.min {
+                    0 => "its length is never checked before it".to_string(),
+                    min => format!("only `{name}.len() >= {min}` is established before it"),
+                };
+                let message = match needed {
+                    Bound::Len(len) => format!(
+                        "indexing `{name}` up to byte {len} needs `{name}.len() >= {len}`, \
+                         but {checked}"
+                    ),
+                    Bound::Expr { .. } => format!(
+                        "indexing `{name}` with a bound that is not a constant needs a guard \
+                         comparing `{name}.len()` to that bound, but {checked}"
+                    ),
+                };
+                self.diagnostics.push(Diagnostic {
+                    span: index.span(),
+                    message,
+                });
+            }
+        }
+        visit::visit_expr_index(self, index);
+    }
+
+    fn visit_macro(&mut self, mac: &'v syn::Macro) {
+        if let Ok(args) = mac.parse_body_with(Punctuated::<Expr, Token![,]>::parse_terminated) {
+            args.iter().for_each(|arg| self.visit_expr(arg));
+        }
+    }
+
+    fn visit_item_fn(&mut self, _: &'v syn::ItemFn) {}
+}
@@ -0,0 +1,298 @@
+//! `unsafe-close`: an account closed by draining its lamports while its data
+//! is left as it was.
+//!
+//! The runtime only garbage-collects an account with no lamports at the end
+//! of the transaction. Until then, and for good if a later instruction tops
+//! it back up, the account still holds its data and discriminator and the
+//! program accepts it as live. Closing also has to zero the data or write
+//! `CLOSED_ACCOUNT_DISCRIMINATOR`, which Anchor's `close = destination` does.
+//! Only zeroing traced to the drained account's own data counts: a buffer
+//! borrowed from it, and the locals, indexes and iterators taken from that.
+
+use std::collections::{HashMap, HashSet};
+
+use proc_macro2::Span;
+use syn::{
+    punctuated::Punctuated,
+    spanned::Spanned,
+    visit::{self, Visit},
+    BinOp, Expr, Token,
+};
+
+use crate::{
+    anchor::{self, AccountsStruct},
+    scope::{self, FnScope},
+    Context, Diagnostic, Rule,
+};
+
+/// Account types the program does not own, so cannot drain.
+const NOT_OWNED: &[&str] = &["Signer", "SystemAccount", "Program", "Sysvar"];
+
+pub struct UnsafeClose;
+
+impl Rule for UnsafeClose {
+    fn name(&self) -> &'static str {
+        "unsafe-close"
+    }
+
+    fn description(&self) -> &'static str {
+        "all lamports moved out of an account without zeroing its data or using close ="
+    }
+
+    fn check(&self, file: &syn::File, _cx: &Context) -> Vec<Diagnostic> {
+        let structs = anchor::accounts_structs(file);
+        let mut diagnostics = Vec::new();
+        for function in scope::functions(file) {
+            if function.test {
+                continue;
+            }
+            let mut closing = Closing {
+                function: &function,
+                drained: HashMap::new(),
+                buffers: HashMap::new(),
+                cleared: HashSet::new(),
+                zeroed: HashSet::new(),
+            };
+            closing.visit_block(function.block);
+            let mut drained: Vec<_> = closing.drained.into_iter().collect();
+            drained.sort_by_key(|(_, span)| (span.start().line, span.start().column));
+            for (account, span) in drained {
+                if closing.zeroed.contains(&account)
+                    || closing.cleared.contains(&account)
+                    || !owned(&structs, &function, &account)
+                {
+                    continue;
+                }
+                diagnostics.push(Diagnostic {
+                    span,
+                    message: format!(
+                        "`{account}` is emptied of lamports in `{}` but its data is neither \
+                         zeroed nor marked with `CLOSED_ACCOUNT_DISCRIMINATOR`; it stays a live \
+                         account until garbage collected and can be revived by refunding it. \
+                         Use `close = destination` on the field",
+                        function.name
+                    ),
+                });
+            }
+        }
+        diagnostics
+    }
+}
+
+/// Whether `account` may be owned by the program and is not already closed
+/// by a `close =` constraint.
+fn owned(structs: &[AccountsStruct], function: &FnScope, account: &str) -> bool {
+    let fields = anchor::fields(structs, function, account);
+    fields.is_empty()
+        || fields.iter().any(|field| {
+            field.constraint("close").is_none() && !NOT_OWNED.contains(&field.kind().as_str())
+        })
+}
+
+/// What one function does to account lamports and data.
+struct Closing<'a, 'ast> {
+    function: &'a FnScope<'ast>,
+    /// Accounts set to zero lamports, or debited their whole balance.
+    drained: HashMap<String, Span>,
+    /// Locals that are part of an account's data, with the account:
+    /// `data` after `let mut data = account.try_borrow_mut_data()?`, or
+    /// `byte` in `for byte in data.iter_mut()`.
+    buffers: HashMap<String, String>,
+    /// Accounts reassigned or shrunk to no data.
+    cleared: HashSet<String>,
+    /// Accounts whose data is zeroed or marked with the closed discriminator.
+    zeroed: HashSet<String>,
+}
+
+impl Closing<'_, '_> {
+    /// The account whose lamports `expr` borrows mutably:
+    /// `**x.try_borrow_mut_lamports()?` or `**x.lamports.borrow_mut()`.
+    fn lamports_mut(&self, expr: &Expr) -> Option<String> {
+        let mut expr = scope::strip(expr);
+        if let Expr::Try(tried) = expr {
+            expr = scope::strip(&tried.expr);
+        }
+        let Expr::MethodCall(call) = expr else {
+            return None;
+        };
+        match call.method.to_string().as_str() {
+            "try_borrow_mut_lamports" => self.function.account(&call.receiver),
+            "borrow_mut" => match scope::strip(&call.receiver) {
+                Expr::Field(field) if matches!(&field.member, syn::Member::Named(name) if name == "lamports") => {
+                    self.function.account(&field.base)
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// The account whose whole balance `expr` is: `x.lamports()`,
+    /// `x.get_lamports()` or `**x.lamports.borrow()`.
+    fn balance(&self, expr: &Expr) -> Option<String> {
+        let mut expr = scope::strip(expr);
+        if let Expr::Try(tried) = expr {
+            expr = scope::strip(&tried.expr);
+        }
+        let Expr::MethodCall(call) = expr else {
+            return None;
+        };
+        match call.method.to_string().as_str() {
+            "lamports" | "try_lamports" | "get_lamports" => self.function.account(&call.receiver),
+            "borrow" => match scope::strip(&call.receiver) {
+                Expr::Field(field) if matches!(&field.member, syn::Member::Named(name) if name == "lamports") => {
+                    self.function.account(&field.base)
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// The account whose data `expr` is or is part of: a mutable borrow of
+    /// it, a local holding one, an index or iterator into one, or a wrapper
+    /// such as `Cursor::new(data)` around one.
+    fn data_of(&self, expr: &Expr) -> Option<String> {
+        match scope::strip(expr) {
+            Expr::Try(tried) => self.data_of(&tried.expr),
+            Expr::Index(index) => self.data_of(&index.expr),
+            Expr::MethodCall(call) => match call.method.to_string().as_str() {
+                "try_borrow_mut_data" => self.function.account(&call.receiver),
+                "borrow_mut" => match scope::strip(&call.receiver) {
+                    Expr::Field(field) if matches!(&field.member, syn::Member::Named(name) if name == "data") => {
+                        self.function.account(&field.base)
+                    }
+                    _ => None,
+                },
+                "deref_mut" | "iter_mut" | "as_mut" | "as_mut_slice" | "get_mut" => {
+                    self.data_of(&call.receiver)
+                }
+                _ => None,
+            },
+            Expr::Call(call) => call.args.first().and_then(|arg| self.data_of(arg)),
+            expr => self.buffers.get(&scope::ident(expr)?).cloned(),
+        }
+    }
+
+    /// Records that `expr`, if it is part of an account's data, is zeroed.
+    fn zero(&mut self, expr: &Expr) {
+        self.zeroed.extend(self.data_of(expr));
+    }
+
+    fn drain(&mut self, account: String, span: Span) {
+        self.drained.entry(account).or_insert(span);
+    }
+}
+
+// Macro arguments are parsed while walking, so nothing visited is kept
+impl<'v> Visit<'v> for Closing<'_, '_> {
+    fn visit_local(&mut self, local: &'v syn::Local) {
+        visit::visit_local(self, local);
+        let Some(account) = local
+            .init
+            .as_ref()
+            .and_then(|init| self.data_of(&init.expr))
+        else {
+            return;
+        };
+        for name in scope::bindings(&local.pat) {
+            self.buffers.insert(name, account.clone());
+        }
+    }
+
+    fn visit_expr_for_loop(&mut self, expr: &'v syn::ExprForLoop) {
+        if let Some(account) = self.data_of(&expr.expr) {
+            for name in scope::bindings(&expr.pat) {
+                self.buffers.insert(name, account.clone());
+            }
+        }
+        visit::visit_expr_for_loop(self, expr);
+    }
+
+    fn visit_expr_assign(&mut self, assign: &'v syn::ExprAssign) {
+        if is_zero(&assign.right) {
+            match self.lamports_mut(&assign.left) {
+                Some(account) => self.drain(account, assign.span()),
+                // `*byte = 0`, `data[i] = 0`
+                None => self.zero(&assign.left),
+            }
+        }
+        visit::visit_expr_assign(self, assign);
+    }
+
+    fn visit_expr_binary(&mut self, binary: &'v syn::ExprBinary) {
+        if let BinOp::SubAssign(_) = binary.op {
+            if let Some(account) = self.lamports_mut(&binary.left) {
+                if self.balance(&binary.right).as_ref() == Some(&account) {
+                    self.drain(account, binary.span());
+                }
+            }
+        }
+        visit::visit_expr_binary(self, binary);
+    }
+
+    fn visit_expr_method_call(&mut self, call: &'v syn::ExprMethodCall) {
+        let method = call.method.to_string();
+        let first = call.args.first();
+        match method.as_str() {
+            "sub_lamports" => {
+                if let Some(account) = self.function.account(&call.receiver) {
+                    if first.and_then(|arg| self.balance(arg)).as_ref() == Some(&account) {
+                        self.drain(account, call.span());
+                    }
+                }
+            }
+            "assign" => self.cleared.extend(self.function.account(&call.receiver)),
+            "realloc" | "resize" if first.is_some_and(is_zero) => {
+                self.cleared.extend(self.function.account(&call.receiver))
+            }
+            "fill" if first.is_some_and(is_zero) => self.zero(&call.receiver),
+            // `data[..8].copy_from_slice(&CLOSED_ACCOUNT_DISCRIMINATOR)`
+            _ if call.args.iter().any(closed_discriminator) => self.zero(&call.receiver),
+            _ => {}
+        }
+        visit::visit_expr_method_call(self, call);
+    }
+
+    fn visit_expr_call(&mut self, call: &'v syn::ExprCall) {
+        // `sol_memset(&mut data, 0, len)`
+        if let (Expr::Path(path), Some(dst)) = (&*call.func, call.args.first()) {
+            if path
+                .path
+                .segments
+                .last()
+                .is_some_and(|s| s.ident == "sol_memset")
+            {
+                self.zero(dst);
+            }
+        }
+        visit::visit_expr_call(self, call);
+    }
+
+    fn visit_macro(&mut self, mac: &'v syn::Macro) {
+        if let Ok(args) = mac.parse_body_with(Punctuated::<Expr, Token![,]>::parse_terminated) {
+            args.iter().for_each(|arg| self.visit_expr(arg));
+        }
+    }
+
+    fn visit_item_fn(&mut self, _: &'v syn::ItemFn) {}
+}
+
+fn is_zero(expr: &Expr) -> bool {
+    matches!(
+        scope::strip(expr),
+        Expr::Lit(syn::ExprLit { lit: syn::Lit::Int(int), .. }) if int.base10_digits() == "0"
+    )
+}
+
+/// Whether `expr` is `CLOSED_ACCOUNT_DISCRIMINATOR`, borrowed or not.
+fn closed_discriminator(expr: &Expr) -> bool {
+    matches!(
+        scope::strip(expr),
+        Expr::Path(path) if path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "CLOSED_ACCOUNT_DISCRIMINATOR")
+    )
+}
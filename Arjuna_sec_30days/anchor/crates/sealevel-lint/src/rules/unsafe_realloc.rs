@@ -0,0 +1,277 @@
+//! `unsafe-realloc`: `realloc` that can re-expose old bytes, or grow an
+//! account without paying for the rent.
+//!
+//! Shrinking an account's data does not clear the bytes past the new end.
+//! Growing it again in the same transaction with `zero_init` set to `false`
+//! (or `realloc::zero = false`) hands those stale bytes back as part of the
+//! account. A length that can move either way is treated as one that will.
+//! A native `realloc` that can grow also needs the account topped up to the
+//! new rent-exempt minimum, by a transfer to it or a write to its lamports;
+//! working out a `minimum_balance` pays for nothing on its own. Anchor's
+//! `realloc::payer` does that itself.
+
+use std::collections::HashSet;
+
+use syn::{
+    punctuated::Punctuated,
+    spanned::Spanned,
+    visit::{self, Visit},
+    BinOp, Expr, Token,
+};
+
+use crate::{
+    anchor,
+    scope::{self, FnScope},
+    Context, Diagnostic, Rule,
+};
+
+pub struct UnsafeRealloc;
+
+impl Rule for UnsafeRealloc {
+    fn name(&self) -> &'static str {
+        "unsafe-realloc"
+    }
+
+    fn description(&self) -> &'static str {
+        "realloc without zero_init on a length that can shrink, or growth with no rent top-up"
+    }
+
+    fn check(&self, file: &syn::File, _cx: &Context) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        for accounts in anchor::accounts_structs(file) {
+            for field in &accounts.fields {
+                let (Some(realloc), Some(zero)) = (
+                    field.constraint("realloc"),
+                    field.constraint("realloc::zero"),
+                ) else {
+                    continue;
+                };
+                let Ok(len) = syn::parse2::<Expr>(realloc.value.clone()) else {
+                    continue;
+                };
+                if zero.value.to_string() == "false" && !grows_only(&len) {
+                    diagnostics.push(Diagnostic {
+                        span: zero.span,
+                        message: format!(
+                            "`{}` in `{}` is reallocated with `realloc::zero = false` to a \
+                             length that can shrink as well as grow; growing back after a \
+                             shrink in the same transaction exposes the old bytes. Use \
+                             `realloc::zero = true`",
+                            field.name, accounts.name
+                        ),
+                    });
+                }
+            }
+        }
+
+        for function in scope::functions(file) {
+            if function.test {
+                continue;
+            }
+            let mut reallocs = Reallocs(Vec::new());
+            reallocs.visit_block(function.block);
+            if reallocs.0.is_empty() {
+                continue;
+            }
+            let mut top_ups = TopUps {
+                function: &function,
+                accounts: HashSet::new(),
+            };
+            top_ups.visit_block(function.block);
+            for call in reallocs.0 {
+                let (Some(len), Some(zero)) = (call.args.first(), call.args.iter().nth(1)) else {
+                    continue;
+                };
+                let account = function
+                    .account(&call.receiver)
+                    .unwrap_or_else(|| "the account".to_string());
+                if is_false(zero) && !grows_only(len) {
+                    diagnostics.push(Diagnostic {
+                        span: call.span(),
+                        message: format!(
+                            "`{account}` is reallocated in `{}` with `zero_init` false to a \
+                             length that can shrink as well as grow; growing back after a \
+                             shrink in the same transaction exposes the old bytes. Pass `true`",
+                            function.name
+                        ),
+                    });
+                }
+                if !shrinks_only(len) && !top_ups.accounts.contains(&account) {
+                    diagnostics.push(Diagnostic {
+                        span: call.span(),
+                        message: format!(
+                            "`{account}` can grow in `{}` but nothing tops up its lamports; \
+                             transfer the difference to the new `minimum_balance` first or the \
+                             account is no longer rent exempt",
+                            function.name
+                        ),
+                    });
+                }
+            }
+        }
+        diagnostics
+    }
+}
+
+/// `x.realloc(len, zero_init)` calls, macro arguments included.
+struct Reallocs(Vec<syn::ExprMethodCall>);
+
+// Macro arguments are parsed while walking, so calls are kept by value
+impl<'v> Visit<'v> for Reallocs {
+    fn visit_expr_method_call(&mut self, call: &'v syn::ExprMethodCall) {
+        if call.method == "realloc" && call.args.len() == 2 {
+            self.0.push(call.clone());
+        }
+        visit::visit_expr_method_call(self, call);
+    }
+
+    fn visit_macro(&mut self, mac: &'v syn::Macro) {
+        if let Ok(args) = mac.parse_body_with(Punctuated::<Expr, Token![,]>::parse_terminated) {
+            args.iter().for_each(|arg| self.visit_expr(arg));
+        }
+    }
+
+    fn visit_item_fn(&mut self, _: &'v syn::ItemFn) {}
+}
+
+/// The accounts a function pays into: ones a transfer names, or whose
+/// lamports are added to.
+struct TopUps<'a, 'ast> {
+    function: &'a FnScope<'ast>,
+    accounts: HashSet<String>,
+}
+
+// Macro arguments are parsed while walking, so nothing visited is kept
+impl<'v> Visit<'v> for TopUps<'_, '_> {
+    fn visit_expr_call(&mut self, call: &'v syn::ExprCall) {
+        if let Expr::Path(path) = &*call.func {
+            if path
+                .path
+                .segments
+                .last()
+                .is_some_and(|s| s.ident == "transfer")
+            {
+                for arg in &call.args {
+                    self.accounts.extend(accounts_in(self.function, arg));
+                }
+            }
+        }
+        visit::visit_expr_call(self, call);
+    }
+
+    fn visit_expr_method_call(&mut self, call: &'v syn::ExprMethodCall) {
+        if call.method == "add_lamports" {
+            self.accounts.extend(self.function.account(&call.receiver));
+        }
+        visit::visit_expr_method_call(self, call);
+    }
+
+    fn visit_expr_binary(&mut self, binary: &'v syn::ExprBinary) {
+        // `**x.try_borrow_mut_lamports()? += n`
+        if matches!(binary.op, BinOp::AddAssign(_)) && mentions_lamports(&binary.left) {
+            self.accounts
+                .extend(accounts_in(self.function, &binary.left));
+        }
+        visit::visit_expr_binary(self, binary);
+    }
+
+    fn visit_macro(&mut self, mac: &'v syn::Macro) {
+        if let Ok(args) = mac.parse_body_with(Punctuated::<Expr, Token![,]>::parse_terminated) {
+            args.iter().for_each(|arg| self.visit_expr(arg));
+        }
+    }
+
+    fn visit_item_fn(&mut self, _: &'v syn::ItemFn) {}
+}
+
+/// Every account `expr` refers to, or whose key it reads.
+fn accounts_in(function: &FnScope, expr: &Expr) -> HashSet<String> {
+    struct Accounts<'a, 'ast>(&'a FnScope<'ast>, HashSet<String>);
+    impl<'v> Visit<'v> for Accounts<'_, '_> {
+        fn visit_expr(&mut self, expr: &'v Expr) {
+            let key = scope::key_of(expr).map(|key| key.name().to_string());
+            self.1.extend(key.or_else(|| self.0.account(expr)));
+            visit::visit_expr(self, expr);
+        }
+    }
+    let mut accounts = Accounts(function, HashSet::new());
+    accounts.visit_expr(expr);
+    accounts.1
+}
+
+/// Whether `len` is the current length plus something, so it never shrinks.
+fn grows_only(len: &Expr) -> bool {
+    matches!(
+        scope::strip(len),
+        Expr::Binary(binary) if matches!(binary.op, BinOp::Add(_))
+            && (current_len(&binary.left) || current_len(&binary.right))
+    )
+}
+
+/// Whether `len` is zero or the current length minus something.
+fn shrinks_only(len: &Expr) -> bool {
+    match scope::strip(len) {
+        Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Int(int),
+            ..
+        }) => int.base10_digits() == "0",
+        Expr::Binary(binary) => matches!(binary.op, BinOp::Sub(_)) && current_len(&binary.left),
+        _ => false,
+    }
+}
+
+/// Whether `expr` reads an account's current data length: `x.data_len()`
+/// or `x.data.borrow().len()`.
+fn current_len(expr: &Expr) -> bool {
+    struct Len(bool);
+    impl<'ast> Visit<'ast> for Len {
+        fn visit_expr_method_call(&mut self, call: &'ast syn::ExprMethodCall) {
+            self.0 |= call.method == "data_len"
+                || (call.method == "len" && mentions_field(&call.receiver, "data"));
+            visit::visit_expr_method_call(self, call);
+        }
+    }
+    let mut len = Len(false);
+    len.visit_expr(expr);
+    len.0
+}
+
+fn mentions_lamports(expr: &Expr) -> bool {
+    mentions_field(expr, "lamports") || {
+        struct Borrow(bool);
+        impl<'ast> Visit<'ast> for Borrow {
+            fn visit_expr_method_call(&mut self, call: &'ast syn::ExprMethodCall) {
+                self.0 |= call.method == "try_borrow_mut_lamports";
+                visit::visit_expr_method_call(self, call);
+            }
+        }
+        let mut borrow = Borrow(false);
+        borrow.visit_expr(expr);
+        borrow.0
+    }
+}
+
+/// Whether `expr` reads a field called `name` anywhere.
+fn mentions_field(expr: &Expr, name: &str) -> bool {
+    struct Field<'a>(&'a str, bool);
+    impl<'ast> Visit<'ast> for Field<'_> {
+        fn visit_expr_field(&mut self, field: &'ast syn::ExprField) {
+            self.1 |= matches!(&field.member, syn::Member::Named(member) if member == self.0);
+            visit::visit_expr_field(self, field);
+        }
+    }
+    let mut field = Field(name, false);
+    field.visit_expr(expr);
+    field.1
+}
+
+fn is_false(expr: &Expr) -> bool {
+    matches!(
+        scope::strip(expr),
+        Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Bool(value),
+            ..
+        }) if !value.value
+    )
+}
@@ -0,0 +1,145 @@
+//! `user-bump`: `create_program_address` with a bump the caller chose.
+//!
+//! Any bump that yields an off-curve address is accepted by
+//! `create_program_address`, so a caller-supplied one lets an attacker pick
+//! among up to 256 addresses for the same seeds. Only the canonical bump from
+//! `find_program_address`, or one stored when the account was created with it,
+//! pins the PDA.
+
+use std::collections::HashSet;
+
+use syn::{
+    spanned::Spanned,
+    visit::{self, Visit},
+    Expr, ExprArray,
+};
+
+use crate::{
+    scope::{self, FnScope},
+    Context, Diagnostic, Rule,
+};
+
+pub struct UserBump;
+
+impl Rule for UserBump {
+    fn name(&self) -> &'static str {
+        "user-bump"
+    }
+
+    fn description(&self) -> &'static str {
+        "create_program_address seeded with a bump from a parameter or instruction data"
+    }
+
+    fn check(&self, file: &syn::File, _cx: &Context) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        for function in scope::functions(file) {
+            let mut calls = Calls(Vec::new());
+            calls.visit_block(function.block);
+            for (span, seeds) in calls.0 {
+                let mut bumps = Vec::new();
+                seed_bumps(&function, seeds, &mut HashSet::new(), &mut bumps);
+                // One finding per call, naming the bump closest to where it came from
+                let tainted = bumps.iter().rev().find_map(|bump| {
+                    origin(&function, bump, &mut HashSet::new()).map(|param| (bump, param))
+                });
+                if let Some((bump, param)) = tainted {
+                    diagnostics.push(Diagnostic {
+                        span,
+                        message: format!(
+                            "bump `{bump}` in the seeds of `create_program_address` comes \
+                                 from parameter `{param}` of `{}`; derive it with \
+                                 `find_program_address` or use the stored canonical bump",
+                            function.name
+                        ),
+                    });
+                }
+            }
+        }
+        diagnostics
+    }
+}
+
+/// The seeds argument of every `create_program_address` call in one function.
+struct Calls<'ast>(Vec<(proc_macro2::Span, &'ast Expr)>);
+
+impl<'ast> Visit<'ast> for Calls<'ast> {
+    fn visit_expr(&mut self, expr: &'ast Expr) {
+        let seeds = match expr {
+            Expr::Call(call) => call.args.first(),
+            Expr::MethodCall(call) => call.args.first(),
+            _ => None,
+        };
+        if let Some(seeds) = seeds {
+            if scope::called_fn(expr).as_deref() == Some("create_program_address") {
+                self.0.push((expr.span(), seeds));
+            }
+        }
+        visit::visit_expr(self, expr);
+    }
+
+    fn visit_item_fn(&mut self, _: &'ast syn::ItemFn) {}
+}
+
+/// Names of the locals that end up as the bump in `seeds`: the element of a
+/// one-byte array like `&[bump]`, or anything named like a bump, followed
+/// through the locals `seeds` is built from.
+fn seed_bumps(
+    function: &FnScope,
+    seeds: &Expr,
+    seen: &mut HashSet<String>,
+    bumps: &mut Vec<String>,
+) {
+    struct Arrays(Vec<String>);
+    impl<'ast> Visit<'ast> for Arrays {
+        fn visit_expr_array(&mut self, array: &'ast ExprArray) {
+            if let [element] = array.elems.iter().collect::<Vec<_>>()[..] {
+                self.0.extend(scope::idents(element));
+            }
+            visit::visit_expr_array(self, array);
+        }
+    }
+    let mut arrays = Arrays(Vec::new());
+    arrays.visit_expr(seeds);
+
+    let names = scope::idents(seeds);
+    let named = names.iter().filter(|name| name.contains("bump")).cloned();
+    for bump in arrays.0.into_iter().chain(named) {
+        if !bumps.contains(&bump) {
+            bumps.push(bump);
+        }
+    }
+
+    for name in names {
+        if !seen.insert(name.clone()) {
+            continue;
+        }
+        let defs = function.defs.get(&name).into_iter().flatten();
+        for def in defs.filter(|def| !is_canonical(def)) {
+            seed_bumps(function, def, seen, bumps);
+        }
+    }
+}
+
+/// The parameter `name` is computed from, if it is one the caller controls.
+/// Values from `find_program_address` are canonical and end the search.
+fn origin(function: &FnScope, name: &str, seen: &mut HashSet<String>) -> Option<String> {
+    if !seen.insert(name.to_string()) {
+        return None;
+    }
+    if let Some(defs) = function.defs.get(name) {
+        return defs
+            .iter()
+            .filter(|def| !is_canonical(def))
+            .flat_map(|def| scope::idents(def))
+            .find_map(|ident| origin(function, &ident, seen));
+    }
+    let ty = function.param(name)?;
+    let user_supplied = scope::last_segment(ty).as_deref() == Some("u8")
+        || scope::is_byte_slice(ty)
+        || (function.is_anchor_handler() && scope::last_segment(ty).as_deref() != Some("Context"));
+    user_supplied.then(|| name.to_string())
+}
+
+fn is_canonical(def: &Expr) -> bool {
+    scope::called_fn(def).as_deref() == Some("find_program_address")
+}
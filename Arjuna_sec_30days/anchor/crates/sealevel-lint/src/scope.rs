@@ -0,0 +1,445 @@
+//! The per-function view rules work from: parameters, and what each local was
+//! assigned, so a value can be traced back to where it came from.
+
+use std::collections::{HashMap, HashSet};
+
+use syn::{
+    visit::{self, Visit},
+    Block, Expr, ExprPath, FnArg, ImplItemFn, ItemFn, Pat, Signature, Type,
+};
+
+/// One function with a body.
+pub(crate) struct FnScope<'ast> {
+    pub name: String,
+    /// The type of `self` for methods, e.g. `SecureAccounts`.
+    pub self_ty: Option<String>,
+    /// A `#[test]`, or in a `#[cfg(test)]` module.
+    pub test: bool,
+    /// Declared in the `#[program]` module.
+    pub program: bool,
+    pub block: &'ast Block,
+    /// Parameter names with their types, `self` excluded.
+    pub params: Vec<(String, &'ast Type)>,
+    /// Every expression assigned to a local: `let` initializers, plain
+    /// assignments and the arguments of `push`/`extend` calls on it.
+    pub defs: HashMap<String, Vec<&'ast Expr>>,
+}
+
+impl<'ast> FnScope<'ast> {
+    fn new(sig: &'ast Signature, block: &'ast Block) -> Self {
+        let mut params = Vec::new();
+        for input in &sig.inputs {
+            if let FnArg::Typed(typed) = input {
+                for name in bindings(&typed.pat) {
+                    params.push((name, &*typed.ty));
+                }
+            }
+        }
+        let mut defs = Defs::default();
+        defs.visit_block(block);
+        FnScope {
+            name: sig.ident.to_string(),
+            self_ty: None,
+            test: false,
+            program: false,
+            block,
+            params,
+            defs: defs.0,
+        }
+    }
+
+    pub fn param(&self, name: &str) -> Option<&'ast Type> {
+        self.params
+            .iter()
+            .find(|(param, _)| param == name)
+            .map(|(_, ty)| *ty)
+    }
+
+    /// Whether this is an Anchor instruction handler, i.e. takes a `Context<_>`.
+    pub fn is_anchor_handler(&self) -> bool {
+        self.params
+            .iter()
+            .any(|(_, ty)| last_segment(ty).as_deref() == Some("Context"))
+    }
+
+    /// Whether instructions enter the program here: a function of the
+    /// `#[program]` module, an Anchor handler, or a native processor taking
+    /// the accounts and the instruction data.
+    pub fn is_instruction_handler(&self) -> bool {
+        let native = self.params.iter().any(|(_, ty)| is_account_slice(ty))
+            && self.params.iter().any(|(_, ty)| is_byte_slice(ty));
+        self.program || self.is_anchor_handler() || native
+    }
+
+    /// The name of the account `expr` ends up referring to, locals followed
+    /// back to the account they were made from: `data_account` after
+    /// `let account = ctx.accounts.data_account.to_account_info()`. A local
+    /// taken out of an `&[AccountInfo]` parameter, by `&accounts[i]` or
+    /// `let [a, ..] = accounts`, is its own account.
+    pub fn account(&self, expr: &Expr) -> Option<String> {
+        let mut name = account(expr)?.name().to_string();
+        for _ in 0..8 {
+            let Some(next) = self
+                .defs
+                .get(&name)
+                .and_then(|defs| defs.first())
+                .and_then(|def| account(def))
+            else {
+                break;
+            };
+            if next.name() == name || self.param(next.name()).is_some_and(is_account_slice) {
+                break;
+            }
+            name = next.name().to_string();
+        }
+        Some(name)
+    }
+
+    /// The accounts struct `ctx.accounts` is, `Foo` for a `Context<Foo>` parameter.
+    pub fn accounts_struct(&self) -> Option<String> {
+        self.params.iter().find_map(|(_, ty)| {
+            let ty = match ty {
+                Type::Reference(reference) => &*reference.elem,
+                ty => ty,
+            };
+            let Type::Path(path) = ty else {
+                return None;
+            };
+            let segment = path.path.segments.last()?;
+            if segment.ident != "Context" {
+                return None;
+            }
+            let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
+                return None;
+            };
+            args.args.iter().find_map(|arg| match arg {
+                syn::GenericArgument::Type(ty) => last_segment(ty),
+                _ => None,
+            })
+        })
+    }
+}
+
+/// Every function with a body in `file`, including methods and functions in
+/// inline modules.
+pub(crate) fn functions(file: &syn::File) -> Vec<FnScope<'_>> {
+    let mut collector = Functions {
+        scopes: Vec::new(),
+        self_ty: None,
+        in_test: false,
+        in_program: false,
+    };
+    collector.visit_file(file);
+    collector.scopes
+}
+
+/// The functions instructions run: the instruction handlers of `functions`
+/// and every function of the same file they call, directly or not. Calls are
+/// matched by name, and calls into other files are not followed, so in a file
+/// without handlers, such as a crate root of shared helpers, every function
+/// other than a test counts as run.
+pub(crate) fn reachable<'a, 'ast>(functions: &'a [FnScope<'ast>]) -> Vec<&'a FnScope<'ast>> {
+    let handlers = functions
+        .iter()
+        .any(|function| !function.test && function.is_instruction_handler());
+    let mut reached: Vec<bool> = functions
+        .iter()
+        .map(|function| !function.test && (!handlers || function.is_instruction_handler()))
+        .collect();
+    let mut queue: Vec<usize> = (0..functions.len()).filter(|&i| reached[i]).collect();
+    while let Some(caller) = queue.pop() {
+        let called = calls(functions[caller].block);
+        for (i, function) in functions.iter().enumerate() {
+            if !reached[i] && !function.test && called.contains(&function.name) {
+                reached[i] = true;
+                queue.push(i);
+            }
+        }
+    }
+    functions
+        .iter()
+        .zip(reached)
+        .filter(|(_, reached)| *reached)
+        .map(|(function, _)| function)
+        .collect()
+}
+
+/// The names of the functions and methods `block` calls.
+fn calls(block: &Block) -> HashSet<String> {
+    struct Calls(HashSet<String>);
+    impl<'ast> Visit<'ast> for Calls {
+        fn visit_expr(&mut self, expr: &'ast Expr) {
+            if matches!(expr, Expr::Call(_) | Expr::MethodCall(_)) {
+                self.0.extend(called_fn(expr));
+            }
+            visit::visit_expr(self, expr);
+        }
+
+        fn visit_item_fn(&mut self, _: &'ast ItemFn) {}
+    }
+    let mut calls = Calls(HashSet::new());
+    calls.visit_block(block);
+    calls.0
+}
+
+struct Functions<'ast> {
+    scopes: Vec<FnScope<'ast>>,
+    self_ty: Option<String>,
+    in_test: bool,
+    in_program: bool,
+}
+
+impl<'ast> Visit<'ast> for Functions<'ast> {
+    fn visit_item_mod(&mut self, item: &'ast syn::ItemMod) {
+        let (in_test, in_program) = (self.in_test, self.in_program);
+        self.in_test |= has_attr(&item.attrs, "cfg", "test");
+        self.in_program = has_attr(&item.attrs, "program", "");
+        visit::visit_item_mod(self, item);
+        (self.in_test, self.in_program) = (in_test, in_program);
+    }
+
+    fn visit_item_fn(&mut self, item: &'ast ItemFn) {
+        let mut scope = FnScope::new(&item.sig, &item.block);
+        scope.test = self.in_test || has_attr(&item.attrs, "test", "");
+        scope.program = self.in_program;
+        self.scopes.push(scope);
+        visit::visit_item_fn(self, item);
+    }
+
+    fn visit_item_impl(&mut self, item: &'ast syn::ItemImpl) {
+        let outer = self
+            .self_ty
+            .replace(last_segment(&item.self_ty).unwrap_or_default());
+        visit::visit_item_impl(self, item);
+        self.self_ty = outer;
+    }
+
+    fn visit_impl_item_fn(&mut self, item: &'ast ImplItemFn) {
+        let mut scope = FnScope::new(&item.sig, &item.block);
+        scope.self_ty = self.self_ty.clone();
+        scope.test = self.in_test;
+        self.scopes.push(scope);
+        visit::visit_impl_item_fn(self, item);
+    }
+}
+
+/// Whether `attrs` has `#[name]`, or `#[name(arg)]` if `arg` is not empty.
+fn has_attr(attrs: &[syn::Attribute], name: &str, arg: &str) -> bool {
+    attrs.iter().any(|attr| {
+        attr.path().is_ident(name)
+            && (arg.is_empty()
+                || attr
+                    .meta
+                    .require_list()
+                    .is_ok_and(|list| list.tokens.to_string() == arg))
+    })
+}
+
+#[derive(Default)]
+struct Defs<'ast>(HashMap<String, Vec<&'ast Expr>>);
+
+impl<'ast> Visit<'ast> for Defs<'ast> {
+    fn visit_local(&mut self, local: &'ast syn::Local) {
+        if let Some(init) = &local.init {
+            for name in bindings(&local.pat) {
+                self.0.entry(name).or_default().push(&init.expr);
+            }
+        }
+        visit::visit_local(self, local);
+    }
+
+    fn visit_expr_assign(&mut self, assign: &'ast syn::ExprAssign) {
+        if let Some(name) = ident(&assign.left) {
+            self.0.entry(name).or_default().push(&assign.right);
+        }
+        visit::visit_expr_assign(self, assign);
+    }
+
+    fn visit_expr_method_call(&mut self, call: &'ast syn::ExprMethodCall) {
+        let method = call.method.to_string();
+        if matches!(
+            method.as_str(),
+            "push" | "extend" | "extend_from_slice" | "insert"
+        ) {
+            if let Some(name) = ident(&call.receiver) {
+                self.0.entry(name).or_default().extend(call.args.iter());
+            }
+        }
+        visit::visit_expr_method_call(self, call);
+    }
+
+    // Nested functions get their own scope
+    fn visit_item_fn(&mut self, _: &'ast ItemFn) {}
+}
+
+/// Names bound by a pattern.
+pub(crate) fn bindings(pat: &Pat) -> Vec<String> {
+    let mut names = Vec::new();
+    collect_bindings(pat, &mut names);
+    names
+}
+
+fn collect_bindings(pat: &Pat, names: &mut Vec<String>) {
+    match pat {
+        Pat::Ident(pat) => {
+            names.push(pat.ident.to_string());
+            if let Some((_, sub)) = &pat.subpat {
+                collect_bindings(sub, names);
+            }
+        }
+        Pat::Tuple(pat) => pat.elems.iter().for_each(|p| collect_bindings(p, names)),
+        Pat::TupleStruct(pat) => pat.elems.iter().for_each(|p| collect_bindings(p, names)),
+        Pat::Slice(pat) => pat.elems.iter().for_each(|p| collect_bindings(p, names)),
+        Pat::Struct(pat) => pat
+            .fields
+            .iter()
+            .for_each(|field| collect_bindings(&field.pat, names)),
+        Pat::Reference(pat) => collect_bindings(&pat.pat, names),
+        Pat::Type(pat) => collect_bindings(&pat.pat, names),
+        Pat::Paren(pat) => collect_bindings(&pat.pat, names),
+        Pat::Or(pat) => pat.cases.iter().for_each(|p| collect_bindings(p, names)),
+        _ => {}
+    }
+}
+
+/// The name if `expr` is a plain local or parameter, e.g. `bump`.
+pub(crate) fn ident(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Path(ExprPath {
+            path, qself: None, ..
+        }) => path.get_ident().map(ToString::to_string),
+        _ => None,
+    }
+}
+
+/// Every plain local or parameter name used in `expr`.
+pub(crate) fn idents(expr: &Expr) -> Vec<String> {
+    struct Idents(Vec<String>);
+    impl<'ast> Visit<'ast> for Idents {
+        fn visit_expr_path(&mut self, path: &'ast ExprPath) {
+            if let Some(name) = path.path.get_ident() {
+                self.0.push(name.to_string());
+            }
+        }
+    }
+    let mut idents = Idents(Vec::new());
+    idents.visit_expr(expr);
+    idents.0
+}
+
+/// An account as code refers to it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum AccountRef {
+    /// A local or parameter, `token_program`.
+    Local(String),
+    /// A field of an accounts struct, `ctx.accounts.token_program` or
+    /// `self.token_program`.
+    Field(String),
+}
+
+impl AccountRef {
+    pub fn name(&self) -> &str {
+        match self {
+            AccountRef::Local(name) | AccountRef::Field(name) => name,
+        }
+    }
+}
+
+/// The account `expr` refers to, with `&`, `*`, `.clone()`, `.as_ref()` and
+/// `.to_account_info()` looked through.
+pub(crate) fn account(expr: &Expr) -> Option<AccountRef> {
+    match strip(expr) {
+        Expr::Path(_) => ident(strip(expr)).map(AccountRef::Local),
+        Expr::Field(field) => match &field.member {
+            syn::Member::Named(name) => Some(AccountRef::Field(name.to_string())),
+            syn::Member::Unnamed(_) => None,
+        },
+        Expr::MethodCall(call)
+            if call.args.is_empty()
+                && matches!(
+                    call.method.to_string().as_str(),
+                    "clone" | "as_ref" | "to_account_info"
+                ) =>
+        {
+            account(&call.receiver)
+        }
+        _ => None,
+    }
+}
+
+/// The account whose key `expr` reads: `token_program` for
+/// `*token_program.key` or `&ctx.accounts.token_program.key()`.
+pub(crate) fn key_of(expr: &Expr) -> Option<AccountRef> {
+    match strip(expr) {
+        Expr::Field(field) if matches!(&field.member, syn::Member::Named(name) if name == "key") => {
+            account(&field.base)
+        }
+        Expr::MethodCall(call) if call.method == "key" && call.args.is_empty() => {
+            account(&call.receiver)
+        }
+        _ => None,
+    }
+}
+
+/// `expr` without the references, dereferences and parentheses around it.
+pub(crate) fn strip(mut expr: &Expr) -> &Expr {
+    loop {
+        expr = match expr {
+            Expr::Reference(reference) => &reference.expr,
+            Expr::Unary(syn::ExprUnary {
+                op: syn::UnOp::Deref(_),
+                expr,
+                ..
+            }) => expr,
+            Expr::Paren(paren) => &paren.expr,
+            Expr::Group(group) => &group.expr,
+            _ => return expr,
+        }
+    }
+}
+
+/// The called function's name if `expr` is a call, e.g.
+/// `create_program_address` for `Pubkey::create_program_address(..)`.
+pub(crate) fn called_fn(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Call(call) => match &*call.func {
+            Expr::Path(path) => path.path.segments.last().map(|s| s.ident.to_string()),
+            _ => None,
+        },
+        Expr::MethodCall(call) => Some(call.method.to_string()),
+        Expr::Try(expr) => called_fn(&expr.expr),
+        _ => None,
+    }
+}
+
+/// The last path segment of a type, references and slices looked through:
+/// `Context` for `Context<'_, '_, '_, 'info, Foo<'info>>`.
+pub(crate) fn last_segment(ty: &Type) -> Option<String> {
+    match ty {
+        Type::Path(path) => path.path.segments.last().map(|s| s.ident.to_string()),
+        Type::Reference(reference) => last_segment(&reference.elem),
+        _ => None,
+    }
+}
+
+/// Whether `ty` is `&[u8]`, the shape of native instruction data.
+pub(crate) fn is_byte_slice(ty: &Type) -> bool {
+    match ty {
+        Type::Reference(reference) => match &*reference.elem {
+            Type::Slice(slice) => last_segment(&slice.elem).as_deref() == Some("u8"),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// Whether `ty` is `&[AccountInfo]`, the accounts of a native instruction.
+pub(crate) fn is_account_slice(ty: &Type) -> bool {
+    match ty {
+        Type::Reference(reference) => match &*reference.elem {
+            Type::Slice(slice) => last_segment(&slice.elem).as_deref() == Some("AccountInfo"),
+            _ => false,
+        },
+        _ => false,
+    }
+}
@@ -0,0 +1,31 @@
+use std::path::Path;
+
+use sealevel_lint::{lint_path, rules, Finding};
+
+const PROGRAM: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../../programs/ata/src");
+
+fn lint(file: &str) -> (String, Vec<Finding>) {
+    let path = Path::new(PROGRAM).join(file);
+    let findings = lint_path(&path, &[rules::find("ata-init").unwrap()]).unwrap();
+    (std::fs::read_to_string(path).unwrap(), findings)
+}
+
+#[test]
+fn flags_init_on_an_associated_token_account() {
+    let (source, findings) = lint("insecure.rs");
+    let line = source
+        .lines()
+        .position(|line| line.trim() == "init,")
+        .unwrap()
+        + 1;
+    assert_eq!(findings.len(), 1, "{findings:#?}");
+    assert_eq!(findings[0].line, line);
+    assert!(findings[0].message.contains("`ata` in `CreateAta`"));
+    assert!(findings[0].message.contains("init_if_needed"));
+}
+
+#[test]
+fn passes_init_if_needed() {
+    let (_, findings) = lint("secure.rs");
+    assert_eq!(findings, []);
+}
@@ -0,0 +1,98 @@
+use std::path::Path;
+
+use sealevel_lint::{lint_source, rules, Finding};
+
+fn lint(source: &str) -> Vec<Finding> {
+    let rule = rules::find("close-target").unwrap();
+    lint_source(source, Path::new("inline.rs"), &[rule]).unwrap()
+}
+
+const CLOSE: &str = r#"
+pub fn close(ctx: Context<Close>) -> Result<()> {
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct Close<'info> {
+    #[account(mut, close = receiver)]
+    pub data_account: Account<'info, Data>,
+    #[account(mut)]
+    pub receiver: UncheckedAccount<'info>,
+    pub authority: Signer<'info>,
+}
+"#;
+
+#[test]
+fn flags_a_target_that_may_be_the_account() {
+    let findings = lint(CLOSE);
+    let line = CLOSE
+        .lines()
+        .position(|line| line.contains("close = receiver"))
+        .unwrap()
+        + 1;
+    assert_eq!(findings.len(), 1, "{findings:#?}");
+    assert_eq!(findings[0].line, line);
+    assert!(findings[0]
+        .message
+        .contains("`data_account` in `Close` is closed into `receiver`, which may be"));
+}
+
+#[test]
+fn flags_closing_into_itself() {
+    let findings = lint(&CLOSE.replace("close = receiver", "close = data_account"));
+    assert_eq!(findings.len(), 1, "{findings:#?}");
+    assert!(findings[0].message.contains("closed into itself"));
+}
+
+#[test]
+fn reads_the_target_as_an_expression() {
+    for target in ["close = ctx.accounts.receiver", "close=receiver"] {
+        let findings = lint(&CLOSE.replace("close = receiver", target));
+        assert_eq!(findings.len(), 1, "{target}: {findings:#?}");
+        assert!(findings[0].message.contains("closed into `receiver`"));
+    }
+    let findings = lint(&CLOSE.replace("close = receiver", "close = ctx.accounts.data_account"));
+    assert!(findings[0].message.contains("closed into itself"));
+}
+
+#[test]
+fn passes_targets_that_differ() {
+    // Only a target of the same type, or an unchecked one, can be flagged
+    assert_eq!(
+        lint(&CLOSE.replace("close = receiver", "close = authority")),
+        []
+    );
+    let source = CLOSE.replace(
+        "close = receiver",
+        "close = receiver, constraint = data_account.key() != receiver.key()",
+    );
+    assert_eq!(lint(&source), []);
+}
+
+#[test]
+fn only_instruction_handlers_keep_accounts_apart() {
+    let source = r#"
+#[program]
+pub mod vault {
+    use super::*;
+
+    pub fn close(ctx: Context<Close>) -> Result<()> {
+        require_keys_neq!(ctx.accounts.data_account.key(), ctx.accounts.receiver.key());
+        Ok(())
+    }
+}
+
+pub fn preview(ctx: Context<Close>) -> Result<()> {
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct Close<'info> {
+    #[account(mut, close = receiver)]
+    pub data_account: Account<'info, Data>,
+    #[account(mut)]
+    pub receiver: UncheckedAccount<'info>,
+}
+"#;
+    assert_eq!(lint(source), []);
+}
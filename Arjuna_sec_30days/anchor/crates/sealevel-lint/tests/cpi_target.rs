@@ -0,0 +1,212 @@
+use std::path::Path;
+
+use sealevel_lint::{lint_path, lint_source, rules, Finding};
+
+const PROGRAM: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../../programs/Arbitary_CPI/src"
+);
+
+fn lint(source: &str) -> Vec<Finding> {
+    let rule = rules::find("cpi-target").unwrap();
+    lint_source(source, Path::new("inline.rs"), &[rule]).unwrap()
+}
+
+#[test]
+fn flags_every_unchecked_cpi_target() {
+    let path = Path::new(PROGRAM).join("insecure.rs");
+    let findings = lint_path(&path, &[rules::find("cpi-target").unwrap()]).unwrap();
+
+    let source = std::fs::read_to_string(&path).unwrap();
+    let line_of = |needle: &str| {
+        source
+            .lines()
+            .position(|line| line.contains(needle))
+            .unwrap()
+            + 1
+    };
+    let lines: Vec<_> = findings.iter().map(|finding| finding.line).collect();
+    assert_eq!(
+        lines,
+        [
+            line_of("program_id: *token_program.key"),
+            line_of("&ctx.accounts.token_program.key()"),
+            line_of("Instruction::new_with_bytes(metadata_program.key()"),
+        ],
+        "{findings:#?}"
+    );
+    assert!(findings[0].message.contains("an `AccountInfo`"));
+    assert!(findings[1].message.contains("an `UncheckedAccount`"));
+}
+
+#[test]
+fn passes_program_types_and_checked_keys() {
+    let path = Path::new(PROGRAM).join("secure.rs");
+    let findings = lint_path(&path, &[rules::find("cpi-target").unwrap()]).unwrap();
+    assert_eq!(findings, []);
+}
+
+#[test]
+fn follows_locals_and_accepts_address_constraints() {
+    let findings = lint(
+        r#"
+        fn native(accounts: &[AccountInfo]) -> ProgramResult {
+            let iter = &mut accounts.iter();
+            let token_program = next_account_info(iter)?;
+            let program = *token_program.key;
+            let ix = Instruction::new_with_borsh(program, &(), vec![]);
+            invoke(&ix, &[])
+        }
+
+        fn checked(accounts: &[AccountInfo]) -> ProgramResult {
+            let iter = &mut accounts.iter();
+            let token_program = next_account_info(iter)?;
+            spl_token::check_program_account(token_program.key)?;
+            let ix = spl_token::instruction::burn(token_program.key, a, b, c, &[], 1)?;
+            invoke(&ix, &[])
+        }
+
+        pub fn pinned(ctx: Context<Pinned>) -> Result<()> {
+            let ix = Instruction::new_with_bytes(ctx.accounts.program.key(), &[], vec![]);
+            invoke(&ix, &[])?;
+            Ok(())
+        }
+
+        #[derive(Accounts)]
+        pub struct Pinned<'info> {
+            /// CHECK: pinned
+            #[account(executable, address = memo::ID @ ErrorCode::Memo)]
+            program: UncheckedAccount<'info>,
+        }
+    "#,
+    );
+    let lines: Vec<_> = findings.iter().map(|finding| finding.line).collect();
+    assert_eq!(lines, [6], "{findings:#?}");
+}
+
+#[test]
+fn resolves_imported_builders() {
+    let findings = lint(
+        r#"
+        use spl_token::instruction::{self as token_ix, transfer};
+        use anchor_lang::solana_program::instruction::Instruction as Ix;
+
+        fn imported(accounts: &[AccountInfo]) -> ProgramResult {
+            let iter = &mut accounts.iter();
+            let token_program = next_account_info(iter)?;
+            let ix = transfer(token_program.key, a, b, c, &[], 1)?;
+            invoke(&ix, &[])?;
+            let ix = token_ix::burn(token_program.key, a, b, c, &[], 1)?;
+            invoke(&ix, &[])?;
+            let ix = Ix::new_with_bytes(*token_program.key, &[], vec![]);
+            invoke(&ix, &[])
+        }
+
+        fn local(accounts: &[AccountInfo]) -> ProgramResult {
+            let iter = &mut accounts.iter();
+            let token_program = next_account_info(iter)?;
+            let ix = instruction::transfer(token_program.key, a, b, c, &[], 1)?;
+            invoke(&ix, &[])
+        }
+    "#,
+    );
+    let lines: Vec<_> = findings.iter().map(|finding| finding.line).collect();
+    assert_eq!(lines, [8, 10, 12], "{findings:#?}");
+}
+
+#[test]
+fn ignores_checks_after_the_cpi() {
+    let findings = lint(
+        r#"
+        fn late(accounts: &[AccountInfo]) -> ProgramResult {
+            let iter = &mut accounts.iter();
+            let token_program = next_account_info(iter)?;
+            let ix = Instruction::new_with_bytes(*token_program.key, &[], vec![]);
+            invoke(&ix, &[])?;
+            if *token_program.key != spl_token::ID {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+            Ok(())
+        }
+
+        fn early(accounts: &[AccountInfo]) -> ProgramResult {
+            let iter = &mut accounts.iter();
+            let token_program = next_account_info(iter)?;
+            if *token_program.key != spl_token::ID {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+            let ix = Instruction::new_with_bytes(*token_program.key, &[], vec![]);
+            invoke(&ix, &[])
+        }
+    "#,
+    );
+    let lines: Vec<_> = findings.iter().map(|finding| finding.line).collect();
+    assert_eq!(lines, [5], "{findings:#?}");
+}
+
+#[test]
+fn only_counts_checks_that_stop_the_instruction() {
+    let findings = lint(
+        r#"
+        fn unused(accounts: &[AccountInfo]) -> ProgramResult {
+            let iter = &mut accounts.iter();
+            let token_program = next_account_info(iter)?;
+            let _ok = *token_program.key == spl_token::ID;
+            if *token_program.key != spl_token::ID {
+                msg!("unexpected token program");
+            }
+            let ix = Instruction::new_with_bytes(*token_program.key, &[], vec![]);
+            invoke(&ix, &[])
+        }
+
+        fn guarded(accounts: &[AccountInfo]) -> ProgramResult {
+            let iter = &mut accounts.iter();
+            let token_program = next_account_info(iter)?;
+            let memo_program = next_account_info(iter)?;
+            if *token_program.key == spl_token::ID {
+                msg!("token program");
+            } else {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+            require!(spl_memo::check_id(memo_program.key), ErrorCode::Memo);
+            let ix = Instruction::new_with_bytes(*token_program.key, &[], vec![]);
+            invoke(&ix, &[])?;
+            let ix = Instruction::new_with_bytes(*memo_program.key, &[], vec![]);
+            invoke(&ix, &[])
+        }
+    "#,
+    );
+    let lines: Vec<_> = findings.iter().map(|finding| finding.line).collect();
+    assert_eq!(lines, [9], "{findings:#?}");
+}
+
+#[test]
+fn follows_accounts_out_of_the_slice() {
+    let findings = lint(
+        r#"
+        fn indexed(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+            let token_program = &accounts[3];
+            let ix = Instruction::new_with_bytes(*token_program.key, &[], vec![]);
+            invoke(&ix, &[])
+        }
+
+        fn destructured(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+            let [token_program, memo_program, ..] = accounts else {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            };
+            if *memo_program.key != spl_memo::ID {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+            let ix = Instruction::new_with_bytes(*token_program.key, &[], vec![]);
+            invoke(&ix, &[])?;
+            let ix = Instruction::new_with_bytes(*memo_program.key, &[], vec![]);
+            invoke(&ix, &[])
+        }
+    "#,
+    );
+    let lines: Vec<_> = findings.iter().map(|finding| finding.line).collect();
+    assert_eq!(lines, [4, 15], "{findings:#?}");
+    assert!(findings[0]
+        .message
+        .contains("`token_program`, an `AccountInfo`"));
+}
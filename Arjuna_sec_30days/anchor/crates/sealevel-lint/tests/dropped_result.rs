@@ -0,0 +1,100 @@
+use std::path::Path;
+
+use sealevel_lint::{lint_path, lint_source, rules, Finding};
+
+const PROGRAMS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../../programs");
+
+fn lint(file: &str) -> (String, Vec<Finding>) {
+    let path = Path::new(PROGRAMS).join(file);
+    let rule = rules::find("dropped-result").unwrap();
+    let findings = lint_path(&path, &[rule]).unwrap();
+    (std::fs::read_to_string(path).unwrap(), findings)
+}
+
+fn line_of(source: &str, needle: &str) -> usize {
+    source
+        .lines()
+        .position(|line| line.contains(needle))
+        .unwrap()
+        + 1
+}
+
+#[test]
+fn flags_an_error_turned_into_success() {
+    let (source, findings) = lint("bump_seed_canonicalization/src/insecure.rs");
+    assert_eq!(findings.len(), 1, "{findings:#?}");
+    assert_eq!(findings[0].line, line_of(&source, "Err::<(), _>"));
+    assert!(findings[0]
+        .message
+        .contains("`Ok(())` returned right after"));
+
+    let (_, findings) = lint("bump_seed_canonicalization/src/secure.rs");
+    assert_eq!(findings, []);
+}
+
+#[test]
+fn flags_a_cpi_without_question_mark() {
+    let (source, findings) = lint("Arbitary_CPI/src/insecure.rs");
+    assert_eq!(findings.len(), 1, "{findings:#?}");
+    assert_eq!(findings[0].line, line_of(&source, "        invoke("));
+    assert!(findings[0].message.contains("result of `invoke`"));
+
+    let (_, findings) = lint("Arbitary_CPI/src/secure.rs");
+    assert_eq!(findings, []);
+}
+
+#[test]
+fn knows_results_by_name() {
+    let source = r#"
+        pub fn handler(ctx: Context<Pay>, amount: u64) -> Result<()> {
+            anchor_spl::token::transfer(ctx.accounts.transfer_context(), amount);
+            settle(&ctx);
+            ctx.accounts.vault.reload();
+            Err(ErrorCode::Paused);
+            let _ = invoke(&ix, &[]);
+            log(amount);
+            if amount == 0 {
+                return Ok(());
+            }
+            Ok(())
+        }
+
+        fn settle(ctx: &Context<Pay>) -> ProgramResult {
+            Ok(())
+        }
+
+        fn log(amount: u64) {}
+    "#;
+    let rule = rules::find("dropped-result").unwrap();
+    let findings = lint_source(source, Path::new("inline.rs"), &[rule]).unwrap();
+    let lines: Vec<_> = findings.iter().map(|finding| finding.line).collect();
+    assert_eq!(lines, [3, 4, 5, 6, 7], "{findings:#?}");
+    assert!(findings[3]
+        .message
+        .contains("`Err(..)` is built and dropped"));
+}
+
+#[test]
+fn knows_helpers_by_module_and_import() {
+    let source = r#"
+        use anchor_spl::token::{self, mint_to as mint};
+        use crate::system_program;
+
+        pub fn handler(ctx: Context<Pay>, amount: u64) -> Result<()> {
+            token::transfer(ctx.accounts.transfer_context(), amount);
+            mint(ctx.accounts.mint_context(), amount);
+            system_program::seeds(&ctx);
+            token::amount(&ctx.accounts.vault);
+            invoke(&ix, &[]).ok();
+            drop(invoke_signed(&ix, &[], &[]));
+            let _ = anchor_lang::system_program::transfer(ctx.accounts.pay_context(), amount);
+            let _ = token::amount(&ctx.accounts.vault);
+            Ok(())
+        }
+    "#;
+    let rule = rules::find("dropped-result").unwrap();
+    let findings = lint_source(source, Path::new("inline.rs"), &[rule]).unwrap();
+    let lines: Vec<_> = findings.iter().map(|finding| finding.line).collect();
+    assert_eq!(lines, [6, 7, 10, 11, 12], "{findings:#?}");
+    assert!(findings[1].message.contains("result of `mint_to`"));
+}
@@ -0,0 +1,157 @@
+use std::path::Path;
+
+use sealevel_lint::{lint_path, lint_source, rules, Finding};
+
+fn lint(source: &str) -> Vec<Finding> {
+    let rule = rules::find("duplicate-mutable").unwrap();
+    lint_source(source, Path::new("inline.rs"), &[rule]).unwrap()
+}
+
+// The example of DuplicateMutableAccounts.md
+const UPDATE: &str = r#"
+use anchor_lang::prelude::*;
+
+#[program]
+pub mod duplicate {
+    use super::*;
+
+    pub fn update(ctx: Context<Update>, a: u64, b: u64) -> Result<()> {
+        ctx.accounts.user_a.data = a;
+        ctx.accounts.user_b.data = b;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Update<'info> {
+    #[account(mut)]
+    user_a: Account<'info, User>,
+    #[account(mut)]
+    user_b: Account<'info, User>,
+    #[account(mut)]
+    payer: Signer<'info>,
+}
+"#;
+
+#[test]
+fn flags_two_mutable_accounts_of_one_type() {
+    let findings = lint(UPDATE);
+    let line = UPDATE
+        .lines()
+        .position(|line| line.contains("user_a:"))
+        .unwrap()
+        + 1;
+    assert_eq!(findings.len(), 1, "{findings:#?}");
+    assert_eq!(findings[0].line, line);
+    let message = &findings[0].message;
+    assert!(message.contains("`user_a` and `user_b` in `Update`"));
+    assert!(message.contains("used by `update`"));
+    assert!(message.contains("`Account<User>`"));
+}
+
+#[test]
+fn passes_a_key_constraint() {
+    let source = UPDATE.replace(
+        "#[account(mut)]\n    user_a",
+        "#[account(mut, constraint = user_a.key() != user_b.key() @ ErrorCode::Same)]\n    user_a",
+    );
+    assert_eq!(lint(&source), []);
+}
+
+#[test]
+fn a_key_constraint_has_to_keep_them_apart() {
+    let constrained = |constraint: &str| {
+        UPDATE.replace(
+            "#[account(mut)]\n    user_a",
+            &format!("#[account(mut, constraint = {constraint} @ ErrorCode::Same)]\n    user_a"),
+        )
+    };
+    for kept_apart in [
+        "!(user_a.key() == user_b.key())",
+        "user_a.data > 0 && user_a.key() != user_b.key()",
+    ] {
+        assert_eq!(lint(&constrained(kept_apart)), [], "{kept_apart}");
+    }
+    for allowed in [
+        "user_a.key() == user_b.key()",
+        "!(user_a.key() != user_b.key())",
+        "user_a.data > 0 || user_a.key() != user_b.key()",
+    ] {
+        assert_eq!(lint(&constrained(allowed)).len(), 1, "{allowed}");
+    }
+}
+
+#[test]
+fn passes_a_check_in_the_handler() {
+    let source = UPDATE.replace(
+        "ctx.accounts.user_a.data = a;",
+        "require_keys_neq!(ctx.accounts.user_a.key(), ctx.accounts.user_b.key());\n        \
+         ctx.accounts.user_a.data = a;",
+    );
+    assert_eq!(lint(&source), []);
+}
+
+#[test]
+fn passes_distinct_seeds() {
+    let source = UPDATE
+        .replace(
+            "mut)]\n    user_a",
+            "mut, seeds = [b\"a\"], bump)]\n    user_a",
+        )
+        .replace(
+            "mut)]\n    user_b",
+            "mut, seeds = [b\"b\"], bump)]\n    user_b",
+        );
+    assert_eq!(lint(&source), []);
+}
+
+#[test]
+fn seeds_differing_only_in_keys_may_collide() {
+    let seeded = |a: &str, b: &str| {
+        UPDATE
+            .replace(
+                "mut)]\n    user_a",
+                &format!("mut, seeds = {a}, bump)]\n    user_a"),
+            )
+            .replace(
+                "mut)]\n    user_b",
+                &format!("mut, seeds = {b}, bump)]\n    user_b"),
+            )
+    };
+    let same_prefix = seeded(
+        "[b\"v\", owner_a.key().as_ref()]",
+        "[b\"v\", owner_b.key().as_ref()]",
+    );
+    assert_eq!(lint(&same_prefix).len(), 1);
+
+    let uneven = seeded("[b\"a\"]", "[b\"b\", owner.key().as_ref()]");
+    assert_eq!(lint(&uneven).len(), 1);
+
+    // The same bytes spelled as a constant and as a literal
+    let spelled = seeded(
+        "[VAULT, owner_a.key().as_ref()]",
+        "[b\"vault\", owner_b.key().as_ref()]",
+    ) + "const VAULT: &[u8] = b\"vault\";\n";
+    assert_eq!(lint(&spelled).len(), 1);
+
+    let constants = seeded(
+        "[LEFT_SEED, owner.key().as_ref()]",
+        "[RIGHT_SEED.as_bytes(), owner.key().as_ref()]",
+    );
+    assert_eq!(lint(&constants), []);
+}
+
+#[test]
+fn flags_the_token_accounts_of_a_transfer() {
+    let path = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../../programs/Arbitary_CPI/src/secure.rs"
+    );
+    let rule = rules::find("duplicate-mutable").unwrap();
+    let findings = lint_path(Path::new(path), &[rule]).unwrap();
+    assert_eq!(findings.len(), 1, "{findings:#?}");
+    let message = &findings[0].message;
+    assert!(message.contains("`source` and `destination` in `SecureAccounts`"));
+    // `process_trusted_metadata` takes the `Context` but is not a handler
+    assert!(message.contains("(used by `secure_transfer`)"), "{message}");
+}
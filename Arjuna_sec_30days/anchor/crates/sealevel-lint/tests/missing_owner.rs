@@ -0,0 +1,92 @@
+use std::path::Path;
+
+use sealevel_lint::{lint_path, lint_source, rules, Finding};
+
+const PROGRAM: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../../programs/ownership_verification/src"
+);
+
+fn lint(path: &Path, source: &str) -> Vec<Finding> {
+    let rule = rules::find("missing-owner").unwrap();
+    lint_source(source, path, &[rule]).unwrap()
+}
+
+#[test]
+fn flags_state_read_from_any_account() {
+    let path = Path::new(PROGRAM).join("insecure.rs");
+    let source = std::fs::read_to_string(&path).unwrap();
+    let findings = lint(&path, &source);
+
+    let line = source
+        .lines()
+        .position(|line| line.contains("ProgramState::unpack("))
+        .unwrap()
+        + 1;
+    assert_eq!(findings.len(), 1, "{findings:#?}");
+    assert_eq!(findings[0].line, line);
+    assert!(findings[0].message.contains("`ProgramState::unpack`"));
+}
+
+#[test]
+fn passes_state_from_an_owned_account() {
+    let rule = rules::find("missing-owner").unwrap();
+    let findings = lint_path(&Path::new(PROGRAM).join("secure.rs"), &[rule]).unwrap();
+    assert_eq!(findings, []);
+}
+
+#[test]
+fn understands_borsh_and_bytemuck() {
+    let findings = lint(
+        Path::new("inline.rs"),
+        r#"
+        fn process(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+            let iter = &mut accounts.iter();
+            let config = next_account_info(iter)?;
+            let vault = next_account_info(iter)?;
+            let pool = next_account_info(iter)?;
+
+            if config.owner != program_id {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+            let data = vault.try_borrow_data()?;
+            let vault_state = Vault::try_from_slice(&data[8..])?;
+            let config_state = bytemuck::from_bytes::<Config>(&config.data.borrow());
+
+            require_keys_eq!(*pool.owner, crate::ID);
+            let pool_state = Pool::deserialize(&mut &pool.data.borrow()[..])?;
+            Ok(())
+        }
+    "#,
+    );
+    let lines: Vec<_> = findings.iter().map(|finding| finding.line).collect();
+    assert_eq!(lines, [12], "{findings:#?}");
+    assert!(findings[0].message.contains("`vault`'s data"));
+}
+
+#[test]
+fn follows_the_polarity_of_the_check() {
+    let findings = lint(
+        Path::new("inline.rs"),
+        r#"
+        fn process(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+            let iter = &mut accounts.iter();
+            let vault = next_account_info(iter)?;
+            let config = next_account_info(iter)?;
+            if vault.owner != program_id {
+                let state = State::try_from_slice(&vault.data.borrow())?;
+            }
+            if config.owner == program_id {
+                msg!("owned");
+            } else {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+            let config_state = Config::try_from_slice(&config.data.borrow())?;
+            Ok(())
+        }
+    "#,
+    );
+    let lines: Vec<_> = findings.iter().map(|finding| finding.line).collect();
+    assert_eq!(lines, [7], "{findings:#?}");
+    assert!(findings[0].message.contains("`vault`'s data"));
+}
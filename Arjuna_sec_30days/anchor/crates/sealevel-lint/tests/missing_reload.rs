@@ -0,0 +1,180 @@
+use std::path::Path;
+
+use sealevel_lint::{lint_path, lint_source, rules, Finding};
+
+fn lint(source: &str) -> Vec<Finding> {
+    let rule = rules::find("missing-reload").unwrap();
+    lint_source(source, Path::new("inline.rs"), &[rule]).unwrap()
+}
+
+fn line_of(source: &str, needle: &str) -> usize {
+    source
+        .lines()
+        .position(|line| line.contains(needle))
+        .unwrap()
+        + 1
+}
+
+// The "Account Reloading" example of HeliusSolanaProgramSecurity.md
+const UPDATE_REWARDS: &str = r#"
+use anchor_lang::prelude::*;
+
+pub fn update_rewards(ctx: Context<UpdateStakingRewards>, amount: u64) -> Result<()> {
+    let staking_seeds = &[b"stake", ctx.accounts.staker.key().as_ref(), &[ctx.accounts.staking_account.bump]];
+
+    let cpi_accounts = UpdateRewards {
+        staking_account: ctx.accounts.staking_account.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.rewards_distribution_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, staking_seeds);
+
+    rewards_distribution::cpi::update_rewards(cpi_ctx, amount)?;
+
+    msg!("Updated Reward balance: {}", ctx.accounts.staking_account.rewards);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateStakingRewards<'info> {
+    #[account(mut)]
+    pub staker: Signer<'info>,
+    #[account(mut, seeds = [b"stake", staker.key().as_ref()], bump)]
+    pub staking_account: Account<'info, StakingAccount>,
+    pub rewards_distribution_program: Program<'info, RewardsDistribution>,
+}
+"#;
+
+#[test]
+fn flags_a_read_after_the_cpi() {
+    let findings = lint(UPDATE_REWARDS);
+    assert_eq!(findings.len(), 1, "{findings:#?}");
+    assert_eq!(findings[0].line, line_of(UPDATE_REWARDS, "msg!"));
+    let cpi = line_of(UPDATE_REWARDS, "cpi::update_rewards");
+    assert!(findings[0].message.contains(&format!(
+        "`staking_account` is read in `update_rewards` after the CPI on line {cpi}"
+    )));
+}
+
+#[test]
+fn passes_a_reload() {
+    let source = UPDATE_REWARDS.replace(
+        "    msg!",
+        "    ctx.accounts.staking_account.reload()?;\n    msg!",
+    );
+    assert_eq!(lint(&source), []);
+}
+
+#[test]
+fn reports_every_stale_read() {
+    let source = UPDATE_REWARDS.replace(
+        "    Ok(())\n}",
+        "    let total = ctx.accounts.staking_account.rewards + ctx.accounts.staking_account.staked;\n    \
+         Ok(())\n}",
+    );
+    let findings = lint(&source);
+    let lines: Vec<_> = findings.iter().map(|finding| finding.line).collect();
+    let total = line_of(&source, "let total");
+    assert_eq!(
+        lines,
+        [line_of(&source, "msg!"), total, total],
+        "{findings:#?}"
+    );
+}
+
+#[test]
+fn a_reload_in_one_branch_does_not_cover_the_other() {
+    let source = UPDATE_REWARDS.replace(
+        "    msg!",
+        "    if amount > 0 {\n        ctx.accounts.staking_account.reload()?;\n    }\n    msg!",
+    );
+    let findings = lint(&source);
+    assert_eq!(findings.len(), 1, "{findings:#?}");
+    assert_eq!(findings[0].line, line_of(&source, "msg!"));
+
+    let source = UPDATE_REWARDS.replace(
+        "    msg!",
+        "    if amount > 0 {\n        ctx.accounts.staking_account.reload()?;\n    } else {\n        \
+         ctx.accounts.staking_account.reload()?;\n    }\n    msg!",
+    );
+    assert_eq!(lint(&source), []);
+}
+
+#[test]
+fn follows_the_loop_back_edge() {
+    let source = r#"
+        pub fn drip(ctx: Context<Drip>, rounds: u8) -> Result<()> {
+            for _ in 0..rounds {
+                let rewards = ctx.accounts.staking_account.rewards;
+                rewards_distribution::cpi::update_rewards(ctx.accounts.update_context(), rewards)?;
+            }
+            Ok(())
+        }
+
+        #[derive(Accounts)]
+        pub struct Drip<'info> {
+            #[account(mut)]
+            pub staking_account: Account<'info, StakingAccount>,
+            pub rewards_distribution_program: Program<'info, RewardsDistribution>,
+        }
+
+        impl<'info> Drip<'info> {
+            fn update_context(&self) -> CpiContext<'_, '_, '_, 'info, UpdateRewards<'info>> {
+                CpiContext::new(
+                    self.rewards_distribution_program.to_account_info(),
+                    UpdateRewards {
+                        staking_account: self.staking_account.to_account_info(),
+                    },
+                )
+            }
+        }
+    "#;
+    let findings = lint(source);
+    assert_eq!(findings.len(), 1, "{findings:#?}");
+    assert_eq!(findings[0].line, line_of(source, "let rewards"));
+}
+
+#[test]
+fn follows_aliases_and_context_helpers() {
+    let source = r#"
+        pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+            let before = ctx.accounts.vault.amount;
+            token::transfer(ctx.accounts.transfer_context(), amount)?;
+            let vault = &ctx.accounts.vault;
+            require!(vault.amount == before - amount, ErrorCode::Mismatch);
+            Ok(())
+        }
+
+        #[derive(Accounts)]
+        pub struct Withdraw<'info> {
+            #[account(mut)]
+            vault: Account<'info, TokenAccount>,
+            #[account(mut)]
+            destination: Account<'info, TokenAccount>,
+            authority: Signer<'info>,
+            token_program: Program<'info, Token>,
+        }
+
+        impl<'info> Withdraw<'info> {
+            fn transfer_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+                CpiContext::new(
+                    self.token_program.to_account_info(),
+                    Transfer {
+                        from: self.vault.to_account_info(),
+                        to: self.destination.to_account_info(),
+                        authority: self.authority.to_account_info(),
+                    },
+                )
+            }
+        }
+    "#;
+    let findings = lint(source);
+    assert_eq!(findings.len(), 1, "{findings:#?}");
+    assert_eq!(findings[0].line, line_of(source, "require!"));
+}
+
+#[test]
+fn passes_the_programs() {
+    let rule = rules::find("missing-reload").unwrap();
+    let programs = concat!(env!("CARGO_MANIFEST_DIR"), "/../../programs");
+    assert_eq!(lint_path(Path::new(programs), &[rule]).unwrap(), []);
+}
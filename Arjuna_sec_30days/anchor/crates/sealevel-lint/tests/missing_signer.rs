@@ -0,0 +1,144 @@
+use std::path::Path;
+
+use sealevel_lint::{lint_path, lint_source, rules, Finding};
+
+const PROGRAM: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../../programs/signer_checks/src"
+);
+
+fn lint(path: &Path, source: &str) -> Vec<Finding> {
+    let rule = rules::find("missing-signer").unwrap();
+    lint_source(source, path, &[rule]).unwrap()
+}
+
+#[test]
+fn flags_the_write_without_a_signer_check() {
+    let path = Path::new(PROGRAM).join("insecure.rs");
+    let source = std::fs::read_to_string(&path).unwrap();
+    let findings = lint(&path, &source);
+
+    let line = source
+        .lines()
+        .position(|line| line.contains("try_borrow_mut_data"))
+        .unwrap()
+        + 1;
+    assert_eq!(findings.len(), 1, "{findings:#?}");
+    assert_eq!(findings[0].line, line);
+    assert!(findings[0].message.contains("`vault_account`'s data"));
+}
+
+#[test]
+fn passes_the_checked_processor() {
+    let rule = rules::find("missing-signer").unwrap();
+    let findings = lint_path(&Path::new(PROGRAM).join("secure.rs"), &[rule]).unwrap();
+    assert_eq!(findings, []);
+}
+
+#[test]
+fn a_check_only_covers_what_runs_after_it() {
+    let findings = lint(
+        Path::new("inline.rs"),
+        r#"
+        fn process(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+            let iter = &mut accounts.iter();
+            let vault = next_account_info(iter)?;
+            let admin = next_account_info(iter)?;
+            match data[0] {
+                0 => {
+                    if !admin.is_signer {
+                        return Err(ProgramError::MissingRequiredSignature);
+                    }
+                    vault.try_borrow_mut_data()?[0] = 1;
+                }
+                1 => {
+                    **vault.lamports.borrow_mut() -= 1;
+                }
+                2 => {
+                    if admin.is_signer {
+                        **admin.try_borrow_mut_lamports()? += 1;
+                    }
+                }
+                _ => {
+                    assert_signer(admin)?;
+                    admin.data.borrow_mut()[0] = 2;
+                }
+            }
+            Ok(())
+        }
+    "#,
+    );
+    let lines: Vec<_> = findings.iter().map(|finding| finding.line).collect();
+    assert_eq!(lines, [14], "{findings:#?}");
+    assert!(findings[0].message.contains("`vault`'s lamports"));
+}
+
+#[test]
+fn a_check_that_does_not_return_covers_nothing() {
+    let findings = lint(
+        Path::new("inline.rs"),
+        r#"
+        fn process(accounts: &[AccountInfo]) -> ProgramResult {
+            let iter = &mut accounts.iter();
+            let vault = next_account_info(iter)?;
+            let admin = next_account_info(iter)?;
+            if !admin.is_signer {
+                msg!("admin did not sign");
+            }
+            vault.try_borrow_mut_data()?[0] = 1;
+            Ok(())
+        }
+    "#,
+    );
+    let lines: Vec<_> = findings.iter().map(|finding| finding.line).collect();
+    assert_eq!(lines, [9], "{findings:#?}");
+}
+
+#[test]
+fn reports_every_unsigned_authority() {
+    let findings = lint(
+        Path::new("inline.rs"),
+        r#"
+        fn process(accounts: &[AccountInfo]) -> ProgramResult {
+            let iter = &mut accounts.iter();
+            let vault = next_account_info(iter)?;
+            let admin = next_account_info(iter)?;
+            let guardian = next_account_info(iter)?;
+            let state = Vault::unpack(&vault.data.borrow())?;
+            if state.admin != *admin.key || state.guardian != *guardian.key {
+                return Err(ProgramError::InvalidArgument);
+            }
+            if !admin.is_signer {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            vault.try_borrow_mut_data()?[0] = 1;
+            Ok(())
+        }
+    "#,
+    );
+    let lines: Vec<_> = findings.iter().map(|finding| finding.line).collect();
+    assert_eq!(lines, [14], "{findings:#?}");
+    assert!(findings[0].message.contains("`guardian`'s `is_signer`"));
+}
+
+#[test]
+fn follows_the_polarity_of_the_check() {
+    let findings = lint(
+        Path::new("inline.rs"),
+        r#"
+        fn process(accounts: &[AccountInfo]) -> ProgramResult {
+            let iter = &mut accounts.iter();
+            let vault = next_account_info(iter)?;
+            let authority = next_account_info(iter)?;
+            if !authority.is_signer {
+                vault.try_borrow_mut_data()?[0] = 1;
+            } else {
+                vault.try_borrow_mut_data()?[0] = 2;
+            }
+            Ok(())
+        }
+    "#,
+    );
+    let lines: Vec<_> = findings.iter().map(|finding| finding.line).collect();
+    assert_eq!(lines, [7], "{findings:#?}");
+}
@@ -0,0 +1,112 @@
+use std::path::Path;
+
+use sealevel_lint::{lint_path, lint_source, rules, Finding};
+
+const PROGRAMS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../../programs");
+
+fn lint(file: &str) -> Vec<Finding> {
+    let path = Path::new(PROGRAMS).join(file);
+    lint_path(&path, &rules::select("panics")).unwrap()
+}
+
+fn rules_of(findings: &[Finding]) -> Vec<&str> {
+    findings.iter().map(|finding| finding.rule).collect()
+}
+
+#[test]
+fn the_group_covers_every_panicking_conversion() {
+    let mut names: Vec<_> = rules::select("panics").iter().map(|r| r.name()).collect();
+    names.sort();
+    assert_eq!(names, ["panicking-unwrap", "pubkey-new", "unchecked-slice"]);
+}
+
+#[test]
+fn flags_pubkey_new_and_unwrapped_conversions() {
+    let source = r#"
+        pub fn process_instruction(
+            program_id: &Pubkey,
+            accounts: &[AccountInfo],
+            instruction_data: &[u8],
+        ) -> ProgramResult {
+            let amount = instruction_data.get(32..).ok_or(ProgramError::InvalidInstructionData)?;
+            let amount = u64::from_le_bytes(amount.try_into().unwrap());
+            Ok(())
+        }
+    "#;
+    let findings = lint_source(source, Path::new("inline.rs"), &rules::select("panics")).unwrap();
+    assert_eq!(rules_of(&findings), ["panicking-unwrap"], "{findings:#?}");
+    assert!(findings[0].message.contains("`try_into().unwrap()`"));
+
+    // The signer-checks lesson reads both keys with `Pubkey::new`
+    for file in [
+        "signer_checks/src/insecure.rs",
+        "signer_checks/src/secure.rs",
+    ] {
+        assert_eq!(
+            rules_of(&lint(file)),
+            ["pubkey-new", "pubkey-new"],
+            "{file}"
+        );
+    }
+    // `ProgramState::unpack` runs for every instruction, though no handler
+    // in its file calls it; its `input[32..40]` conversion cannot fail
+    let findings = lint("ownership_verification/src/lib.rs");
+    assert_eq!(rules_of(&findings), ["pubkey-new"], "{findings:#?}");
+}
+
+#[test]
+fn leaves_tests_alone() {
+    let source = r#"
+        pub fn handler(ctx: Context<Claim>) -> Result<()> {
+            let bump = ctx.bumps.get("vault").expect("vault bump");
+            Ok(())
+        }
+
+        #[cfg(test)]
+        mod tests {
+            #[test]
+            fn parses() {
+                let key = Pubkey::new(&[0; 32]);
+                "1".parse::<u8>().unwrap();
+            }
+        }
+    "#;
+    let findings = lint_source(source, Path::new("inline.rs"), &rules::select("panics")).unwrap();
+    assert_eq!(rules_of(&findings), ["panicking-unwrap"]);
+    assert_eq!(findings[0].line, 3);
+    assert!(findings[0].message.contains("`.expect(..)`"));
+}
+
+#[test]
+fn only_checks_what_instructions_reach() {
+    let source = r#"
+        pub fn process_instruction(
+            program_id: &Pubkey,
+            accounts: &[AccountInfo],
+            instruction_data: &[u8],
+        ) -> ProgramResult {
+            if instruction_data.len() < 8 {
+                return Err(ProgramError::InvalidInstructionData);
+            }
+            let amount = u64::from_le_bytes(instruction_data[0..8].try_into().unwrap());
+            let seed = <[u8; 4]>::try_from(&instruction_data[..4]).unwrap();
+            withdraw(accounts, amount)
+        }
+
+        fn withdraw(accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+            let vault = accounts.first().unwrap();
+            Ok(())
+        }
+
+        pub fn client_helper(bytes: &[u8]) -> Pubkey {
+            Pubkey::new(&bytes[..32])
+        }
+    "#;
+    let findings = lint_source(source, Path::new("inline.rs"), &rules::select("panics")).unwrap();
+    let lines: Vec<_> = findings.iter().map(|finding| finding.line).collect();
+    assert_eq!(lines, [16], "{findings:#?}");
+
+    for file in ["Arbitary_CPI/src/insecure.rs", "Arbitary_CPI/src/secure.rs"] {
+        assert_eq!(lint(file), [], "{file}");
+    }
+}
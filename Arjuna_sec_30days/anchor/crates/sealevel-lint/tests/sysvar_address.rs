@@ -0,0 +1,188 @@
+use std::path::Path;
+
+use sealevel_lint::{lint_path, lint_source, rules, Finding};
+
+fn lint(source: &str) -> Vec<Finding> {
+    let rule = rules::find("sysvar-address").unwrap();
+    lint_source(source, Path::new("inline.rs"), &[rule]).unwrap()
+}
+
+fn line_of(source: &str, needle: &str) -> usize {
+    source
+        .lines()
+        .position(|line| line.contains(needle))
+        .unwrap()
+        + 1
+}
+
+#[test]
+fn passes_a_sysvar_typed_rent() {
+    let rule = rules::find("sysvar-address").unwrap();
+    let program = concat!(env!("CARGO_MANIFEST_DIR"), "/../../programs/ata/src");
+    assert_eq!(lint_path(Path::new(program), &[rule]).unwrap(), []);
+}
+
+// "Sysvar address checking" in the checklist
+const CHECK_SYSVAR_ADDRESS: &str = r#"
+#[program]
+pub mod insecure {
+    use super::*;
+
+    pub fn check_sysvar_address(ctx: Context<CheckSysvarAddress>) -> Result<()> {
+        msg!("Rent Key -> {}", ctx.accounts.rent.key().to_string());
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct CheckSysvarAddress<'info> {
+    rent: AccountInfo<'info>,
+}
+"#;
+
+#[test]
+fn flags_an_unchecked_sysvar_field() {
+    let findings = lint(CHECK_SYSVAR_ADDRESS);
+    assert_eq!(findings.len(), 1, "{findings:#?}");
+    assert_eq!(
+        findings[0].line,
+        line_of(CHECK_SYSVAR_ADDRESS, "rent: AccountInfo")
+    );
+    assert!(findings[0]
+        .message
+        .contains("`rent` in `CheckSysvarAddress` is an unchecked `AccountInfo`"));
+}
+
+#[test]
+fn passes_a_checked_sysvar_field() {
+    let source = CHECK_SYSVAR_ADDRESS.replace(
+        "        msg!",
+        "        require_eq!(ctx.accounts.rent.key(), sysvar::rent::ID);\n        msg!",
+    );
+    assert_eq!(lint(&source), []);
+
+    let source = CHECK_SYSVAR_ADDRESS.replace(
+        "    rent: AccountInfo",
+        "    #[account(address = sysvar::rent::ID)]\n    rent: AccountInfo",
+    );
+    assert_eq!(lint(&source), []);
+}
+
+#[test]
+fn a_comparison_has_to_guard_the_handler() {
+    let checked = |check: &str| {
+        CHECK_SYSVAR_ADDRESS.replace("        msg!", &format!("        {check}\n        msg!"))
+    };
+    // Compared, but nothing stops the handler when it differs
+    let logged = checked("msg!(\"is rent: {}\", ctx.accounts.rent.key() == sysvar::rent::ID);");
+    assert_eq!(lint(&logged).len(), 1);
+    let inverted = checked(
+        "if ctx.accounts.rent.key() == sysvar::rent::ID {\n            \
+         return err!(ErrorCode::Sysvar);\n        }",
+    );
+    assert_eq!(lint(&inverted).len(), 1);
+
+    let guarded = checked(
+        "if ctx.accounts.rent.key() != sysvar::rent::ID {\n            \
+         return err!(ErrorCode::Sysvar);\n        }",
+    );
+    assert_eq!(lint(&guarded), []);
+    let required = checked("require!(ctx.accounts.rent.key() == Rent::id(), ErrorCode::Sysvar);");
+    assert_eq!(lint(&required), []);
+}
+
+#[test]
+fn the_id_has_to_be_the_sysvars_own() {
+    let source = CHECK_SYSVAR_ADDRESS.replace(
+        "        msg!",
+        "        require_keys_eq!(ctx.accounts.rent.key(), sysvar::clock::ID);\n        msg!",
+    );
+    assert_eq!(lint(&source).len(), 1);
+
+    let source = VERIFY_SIGNATURES.replace(
+        "    let data",
+        "    if *instruction_acc.key != sysvar::rent::id() {\n        \
+         return Err(ProgramError::InvalidArgument);\n    }\n    let data",
+    );
+    assert_eq!(lint(&source).len(), 2);
+
+    // Through an import
+    let source = format!(
+        "use solana_program::sysvar::instructions::check_id;\n{}",
+        VERIFY_SIGNATURES.replace(
+            "    let data",
+            "    if !check_id(instruction_acc.key) {\n        \
+             return Err(ProgramError::InvalidArgument);\n    }\n    let data",
+        )
+    );
+    assert_eq!(lint(&source), []);
+}
+
+// "Sysvar system account not checked" in the checklist
+const VERIFY_SIGNATURES: &str = r#"
+fn verify_signatures(accounts: &[AccountInfo]) -> ProgramResult {
+    let iter = &mut accounts.iter();
+    let instruction_acc = next_account_info(iter)?;
+    let data = instruction_acc.try_borrow_data()?;
+    let current = load_current_index(&data);
+    let secp_ix = load_instruction_at(current as usize - 1, &data)?;
+    Ok(())
+}
+"#;
+
+#[test]
+fn flags_raw_instruction_sysvar_loads() {
+    let findings = lint(VERIFY_SIGNATURES);
+    assert_eq!(findings.len(), 2, "{findings:#?}");
+    assert_eq!(
+        findings[0].line,
+        line_of(VERIFY_SIGNATURES, "load_current_index")
+    );
+    assert!(findings[0]
+        .message
+        .contains("`load_current_index` in `verify_signatures` reads `instruction_acc`"));
+    assert_eq!(
+        findings[1].line,
+        line_of(VERIFY_SIGNATURES, "load_instruction_at")
+    );
+}
+
+#[test]
+fn passes_loads_after_a_key_check() {
+    let source = VERIFY_SIGNATURES.replace(
+        "    let data",
+        "    if *instruction_acc.key != solana_program::sysvar::instructions::id() {\n        \
+         return Err(ProgramError::InvalidArgument);\n    }\n    let data",
+    );
+    assert_eq!(lint(&source), []);
+}
+
+#[test]
+fn passes_loaders_that_check_the_key() {
+    let source = r#"
+        fn verify(accounts: &[AccountInfo]) -> ProgramResult {
+            let iter = &mut accounts.iter();
+            let rent = Rent::from_account_info(next_account_info(iter)?)?;
+            let clock = Clock::from_account_info(next_account_info(iter)?)?;
+            let instructions = next_account_info(iter)?;
+            let ix = load_instruction_at_checked(0, instructions)?;
+            Ok(())
+        }
+    "#;
+    assert_eq!(lint(source), []);
+}
+
+#[test]
+fn only_instruction_handlers_have_to_check() {
+    // A client-side helper taking the same accounts does not run on chain
+    let source = CHECK_SYSVAR_ADDRESS.replace(
+        "        msg!",
+        "        require_keys_eq!(ctx.accounts.rent.key(), sysvar::rent::ID);\n        msg!",
+    ) + r#"
+pub fn describe(ctx: Context<CheckSysvarAddress>) -> Result<()> {
+    msg!("{}", ctx.accounts.rent.key());
+    Ok(())
+}
+"#;
+    assert_eq!(lint(&source), []);
+}
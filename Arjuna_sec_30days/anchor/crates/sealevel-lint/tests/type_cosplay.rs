@@ -0,0 +1,149 @@
+use std::path::Path;
+
+use sealevel_lint::{lint_path, lint_source, rules, Finding};
+
+fn lint(source: &str) -> Vec<Finding> {
+    let rule = rules::find("type-cosplay").unwrap();
+    lint_source(source, Path::new("inline.rs"), &[rule]).unwrap()
+}
+
+fn lint_program(program: &str) -> (String, Vec<Finding>) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../../programs")
+        .join(program)
+        .join("src/lib.rs");
+    let rule = rules::find("type-cosplay").unwrap();
+    let findings = lint_path(&path, &[rule]).unwrap();
+    (std::fs::read_to_string(path).unwrap(), findings)
+}
+
+#[test]
+fn flags_the_untagged_native_layouts() {
+    for (program, layout) in [
+        ("ownership_verification", "ProgramState"),
+        ("signer_checks", "Vault"),
+    ] {
+        let (source, findings) = lint_program(program);
+        let line = source
+            .lines()
+            .position(|line| line.contains(&format!("pub struct {layout}")))
+            .unwrap()
+            + 1;
+        assert_eq!(findings.len(), 1, "{findings:#?}");
+        assert_eq!(findings[0].line, line);
+        assert!(findings[0].message.contains(&format!(
+            "`{layout}` is an account layout with no discriminator"
+        )));
+    }
+}
+
+// The "Type cosplay" example of the checklist
+const COSPLAY: &str = r#"
+pub fn update_user(ctx: Context<UpdateUser>) -> ProgramResult {
+    let user = User::try_from_slice(&ctx.accounts.user.data.borrow()).unwrap();
+    Ok(())
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct User {
+    authority: Pubkey,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct Metadata {
+    account: Pubkey,
+}
+"#;
+
+#[test]
+fn flags_structs_deserialized_from_account_data() {
+    let findings = lint(COSPLAY);
+    assert_eq!(findings.len(), 1, "{findings:#?}");
+    assert!(findings[0].message.contains("`User`"));
+
+    let tagged = COSPLAY
+        .replace(
+            "pub struct User {\n",
+            "pub struct User {\n    discriminant: AccountDiscriminant,\n",
+        )
+        .replace(
+            "pub struct Metadata {\n",
+            "pub struct Metadata {\n    discriminant: AccountDiscriminant,\n",
+        );
+    assert_eq!(lint(&tagged), []);
+}
+
+#[test]
+fn flags_layouts_of_the_same_length() {
+    let source = r#"
+        const PUBKEY: usize = 32;
+
+        pub struct Config {
+            pub account_type: AccountType,
+            pub admin: Pubkey,
+        }
+
+        impl Config {
+            pub const LEN: usize = 1 + PUBKEY;
+        }
+
+        pub struct Escrow {
+            pub owner: Pubkey,
+            pub bump: u8,
+        }
+
+        impl Escrow {
+            pub const LEN: usize = PUBKEY + 1;
+        }
+    "#;
+    let findings = lint(source);
+    let messages: Vec<_> = findings.iter().map(|f| f.message.as_str()).collect();
+    assert_eq!(findings.len(), 2, "{findings:#?}");
+    assert!(messages[0].contains("`Escrow` is an account layout with no discriminator"));
+    assert!(messages[1].contains("`Escrow` and `Config` are both 33 bytes"));
+}
+
+#[test]
+fn passes_anchor_accounts() {
+    let source = r#"
+        #[account]
+        pub struct Vault {
+            pub admin: Pubkey,
+        }
+
+        impl Vault {
+            pub const LEN: usize = 8 + 32;
+        }
+    "#;
+    assert_eq!(lint(source), []);
+}
+
+#[test]
+fn only_counts_unpacking_account_data() {
+    let source = r#"
+        pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], instruction_data: &[u8]) -> ProgramResult {
+            let args = WithdrawArgs::unpack(instruction_data)?;
+            let vault = next_account_info(&mut accounts.iter())?;
+            let data = vault.try_borrow_data()?;
+            let state = State::unpack(&data[..])?;
+            Ok(())
+        }
+
+        pub struct WithdrawArgs {
+            pub amount: u64,
+        }
+
+        impl WithdrawArgs {
+            pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+                Ok(WithdrawArgs { amount: 0 })
+            }
+        }
+
+        pub struct State {
+            pub admin: Pubkey,
+        }
+    "#;
+    let findings = lint(source);
+    assert_eq!(findings.len(), 1, "{findings:#?}");
+    assert!(findings[0].message.contains("`State` is an account layout"));
+}
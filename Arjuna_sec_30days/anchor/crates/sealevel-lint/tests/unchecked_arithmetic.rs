@@ -0,0 +1,68 @@
+use std::path::Path;
+
+use sealevel_lint::{lint_path, lint_source, lint_source_with, rules, Context, Level};
+
+const WORKSPACE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../..");
+
+const PROGRAM: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../../programs/ownership_verification/src"
+);
+
+#[test]
+fn overflow_checks_downgrade_to_info() {
+    let path = Path::new(PROGRAM).join("insecure.rs");
+    assert!(Context::for_path(&path).overflow_checks);
+
+    let rule = rules::find("unchecked-arithmetic").unwrap();
+    let findings = lint_path(&path, &[rule]).unwrap();
+    let messages: Vec<_> = findings.iter().map(|f| f.message.as_str()).collect();
+    assert_eq!(findings.len(), 2, "{findings:#?}");
+    assert!(messages[0].contains("`-=` on the lamports of `vault_account`"));
+    assert!(messages[1].contains("`+=` on the lamports of `admin_account`"));
+    assert!(findings.iter().all(|finding| finding.level == Level::Info));
+}
+
+#[test]
+fn flags_amounts_without_overflow_checks() {
+    // The underflow example of OverflowAndUnderflow.md with a u64 balance, and its fix
+    let source = r#"
+        pub fn process_instruction(_program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+            let account = next_account_info(&mut accounts.iter())?;
+            let mut balance: u64 = account.lamports();
+            let tokens_to_subtract: u64 = 100;
+            balance = balance - tokens_to_subtract;
+
+            match balance.checked_sub(tokens_to_subtract) {
+                Some(new_balance) => **account.lamports.borrow_mut() = new_balance,
+                None => return Err(ProgramError::InsufficientFunds),
+            }
+
+            let fee = u64::from_le_bytes(data[0..8].try_into().unwrap());
+            let total = source.amount * 2;
+            let offset = 8 + 32;
+            let end = offset + data.len();
+            let bump: u8 = data[8];
+            let next = bump + 1;
+            let count: u32 = 0;
+            let count = count * 2;
+            Ok(())
+        }
+    "#;
+    let rule = rules::find("unchecked-arithmetic").unwrap();
+    let findings = lint_source(source, Path::new("inline.rs"), &[rule]).unwrap();
+    let lines: Vec<_> = findings.iter().map(|finding| finding.line).collect();
+    assert_eq!(lines, [6, 14], "{findings:#?}");
+    assert!(findings[0].message.contains("`-` on `balance`"));
+    assert!(findings[1].message.contains("`*` on a token amount"));
+    assert!(findings
+        .iter()
+        .all(|finding| finding.level == Level::Warning));
+
+    // The same source in this workspace, which sets overflow-checks
+    let cx = Context::for_workspace(Path::new(WORKSPACE));
+    assert!(cx.overflow_checks);
+    let findings = lint_source_with(source, Path::new("inline.rs"), &cx, &[rule]).unwrap();
+    assert_eq!(findings.len(), 2, "{findings:#?}");
+    assert!(findings.iter().all(|finding| finding.level == Level::Info));
+}
@@ -0,0 +1,120 @@
+use std::path::Path;
+
+use sealevel_lint::{lint_path, lint_source, rules, Finding};
+
+const PROGRAMS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../../programs");
+
+fn lint(file: &str) -> Vec<Finding> {
+    let rule = rules::find("unchecked-slice").unwrap();
+    lint_path(&Path::new(PROGRAMS).join(file), &[rule]).unwrap()
+}
+
+#[test]
+fn flags_a_slice_past_the_is_empty_check() {
+    let findings = lint("ownership_verification/src/insecure.rs");
+    assert_eq!(findings.len(), 1, "{findings:#?}");
+    assert!(findings[0]
+        .message
+        .contains("needs `instruction_data.len() >= 9`, but only `instruction_data.len() >= 1`"));
+}
+
+#[test]
+fn passes_sufficient_bounds() {
+    assert_eq!(lint("signer_checks/src/insecure.rs"), []);
+    assert_eq!(lint("signer_checks/src/secure.rs"), []);
+    // `input.len() != Self::LEN` with `LEN = 32 + 8`
+    assert_eq!(lint("ownership_verification/src/lib.rs"), []);
+}
+
+#[test]
+fn tracks_guards_through_branches_and_asserts() {
+    let source = r#"
+        const HEADER: usize = 8;
+
+        fn process(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+            let vault = next_account_info(&mut accounts.iter())?;
+            let state = vault.try_borrow_data()?;
+            let tag = data[0];
+            if 4 > data.len() {
+                return Err(ProgramError::InvalidInstructionData);
+            }
+            let amount = &data[0..=3];
+            if data.len() <= HEADER {
+                let short = data[3];
+            } else {
+                let long = &data[1..HEADER + 1];
+                let past = data[HEADER + 1];
+            }
+            require!(state.len() >= HEADER, ErrorCode::Short);
+            let discriminator = &state[..HEADER];
+            let body = &state[HEADER..HEADER + 32];
+            Ok(())
+        }
+    "#;
+    let rule = rules::find("unchecked-slice").unwrap();
+    let findings = lint_source(source, Path::new("inline.rs"), &[rule]).unwrap();
+    let lines: Vec<_> = findings.iter().map(|finding| finding.line).collect();
+    assert_eq!(lines, [7, 16, 20], "{findings:#?}");
+    assert!(findings[0].message.contains("its length is never checked"));
+    assert!(findings[1].message.contains("`data.len() >= 10`"));
+    assert!(findings[2].message.contains("`state.len() >= 40`"));
+}
+
+#[test]
+fn unknown_bounds_need_a_matching_guard() {
+    let source = r#"
+        fn process(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+            let offset = data.len() / 2;
+            let head = &data[..offset];
+            if data.len() < offset + 8 {
+                return Err(ProgramError::InvalidInstructionData);
+            }
+            let word = &data[offset..offset + 8];
+            let byte = data[offset + 8];
+            if data.len() > offset + 8 {
+                let byte = data[offset + 8];
+            }
+            let tail = &data[offset..];
+            Ok(())
+        }
+    "#;
+    let rule = rules::find("unchecked-slice").unwrap();
+    let findings = lint_source(source, Path::new("inline.rs"), &[rule]).unwrap();
+    let lines: Vec<_> = findings.iter().map(|finding| finding.line).collect();
+    assert_eq!(lines, [4, 9, 13], "{findings:#?}");
+    assert!(findings[0]
+        .message
+        .contains("a bound that is not a constant"));
+}
+
+#[test]
+fn follows_diverging_else_and_rebinding() {
+    let source = r#"
+        fn process(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+            if data.len() >= 9 {
+                msg!("long enough");
+            } else {
+                return Err(ProgramError::InvalidInstructionData);
+            }
+            let amount = &data[1..9];
+            if data.len() < 17 {
+                return Err(ProgramError::InvalidInstructionData);
+            } else {
+                msg!("long enough");
+            }
+            let fee = &data[9..17];
+            if data.len() < 25 {
+                Err(ProgramError::InvalidInstructionData)?;
+            }
+            let limit = &data[17..25];
+            let data = &data[8..];
+            let tag = data[0];
+            Ok(())
+        }
+    "#;
+    let rule = rules::find("unchecked-slice").unwrap();
+    let findings = lint_source(source, Path::new("inline.rs"), &[rule]).unwrap();
+    let lines: Vec<_> = findings.iter().map(|finding| finding.line).collect();
+    assert_eq!(lines, [20], "{findings:#?}");
+    assert!(findings[0].message.contains("its length is never checked"));
+}
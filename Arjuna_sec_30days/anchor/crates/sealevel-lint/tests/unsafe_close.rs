@@ -0,0 +1,136 @@
+use std::path::Path;
+
+use sealevel_lint::{lint_source, rules, Finding};
+
+fn lint(source: &str) -> Vec<Finding> {
+    let rule = rules::find("unsafe-close").unwrap();
+    lint_source(source, Path::new("inline.rs"), &[rule]).unwrap()
+}
+
+// The example of Closing_Accounts.md
+const CLOSE: &str = r#"
+pub fn close_account(ctx: Context<CloseAccount>) -> ProgramResult {
+    let account = ctx.accounts.data_account.to_account_info();
+    let destination = ctx.accounts.destination.to_account_info();
+
+    **destination.lamports.borrow_mut() = destination.lamports().checked_add(account.lamports()).unwrap();
+    **account.lamports.borrow_mut() = 0;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseAccount<'info> {
+    #[account(mut)]
+    pub data_account: Account<'info, Data>,
+    #[account(mut)]
+    pub destination: AccountInfo<'info>,
+}
+"#;
+
+#[test]
+fn flags_draining_lamports_alone() {
+    let findings = lint(CLOSE);
+    let line = CLOSE
+        .lines()
+        .position(|line| line.contains("**account.lamports.borrow_mut() = 0"))
+        .unwrap()
+        + 1;
+    assert_eq!(findings.len(), 1, "{findings:#?}");
+    assert_eq!(findings[0].line, line);
+    assert!(findings[0]
+        .message
+        .contains("`data_account` is emptied of lamports in `close_account`"));
+}
+
+#[test]
+fn passes_zeroed_data() {
+    let source = CLOSE.replace(
+        "    Ok(())",
+        "    let mut data = account.try_borrow_mut_data()?;\n    \
+         for byte in data.deref_mut().iter_mut() {\n        *byte = 0;\n    }\n    Ok(())",
+    );
+    assert_eq!(lint(&source), []);
+}
+
+#[test]
+fn passes_the_closed_discriminator() {
+    let source = CLOSE.replace(
+        "    Ok(())",
+        "    let mut data = account.try_borrow_mut_data()?;\n    \
+         (&mut data[..8]).copy_from_slice(&CLOSED_ACCOUNT_DISCRIMINATOR);\n    Ok(())",
+    );
+    assert_eq!(lint(&source), []);
+}
+
+#[test]
+fn zeroing_counts_for_the_drained_account_only() {
+    let zeroed = |setup: &str| {
+        CLOSE
+            .replace("    Ok(())", &format!("{setup}\n    Ok(())"))
+            .replace(
+                "pub destination: AccountInfo<'info>,",
+                "pub destination: AccountInfo<'info>,\n    #[account(mut)]\n    pub other: Account<'info, Data>,",
+            )
+    };
+    let other = zeroed(
+        "    let other = ctx.accounts.other.to_account_info();\n    \
+         let mut data = other.try_borrow_mut_data()?;\n    data.fill(0);",
+    );
+    assert_eq!(lint(&other).len(), 1);
+
+    let marked_other = zeroed(
+        "    let other = ctx.accounts.other.to_account_info();\n    \
+         other.try_borrow_mut_data()?[..8].copy_from_slice(&CLOSED_ACCOUNT_DISCRIMINATOR);",
+    );
+    assert_eq!(lint(&marked_other).len(), 1);
+
+    let unrelated = zeroed("    let mut scratch = [1u8; 8];\n    scratch.fill(0);");
+    assert_eq!(lint(&unrelated).len(), 1);
+
+    let memset = zeroed(
+        "    let mut data = account.data.borrow_mut();\n    \
+         sol_memset(&mut data, 0, account.data_len());",
+    );
+    assert_eq!(lint(&memset), []);
+}
+
+#[test]
+fn flags_a_native_debit_of_the_whole_balance() {
+    let source = r#"
+        fn process_close(accounts: &[AccountInfo]) -> ProgramResult {
+            let iter = &mut accounts.iter();
+            let vault = next_account_info(iter)?;
+            let owner = next_account_info(iter)?;
+            **owner.try_borrow_mut_lamports()? += vault.lamports();
+            **vault.try_borrow_mut_lamports()? -= vault.lamports();
+            Ok(())
+        }
+    "#;
+    let findings = lint(source);
+    assert_eq!(findings.len(), 1, "{findings:#?}");
+    assert!(findings[0].message.contains("`vault`"));
+
+    // Giving the account back to the system program with no data closes it
+    let source = source.replace(
+        "Ok(())",
+        "vault.assign(&system_program::ID);\n            vault.realloc(0, false)?;\n            Ok(())",
+    );
+    assert_eq!(lint(&source), []);
+}
+
+#[test]
+fn reads_the_balance_through_get_lamports() {
+    let source = r#"
+        pub fn close(ctx: Context<Close>) -> Result<()> {
+            let vault = ctx.accounts.vault.to_account_info();
+            let balance = vault.get_lamports();
+            ctx.accounts.owner.add_lamports(balance)?;
+            vault.sub_lamports(vault.get_lamports())?;
+            Ok(())
+        }
+    "#;
+    let findings = lint(source);
+    assert_eq!(findings.len(), 1, "{findings:#?}");
+    assert!(findings[0].message.contains("`vault`"));
+}
@@ -0,0 +1,146 @@
+use std::path::Path;
+
+use sealevel_lint::{lint_source, rules, Finding};
+
+fn lint(source: &str) -> Vec<Finding> {
+    let rule = rules::find("unsafe-realloc").unwrap();
+    lint_source(source, Path::new("inline.rs"), &[rule]).unwrap()
+}
+
+// The "Account Data Reallocation" example of HeliusSolanaProgramSecurity.md
+const MODIFY: &str = r#"
+pub fn modify_todo_list(ctx: Context<ModifyTodoList>, modifications: Vec<TodoModification>) -> ProgramResult {
+    let required_data_len = calculate_required_data_len(&modifications);
+
+    ctx.accounts.todo_list_data.realloc(required_data_len, false)?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ModifyTodoList<'info> {
+    #[account(mut)]
+    todo_list_data: AccountInfo<'info>,
+    #[account(mut)]
+    payer: Signer<'info>,
+    system_program: Program<'info, System>,
+}
+"#;
+
+#[test]
+fn flags_a_realloc_that_can_shrink_and_grow() {
+    let findings = lint(MODIFY);
+    let line = MODIFY
+        .lines()
+        .position(|line| line.contains(".realloc("))
+        .unwrap()
+        + 1;
+    let messages: Vec<_> = findings.iter().map(|f| f.message.as_str()).collect();
+    assert_eq!(findings.len(), 2, "{findings:#?}");
+    assert!(findings.iter().all(|finding| finding.line == line));
+    assert!(messages[0]
+        .contains("`todo_list_data` is reallocated in `modify_todo_list` with `zero_init` false"));
+    assert!(
+        messages[1].contains("`todo_list_data` can grow in `modify_todo_list` but nothing tops up")
+    );
+}
+
+#[test]
+fn passes_zero_init_and_a_rent_top_up() {
+    let source = MODIFY.replace(
+        "    ctx.accounts.todo_list_data.realloc(required_data_len, false)?;",
+        "    let rent = Rent::get()?.minimum_balance(required_data_len);\n    \
+         let missing = rent.saturating_sub(ctx.accounts.todo_list_data.lamports());\n    \
+         invoke(\n        &system_instruction::transfer(ctx.accounts.payer.key, ctx.accounts.todo_list_data.key, missing),\n        \
+         &[ctx.accounts.payer.to_account_info(), ctx.accounts.todo_list_data.clone()],\n    )?;\n    \
+         ctx.accounts.todo_list_data.realloc(required_data_len, true)?;",
+    );
+    assert_eq!(lint(&source), []);
+}
+
+#[test]
+fn the_top_up_has_to_reach_the_account() {
+    // The minimum is worked out, but the payment goes elsewhere
+    let source = r#"
+        fn resize(accounts: &[AccountInfo], len: usize) -> ProgramResult {
+            let iter = &mut accounts.iter();
+            let list = next_account_info(iter)?;
+            let payer = next_account_info(iter)?;
+            let treasury = next_account_info(iter)?;
+            let rent = Rent::get()?.minimum_balance(len);
+            invoke(
+                &system_instruction::transfer(payer.key, treasury.key, rent),
+                &[payer.clone(), treasury.clone()],
+            )?;
+            list.realloc(len, true)?;
+            Ok(())
+        }
+    "#;
+    let findings = lint(source);
+    assert_eq!(findings.len(), 1, "{findings:#?}");
+    assert!(findings[0].message.contains("`list` can grow in `resize`"));
+
+    let paid = source.replace(
+        "            list.realloc",
+        "            **list.try_borrow_mut_lamports()? += rent;\n            list.realloc",
+    );
+    assert_eq!(lint(&paid), []);
+}
+
+#[test]
+fn passes_growth_only_without_zero_init() {
+    let source = r#"
+        fn append(accounts: &[AccountInfo], entry: &[u8]) -> ProgramResult {
+            let iter = &mut accounts.iter();
+            let list = next_account_info(iter)?;
+            let payer = next_account_info(iter)?;
+            invoke(
+                &system_instruction::transfer(payer.key, list.key, 1_000_000),
+                &[payer.clone(), list.clone()],
+            )?;
+            list.realloc(list.data_len() + entry.len(), false)?;
+            Ok(())
+        }
+
+        fn truncate(accounts: &[AccountInfo], n: usize) -> ProgramResult {
+            let list = next_account_info(&mut accounts.iter())?;
+            list.realloc(list.data_len() - n, true)?;
+            Ok(())
+        }
+    "#;
+    assert_eq!(lint(source), []);
+}
+
+#[test]
+fn flags_anchor_realloc_without_zero() {
+    let source = r#"
+        #[derive(Accounts)]
+        #[instruction(len: u32)]
+        pub struct Resize<'info> {
+            #[account(
+                mut,
+                realloc = 8 + len as usize,
+                realloc::payer = payer,
+                realloc::zero = false,
+            )]
+            pub list: Account<'info, TodoList>,
+            #[account(mut)]
+            pub payer: Signer<'info>,
+            pub system_program: Program<'info, System>,
+        }
+    "#;
+    let findings = lint(source);
+    let line = source
+        .lines()
+        .position(|line| line.contains("realloc::zero"))
+        .unwrap()
+        + 1;
+    assert_eq!(findings.len(), 1, "{findings:#?}");
+    assert_eq!(findings[0].line, line);
+    assert!(findings[0].message.contains("`list` in `Resize`"));
+
+    assert_eq!(
+        lint(&source.replace("realloc::zero = false", "realloc::zero = true")),
+        []
+    );
+}
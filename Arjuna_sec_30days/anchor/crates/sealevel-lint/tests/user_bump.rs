@@ -0,0 +1,77 @@
+use std::path::Path;
+
+use sealevel_lint::{lint_path, lint_source, rules, Finding};
+
+const PROGRAM: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../../programs/bump_seed_canonicalization/src"
+);
+
+fn lint(file: &str) -> Vec<Finding> {
+    let rule = rules::find("user-bump").unwrap();
+    lint_path(&Path::new(PROGRAM).join(file), &[rule]).unwrap()
+}
+
+#[test]
+fn flags_the_caller_supplied_bump() {
+    let findings = lint("insecure.rs");
+    assert_eq!(findings.len(), 1, "{findings:#?}");
+
+    let source = std::fs::read_to_string(Path::new(PROGRAM).join("insecure.rs")).unwrap();
+    let line = source
+        .lines()
+        .position(|line| line.contains("Pubkey::create_program_address("))
+        .unwrap()
+        + 1;
+    assert_eq!(findings[0].line, line);
+    assert!(findings[0].message.contains("`valiadate_pda_usage`"));
+    assert!(findings[0].message.contains("parameter `bump`"));
+}
+
+#[test]
+fn passes_the_canonical_bump() {
+    assert_eq!(lint("secure.rs"), []);
+}
+
+#[test]
+fn flags_instruction_data_and_anchor_arguments() {
+    let source = r#"
+        pub fn native(program_id: &Pubkey, data: &[u8]) -> ProgramResult {
+            let bump = data[0];
+            let pda = Pubkey::create_program_address(&[b"vault", &[bump]], program_id)?;
+            Ok(())
+        }
+
+        #[program]
+        pub mod vault {
+            pub fn withdraw(ctx: Context<Withdraw>, nonce: u64) -> Result<()> {
+                let seeds = [b"vault".as_ref(), &nonce.to_le_bytes()];
+                let pda = Pubkey::create_program_address(&seeds, ctx.program_id)?;
+                Ok(())
+            }
+
+            pub fn stored(ctx: Context<Stored>) -> Result<()> {
+                let bump = ctx.accounts.vault.bump;
+                let pda = Pubkey::create_program_address(&[b"vault", &[bump]], ctx.program_id)?;
+                Ok(())
+            }
+
+            pub fn canonical(ctx: Context<Canonical>, seed: u8) -> Result<()> {
+                let (_, bump) = Pubkey::find_program_address(&[b"vault", &[seed]], ctx.program_id);
+                let pda = Pubkey::create_program_address(&[b"vault", &[bump]], ctx.program_id)?;
+                Ok(())
+            }
+
+            pub fn claim(ctx: Context<Claim>, bump: u8) -> Result<()> {
+                let pda = Pubkey::create_program_address(&[b"vault", &[bump]], ctx.program_id)?;
+                Ok(())
+            }
+        }
+    "#;
+    let rule = rules::find("user-bump").unwrap();
+    let findings = lint_source(source, Path::new("inline.rs"), &[rule]).unwrap();
+    let lines: Vec<_> = findings.iter().map(|finding| finding.line).collect();
+    assert_eq!(lines, [4, 29], "{findings:#?}");
+    assert!(findings[0].message.contains("parameter `data`"));
+    assert!(findings[1].message.contains("parameter `bump`"));
+}
@@ -0,0 +1,111 @@
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey,
+    pubkey::Pubkey,
+    signer::Signer,
+};
+
+use crate::{
+    anchor_discriminator,
+    bench::{Benchmark, Call},
+    Env, Program, Result, Variant, ARBITARY_CPI,
+};
+
+const MEMO_PROGRAM_ID: Pubkey = pubkey!("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+
+// The secure build only calls Metaplex Token Metadata
+const METADATA_PROGRAM_ID: Pubkey = pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
+/// The native vault returning tokens to their owner. The fix adds a single
+/// program id comparison.
+#[derive(Default)]
+pub struct VaultWithdraw;
+
+impl Benchmark for VaultWithdraw {
+    fn name(&self) -> &'static str {
+        "arbitary_cpi::vault_withdraw"
+    }
+
+    fn program(&self) -> Program {
+        ARBITARY_CPI
+    }
+
+    fn setup(&mut self, env: &mut Env) -> Result<Call> {
+        let user = env.payer.pubkey();
+        let mint = env.create_mint(6);
+        let (vault_authority, _) =
+            Pubkey::find_program_address(&[b"vault", user.as_ref()], &ARBITARY_CPI.id);
+        let vault_token_account = env.create_ata(&vault_authority, &mint);
+        env.mint_to(&mint, &vault_token_account, 1_000);
+        let user_token_account = env.create_ata(&user, &mint);
+
+        let instruction = Instruction::new_with_bytes(
+            ARBITARY_CPI.id,
+            &100u64.to_le_bytes(),
+            vec![
+                AccountMeta::new_readonly(spl_token::id(), false),
+                AccountMeta::new_readonly(mint, false),
+                AccountMeta::new_readonly(user, true),
+                AccountMeta::new_readonly(vault_authority, false),
+                AccountMeta::new(vault_token_account, false),
+                AccountMeta::new(user_token_account, false),
+            ],
+        );
+        Ok(Call {
+            instruction,
+            signers: vec![],
+        })
+    }
+}
+
+/// `insecure_transfer` against `secure_transfer`, whose `Program<'info, Token>`
+/// and `Account<'info, TokenAccount>` deserialize and check what the insecure
+/// build takes as `UncheckedAccount`.
+#[derive(Default)]
+pub struct Transfer;
+
+impl Benchmark for Transfer {
+    fn name(&self) -> &'static str {
+        "arbitary_cpi::transfer"
+    }
+
+    fn program(&self) -> Program {
+        ARBITARY_CPI
+    }
+
+    fn setup(&mut self, env: &mut Env) -> Result<Call> {
+        // Metaplex isn't loaded, so the memo program stands in for it on both builds
+        let memo = env
+            .account(&MEMO_PROGRAM_ID)
+            .expect("LiteSVM ships the memo program");
+        env.set_account(METADATA_PROGRAM_ID, memo);
+
+        let authority = env.payer.pubkey();
+        let mint = env.create_mint(6);
+        let source = env.create_ata(&authority, &mint);
+        let destination = env.create_ata(&Pubkey::new_unique(), &mint);
+        env.mint_to(&mint, &source, 100);
+
+        let instruction = match env.variant {
+            Variant::Insecure => "insecure_transfer",
+            Variant::Secure => "secure_transfer",
+        };
+        let mut data = anchor_discriminator(instruction).to_vec();
+        data.extend_from_slice(&40u64.to_le_bytes());
+        let instruction = Instruction::new_with_bytes(
+            ARBITARY_CPI.id,
+            &data,
+            vec![
+                AccountMeta::new_readonly(spl_token::id(), false),
+                AccountMeta::new(source, false),
+                AccountMeta::new(destination, false),
+                AccountMeta::new_readonly(authority, true),
+                AccountMeta::new(METADATA_PROGRAM_ID, false),
+            ],
+        );
+        Ok(Call {
+            instruction,
+            signers: vec![],
+        })
+    }
+}
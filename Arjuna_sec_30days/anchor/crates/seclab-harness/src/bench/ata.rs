@@ -0,0 +1,49 @@
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    signer::Signer,
+    system_program, sysvar,
+};
+use spl_associated_token_account::get_associated_token_address;
+
+use crate::{
+    anchor_discriminator,
+    bench::{Benchmark, Call},
+    Env, Program, Result, ATA,
+};
+
+/// `create_ata` for an account that doesn't exist yet, so `init` and
+/// `init_if_needed` both create it.
+#[derive(Default)]
+pub struct CreateAta;
+
+impl Benchmark for CreateAta {
+    fn name(&self) -> &'static str {
+        "ata::create_ata"
+    }
+
+    fn program(&self) -> Program {
+        ATA
+    }
+
+    fn setup(&mut self, env: &mut Env) -> Result<Call> {
+        let owner = env.payer.pubkey();
+        let mint = env.create_mint(6);
+        let instruction = Instruction::new_with_bytes(
+            ATA.id,
+            &anchor_discriminator("create_ata"),
+            vec![
+                AccountMeta::new(owner, true),
+                AccountMeta::new(get_associated_token_address(&owner, &mint), false),
+                AccountMeta::new_readonly(mint, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new_readonly(spl_token::id(), false),
+                AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+                AccountMeta::new_readonly(sysvar::rent::id(), false),
+            ],
+        );
+        Ok(Call {
+            instruction,
+            signers: vec![],
+        })
+    }
+}
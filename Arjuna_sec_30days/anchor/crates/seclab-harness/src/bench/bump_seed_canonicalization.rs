@@ -0,0 +1,71 @@
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signer::Signer,
+    system_program,
+};
+
+use crate::{
+    bench::{Benchmark, Call},
+    Env, Program, Result, BUMP_SEED_CANONICALIZATION,
+};
+
+const PDA_SEED: &[u8] = b"pda";
+
+/// `[tag, canonical bump]` for the payer's PDA. The insecure build checks the
+/// bump with one `create_program_address`, the secure one ignores it and calls
+/// `find_program_address`, which tries every bump from 255 down.
+fn call(env: &Env, tag: u8) -> Call {
+    let payer = env.payer.pubkey();
+    let (pda, bump) =
+        Pubkey::find_program_address(&[PDA_SEED, payer.as_ref()], &BUMP_SEED_CANONICALIZATION.id);
+    let instruction = Instruction::new_with_bytes(
+        BUMP_SEED_CANONICALIZATION.id,
+        &[tag, bump],
+        vec![
+            AccountMeta::new(payer, true),
+            AccountMeta::new(pda, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    );
+    Call {
+        instruction,
+        signers: vec![],
+    }
+}
+
+/// Creates the canonical PDA.
+#[derive(Default)]
+pub struct Create;
+
+impl Benchmark for Create {
+    fn name(&self) -> &'static str {
+        "bump_seed_canonicalization::create"
+    }
+
+    fn program(&self) -> Program {
+        BUMP_SEED_CANONICALIZATION
+    }
+
+    fn setup(&mut self, env: &mut Env) -> Result<Call> {
+        Ok(call(env, 0))
+    }
+}
+
+/// Validates the canonical PDA.
+#[derive(Default)]
+pub struct Validate;
+
+impl Benchmark for Validate {
+    fn name(&self) -> &'static str {
+        "bump_seed_canonicalization::validate"
+    }
+
+    fn program(&self) -> Program {
+        BUMP_SEED_CANONICALIZATION
+    }
+
+    fn setup(&mut self, env: &mut Env) -> Result<Call> {
+        Ok(call(env, 1))
+    }
+}
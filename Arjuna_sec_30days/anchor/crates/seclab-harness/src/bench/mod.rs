@@ -0,0 +1,195 @@
+//! Compute-unit benchmark: the honest path of each instruction, measured on
+//! both builds so the cost of every fix can be read off and kept in check.
+//!
+//! Measurements are compared against a baseline file ([`BASELINE`] in the
+//! workspace), a table keyed by benchmark name:
+//!
+//! ```toml
+//! ["bump_seed_canonicalization::create"]
+//! insecure = 3000
+//! secure = 5500
+//! ```
+//!
+//! A build regresses when it costs more than [`THRESHOLD_PERCENT`] over its
+//! baseline, which leaves room for noise from toolchain and dependency bumps.
+//!
+//! To add a benchmark, implement [`Benchmark`] in the module for its program,
+//! derive `Default` for it and add it to [`REGISTRY`].
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::{instruction::Instruction, signature::Keypair};
+
+use crate::{workspace_dir, Env, Error, Program, Result, Variant};
+
+mod arbitary_cpi;
+mod ata;
+mod bump_seed_canonicalization;
+mod ownership_verification;
+mod signer_checks;
+
+/// File name of the checked-in baseline, next to `Anchor.toml`.
+pub const BASELINE: &str = "compute-units.toml";
+
+/// How far, in percent of the baseline, a build may get more expensive before
+/// it counts as a regression.
+pub const THRESHOLD_PERCENT: u64 = 2;
+
+/// The checked-in baseline of this workspace.
+pub fn baseline_path() -> PathBuf {
+    workspace_dir().join(BASELINE)
+}
+
+pub const REGISTRY: &[fn() -> Box<dyn Benchmark>] = &[
+    boxed::<arbitary_cpi::VaultWithdraw>,
+    boxed::<arbitary_cpi::Transfer>,
+    boxed::<ata::CreateAta>,
+    boxed::<bump_seed_canonicalization::Create>,
+    boxed::<bump_seed_canonicalization::Validate>,
+    boxed::<ownership_verification::Withdraw>,
+    boxed::<signer_checks::SetAdmin>,
+];
+
+fn boxed<B: Benchmark + Default + 'static>() -> Box<dyn Benchmark> {
+    Box::<B>::default()
+}
+
+/// A fresh instance of every registered benchmark.
+pub fn all() -> Vec<Box<dyn Benchmark>> {
+    REGISTRY.iter().map(|new| new()).collect()
+}
+
+/// One instruction, called the way a legitimate user would.
+pub trait Benchmark {
+    /// `program::instruction`, also the benchmark's key in the baseline.
+    fn name(&self) -> &'static str;
+
+    fn program(&self) -> Program;
+
+    /// Creates the accounts the instruction needs and builds it for `env`'s variant.
+    fn setup(&mut self, env: &mut Env) -> Result<Call>;
+}
+
+/// The instruction a [`Benchmark`] measures.
+pub struct Call {
+    pub instruction: Instruction,
+    /// Keypairs that have to sign besides the payer.
+    pub signers: Vec<Keypair>,
+}
+
+/// Compute units one instruction consumed on each build.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Measurement {
+    pub insecure: u64,
+    pub secure: u64,
+}
+
+impl Measurement {
+    pub fn get(&self, variant: Variant) -> u64 {
+        match variant {
+            Variant::Insecure => self.insecure,
+            Variant::Secure => self.secure,
+        }
+    }
+
+    /// What the fix costs, in compute units. Negative if it saves some.
+    pub fn fix_cost(&self) -> i64 {
+        self.secure as i64 - self.insecure as i64
+    }
+}
+
+/// Runs `benchmark` on a fresh `variant` build and returns the compute units
+/// its instruction consumed. A failed instruction is an error: both builds are
+/// expected to accept honest calls.
+pub fn measure(benchmark: &mut dyn Benchmark, variant: Variant) -> Result<u64> {
+    let mut env = Env::new(variant);
+    env.load(&benchmark.program())?;
+    let call = benchmark.setup(&mut env)?;
+    let signers: Vec<_> = call.signers.iter().collect();
+    let meta = env.send(&[call.instruction], &signers)?;
+    Ok(meta.compute_units_consumed)
+}
+
+/// Measures `benchmark` on both builds.
+pub fn measure_both(benchmark: &mut dyn Benchmark) -> Result<Measurement> {
+    Ok(Measurement {
+        insecure: measure(benchmark, Variant::Insecure)?,
+        secure: measure(benchmark, Variant::Secure)?,
+    })
+}
+
+/// Saved measurements, by benchmark name.
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Baseline {
+    pub measurements: BTreeMap<String, Measurement>,
+}
+
+/// A build that got more expensive than its baseline.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Regression {
+    pub benchmark: String,
+    pub variant: Variant,
+    pub baseline: u64,
+    pub current: u64,
+}
+
+impl Baseline {
+    pub fn load(path: &Path) -> Result<Self> {
+        let error = |message: String| Error::Manifest {
+            path: path.to_path_buf(),
+            message,
+        };
+        let source = fs::read_to_string(path).map_err(|err| error(err.to_string()))?;
+        toml::from_str(&source).map_err(|err| error(err.to_string()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let source = toml::to_string(self).map_err(|err| Error::Manifest {
+            path: path.to_path_buf(),
+            message: err.to_string(),
+        })?;
+        fs::write(path, source)?;
+        Ok(())
+    }
+
+    /// Every measurement in `current` that costs more than [`THRESHOLD_PERCENT`]
+    /// over its baseline. Benchmarks missing from either side are not compared,
+    /// see [`Baseline::missing`].
+    pub fn regressions(&self, current: &Baseline) -> Vec<Regression> {
+        let mut regressions = Vec::new();
+        for (name, now) in &current.measurements {
+            let Some(before) = self.measurements.get(name) else {
+                continue;
+            };
+            for variant in Variant::ALL {
+                let allowed = before.get(variant) * (100 + THRESHOLD_PERCENT) / 100;
+                if now.get(variant) > allowed {
+                    regressions.push(Regression {
+                        benchmark: name.clone(),
+                        variant,
+                        baseline: before.get(variant),
+                        current: now.get(variant),
+                    });
+                }
+            }
+        }
+        regressions
+    }
+
+    /// Benchmarks measured in `current` that have no baseline yet.
+    pub fn missing<'a>(&self, current: &'a Baseline) -> Vec<&'a str> {
+        current
+            .measurements
+            .keys()
+            .filter(|name| !self.measurements.contains_key(*name))
+            .map(String::as_str)
+            .collect()
+    }
+}
@@ -0,0 +1,74 @@
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+    system_program,
+};
+
+use crate::{
+    bench::{Benchmark, Call},
+    Env, Program, Result, OWNERSHIP_VERIFICATION,
+};
+
+/// The real admin withdrawing from the vault, with its `ProgramState` in an
+/// account the program owns.
+#[derive(Default)]
+pub struct Withdraw;
+
+impl Benchmark for Withdraw {
+    fn name(&self) -> &'static str {
+        "ownership_verification::withdraw"
+    }
+
+    fn program(&self) -> Program {
+        OWNERSHIP_VERIFICATION
+    }
+
+    fn setup(&mut self, env: &mut Env) -> Result<Call> {
+        let vault = Pubkey::new_unique();
+        env.set_account(
+            vault,
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                data: vec![],
+                owner: OWNERSHIP_VERIFICATION.id,
+                executable: false,
+                rent_epoch: 0,
+            },
+        );
+
+        // `ProgramState { admin, vault_amount: LAMPORTS_PER_SOL }`
+        let admin = Keypair::new();
+        let mut state = admin.pubkey().to_bytes().to_vec();
+        state.extend_from_slice(&LAMPORTS_PER_SOL.to_le_bytes());
+        env.set_account(
+            admin.pubkey(),
+            Account {
+                lamports: env.rent_exempt(state.len()),
+                data: state,
+                owner: OWNERSHIP_VERIFICATION.id,
+                executable: false,
+                rent_epoch: 0,
+            },
+        );
+
+        let mut data = vec![0];
+        data.extend_from_slice(&(LAMPORTS_PER_SOL / 2).to_le_bytes());
+        let instruction = Instruction::new_with_bytes(
+            OWNERSHIP_VERIFICATION.id,
+            &data,
+            vec![
+                AccountMeta::new(admin.pubkey(), true),
+                AccountMeta::new(vault, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        );
+        Ok(Call {
+            instruction,
+            signers: vec![admin],
+        })
+    }
+}
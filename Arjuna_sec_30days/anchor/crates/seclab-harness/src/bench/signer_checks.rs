@@ -0,0 +1,54 @@
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+};
+
+use crate::{
+    bench::{Benchmark, Call},
+    Env, Program, Result, SIGNER_CHECKS,
+};
+
+/// The vault admin, signing, handing the vault to a new admin.
+#[derive(Default)]
+pub struct SetAdmin;
+
+impl Benchmark for SetAdmin {
+    fn name(&self) -> &'static str {
+        "signer_checks::set_admin"
+    }
+
+    fn program(&self) -> Program {
+        SIGNER_CHECKS
+    }
+
+    fn setup(&mut self, env: &mut Env) -> Result<Call> {
+        let admin = Keypair::new();
+        let vault = Pubkey::new_unique();
+        env.set_account(
+            vault,
+            Account {
+                lamports: env.rent_exempt(32),
+                data: admin.pubkey().to_bytes().to_vec(),
+                owner: SIGNER_CHECKS.id,
+                executable: false,
+                rent_epoch: 0,
+            },
+        );
+
+        let instruction = Instruction::new_with_bytes(
+            SIGNER_CHECKS.id,
+            Pubkey::new_unique().as_ref(),
+            vec![
+                AccountMeta::new(vault, false),
+                AccountMeta::new_readonly(admin.pubkey(), true),
+            ],
+        );
+        Ok(Call {
+            instruction,
+            signers: vec![admin],
+        })
+    }
+}
@@ -0,0 +1,167 @@
+//! `checklist.toml`: which lab code backs each item of `solana_security_checklist.md`.
+
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+
+use crate::{exploits, Error, Result};
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+    /// The checklist, relative to the manifest.
+    pub checklist: PathBuf,
+    #[serde(rename = "item")]
+    pub items: Vec<Item>,
+    /// Directory the manifest's paths are relative to.
+    #[serde(skip)]
+    pub dir: PathBuf,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Item {
+    pub anchor: String,
+    pub insecure: Option<PathBuf>,
+    pub secure: Option<PathBuf>,
+    #[serde(default)]
+    pub exploits: Vec<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Coverage {
+    /// At least one registered exploit proves it.
+    Exploit,
+    /// Vulnerable code exists, but nothing runs it.
+    Program,
+    Prose,
+}
+
+impl Coverage {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Coverage::Exploit => "exploit",
+            Coverage::Program => "program",
+            Coverage::Prose => "prose",
+        }
+    }
+}
+
+impl Item {
+    pub fn coverage(&self) -> Coverage {
+        if !self.exploits.is_empty() {
+            Coverage::Exploit
+        } else if self.insecure.is_some() {
+            Coverage::Program
+        } else {
+            Coverage::Prose
+        }
+    }
+}
+
+/// An entry of the checklist's table of contents.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ChecklistItem {
+    pub anchor: String,
+    pub title: String,
+}
+
+/// The workspace's `checklist.toml`.
+pub fn manifest_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../checklist.toml")
+}
+
+impl Manifest {
+    pub fn load(path: &Path) -> Result<Self> {
+        let error = |message: String| Error::Manifest {
+            path: path.to_path_buf(),
+            message,
+        };
+        let source = fs::read_to_string(path).map_err(|err| error(err.to_string()))?;
+        let mut manifest: Manifest =
+            toml::from_str(&source).map_err(|err| error(err.to_string()))?;
+        manifest.dir = path.parent().unwrap_or(Path::new(".")).to_path_buf();
+        Ok(manifest)
+    }
+
+    pub fn checklist_path(&self) -> PathBuf {
+        self.dir.join(&self.checklist)
+    }
+
+    /// The items listed in the checklist's table of contents.
+    pub fn checklist_items(&self) -> Result<Vec<ChecklistItem>> {
+        let path = self.checklist_path();
+        let markdown = fs::read_to_string(&path).map_err(|err| Error::Manifest {
+            path,
+            message: err.to_string(),
+        })?;
+        Ok(parse_checklist(&markdown))
+    }
+
+    /// Everything in the manifest that doesn't match the checklist, the tree or
+    /// the exploit registry.
+    pub fn problems(&self) -> Result<Vec<String>> {
+        let checklist = self.checklist_items()?;
+        let registered: HashSet<_> = exploits::all()
+            .iter()
+            .map(|exploit| exploit.name())
+            .collect();
+        let mut problems = Vec::new();
+
+        let mut seen = HashSet::new();
+        for item in &self.items {
+            if !seen.insert(item.anchor.as_str()) {
+                problems.push(format!("`{}` is listed twice", item.anchor));
+            }
+            if !checklist.iter().any(|entry| entry.anchor == item.anchor) {
+                problems.push(format!("`{}` is not in the checklist", item.anchor));
+            }
+            for path in [&item.insecure, &item.secure].into_iter().flatten() {
+                if !self.dir.join(path).is_file() {
+                    problems.push(format!(
+                        "`{}`: {} does not exist",
+                        item.anchor,
+                        path.display()
+                    ));
+                }
+            }
+            if item.insecure.is_some() != item.secure.is_some() {
+                problems.push(format!(
+                    "`{}` needs both `insecure` and `secure`",
+                    item.anchor
+                ));
+            }
+            for exploit in &item.exploits {
+                if !registered.contains(exploit.as_str()) {
+                    problems.push(format!("`{}`: no exploit named `{exploit}`", item.anchor));
+                }
+            }
+        }
+        for entry in &checklist {
+            if !seen.contains(entry.anchor.as_str()) {
+                problems.push(format!("checklist item `{}` is missing", entry.anchor));
+            }
+        }
+        Ok(problems)
+    }
+}
+
+/// Reads the `+ [Title](#anchor)` entries of the checklist's table of contents.
+pub fn parse_checklist(markdown: &str) -> Vec<ChecklistItem> {
+    markdown
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("+ ["))
+        .filter_map(|entry| {
+            let (title, rest) = entry.split_once("](#")?;
+            let anchor = rest.strip_suffix(')')?;
+            Some(ChecklistItem {
+                anchor: anchor.to_string(),
+                title: title.to_string(),
+            })
+        })
+        .collect()
+}
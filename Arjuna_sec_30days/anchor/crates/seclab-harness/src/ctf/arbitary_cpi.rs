@@ -0,0 +1,106 @@
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+};
+
+use crate::{
+    ctf::{Challenge, Player, PLAYER_PROGRAM_ID},
+    Env, Program, Result, ARBITARY_CPI,
+};
+
+const FLAG_TOKENS: u64 = 1_000_000;
+
+/// Instruction data tag of the victim wallet's call into the player's program.
+pub const FRONTEND: u8 = 1;
+
+/// Flag: the tokens in a victim's vault. Captured once they are in the player
+/// wallet's token account.
+///
+/// The victim withdraws from their vault through the player's dApp. Their wallet
+/// first calls the player's program with `[FRONTEND]` and no accounts, and uses
+/// the 32 bytes of return data, if any, as the `user_token_account` of the
+/// withdrawal (their own token account otherwise). The player's program is
+/// passed as `token_program`.
+///
+/// Challenge accounts: `4.` mint, `5.` vault token account (writable),
+/// `6.` player token account (writable), `7.` SPL Token program.
+#[derive(Default)]
+pub struct VaultDrain {
+    victim: Option<Keypair>,
+    mint: Pubkey,
+    vault_authority: Pubkey,
+    vault_token_account: Pubkey,
+    victim_token_account: Pubkey,
+    player_token_account: Pubkey,
+}
+
+impl Challenge for VaultDrain {
+    fn name(&self) -> &'static str {
+        "arbitrary-cpi"
+    }
+
+    fn program(&self) -> Program {
+        ARBITARY_CPI
+    }
+
+    fn points(&self) -> u32 {
+        300
+    }
+
+    fn setup(&mut self, env: &mut Env, player: &Player) -> Result<()> {
+        let victim = env.funded_keypair(LAMPORTS_PER_SOL);
+        self.mint = env.create_mint(6);
+        (self.vault_authority, _) =
+            Pubkey::find_program_address(&[b"vault", victim.pubkey().as_ref()], &ARBITARY_CPI.id);
+        self.vault_token_account = env.create_ata(&self.vault_authority, &self.mint);
+        env.mint_to(&self.mint, &self.vault_token_account, FLAG_TOKENS);
+        self.victim_token_account = env.create_ata(&victim.pubkey(), &self.mint);
+        self.player_token_account = env.create_ata(&player.wallet.pubkey(), &self.mint);
+        self.victim = Some(victim);
+        Ok(())
+    }
+
+    fn accounts(&self, _player: &Player) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new_readonly(self.mint, false),
+            AccountMeta::new(self.vault_token_account, false),
+            AccountMeta::new(self.player_token_account, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ]
+    }
+
+    fn victim(&mut self, env: &mut Env, _player: &Player) -> Result<()> {
+        let victim = self.victim.as_ref().expect("setup ran");
+
+        let frontend = Instruction::new_with_bytes(PLAYER_PROGRAM_ID, &[FRONTEND], vec![]);
+        let destination = env
+            .send(&[frontend], &[])
+            .ok()
+            .filter(|meta| meta.return_data.program_id == PLAYER_PROGRAM_ID)
+            .and_then(|meta| Pubkey::try_from(meta.return_data.data.as_slice()).ok())
+            .unwrap_or(self.victim_token_account);
+
+        let withdraw = Instruction::new_with_bytes(
+            ARBITARY_CPI.id,
+            &1u64.to_le_bytes(),
+            vec![
+                AccountMeta::new_readonly(PLAYER_PROGRAM_ID, false),
+                AccountMeta::new_readonly(self.mint, false),
+                AccountMeta::new_readonly(victim.pubkey(), true),
+                AccountMeta::new_readonly(self.vault_authority, false),
+                AccountMeta::new(self.vault_token_account, false),
+                AccountMeta::new(destination, false),
+            ],
+        );
+        // The victim doesn't care whether this goes through, the player's solve call comes next either way
+        let _ = env.send(&[withdraw], &[victim]);
+        Ok(())
+    }
+
+    fn captured(&self, env: &Env, _player: &Player) -> bool {
+        env.token_balance(&self.player_token_account) >= FLAG_TOKENS
+    }
+}
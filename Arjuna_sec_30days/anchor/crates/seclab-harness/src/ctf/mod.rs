@@ -0,0 +1,172 @@
+//! Capture-the-flag mode: players submit their own attacker program and the
+//! runner checks whether it captured the flag of a challenge.
+//!
+//! Each challenge is an insecure lab program guarding a flag (tokens, lamports
+//! or admin rights). The player's `.so` is deployed at [`PLAYER_PROGRAM_ID`]
+//! next to it, and called once with instruction data `[SOLVE]` and accounts:
+//!
+//! ```text
+//! 0. player wallet            signer, writable, 10 SOL
+//! 1. player PDA               writable, `find_program_address(&[PLAYER_SEED], PLAYER_PROGRAM_ID)`
+//! 2. system program
+//! 3. challenge program
+//! 4.. challenge accounts      see each challenge's docs
+//! ```
+//!
+//! Some challenges also have a victim who uses the player's program before the
+//! solve call, see [`Challenge::victim`].
+
+use std::path::Path;
+
+use solana_sdk::{
+    hash::hash,
+    instruction::{AccountMeta, Instruction},
+    native_token::LAMPORTS_PER_SOL,
+    pubkey,
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+    system_program,
+};
+
+use crate::{Env, Program, Result, Variant};
+
+mod arbitary_cpi;
+mod ownership_verification;
+mod signer_checks;
+
+pub use arbitary_cpi::FRONTEND;
+
+/// Where the player's program is deployed.
+pub const PLAYER_PROGRAM_ID: Pubkey = pubkey!("8V5KZwfnD2ekLDmKFbWtW5iXD4vdHQZsjv2EigHZ3FBr");
+
+pub const PLAYER_SEED: &[u8] = b"player";
+
+/// Instruction data tag of the runner's call into the player's program.
+pub const SOLVE: u8 = 0;
+
+pub const CHALLENGES: &[fn() -> Box<dyn Challenge>] = &[
+    boxed::<arbitary_cpi::VaultDrain>,
+    boxed::<ownership_verification::AdminVault>,
+    boxed::<signer_checks::AdminTakeover>,
+];
+
+fn boxed<C: Challenge + Default + 'static>() -> Box<dyn Challenge> {
+    Box::<C>::default()
+}
+
+/// A fresh instance of every challenge.
+pub fn all() -> Vec<Box<dyn Challenge>> {
+    CHALLENGES.iter().map(|new| new()).collect()
+}
+
+/// The player's keys, as seen by a challenge.
+pub struct Player {
+    pub wallet: Keypair,
+    pub pda: Pubkey,
+}
+
+pub trait Challenge {
+    /// Kebab-case name, also the file name (`<name>.so`) players submit under.
+    fn name(&self) -> &'static str;
+
+    /// The insecure program guarding the flag.
+    fn program(&self) -> Program;
+
+    fn points(&self) -> u32;
+
+    /// Creates the flag and whatever guards it.
+    fn setup(&mut self, env: &mut Env, player: &Player) -> Result<()>;
+
+    /// The challenge accounts passed to the player's program after the common ones.
+    fn accounts(&self, player: &Player) -> Vec<AccountMeta>;
+
+    /// What the challenge's victim does with the player's program before the solve call.
+    fn victim(&mut self, _env: &mut Env, _player: &Player) -> Result<()> {
+        Ok(())
+    }
+
+    fn captured(&self, env: &Env, player: &Player) -> bool;
+}
+
+/// The result of one submission against one challenge.
+#[derive(Debug)]
+pub struct Capture {
+    pub challenge: &'static str,
+    pub points: u32,
+    pub captured: bool,
+    /// Only revealed once captured.
+    pub flag: Option<String>,
+    /// Why the run failed, if the player's transaction or the runner did.
+    pub error: Option<String>,
+}
+
+impl Capture {
+    pub fn score(&self) -> u32 {
+        if self.captured {
+            self.points
+        } else {
+            0
+        }
+    }
+}
+
+/// The flag of `challenge` for an event keyed by `secret`.
+pub fn flag(secret: &str, challenge: &str) -> String {
+    let digest = hash(format!("{secret}:{challenge}").as_bytes());
+    let hex: String = digest.to_bytes()[..12]
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect();
+    format!("flag{{{hex}}}")
+}
+
+/// Runs the player's program at `submission` against `challenge`.
+pub fn play(challenge: &mut dyn Challenge, submission: &Path, secret: &str) -> Capture {
+    let mut capture = Capture {
+        challenge: challenge.name(),
+        points: challenge.points(),
+        captured: false,
+        flag: None,
+        error: None,
+    };
+    match try_play(challenge, submission) {
+        Ok((captured, error)) => {
+            capture.captured = captured;
+            capture.error = error;
+        }
+        Err(err) => capture.error = Some(err.to_string()),
+    }
+    if capture.captured {
+        capture.flag = Some(flag(secret, capture.challenge));
+    }
+    capture
+}
+
+fn try_play(challenge: &mut dyn Challenge, submission: &Path) -> Result<(bool, Option<String>)> {
+    let mut env = Env::new(Variant::Insecure);
+    env.load(&challenge.program())?;
+    env.deploy(PLAYER_PROGRAM_ID, submission)?;
+
+    let player = Player {
+        wallet: env.funded_keypair(10 * LAMPORTS_PER_SOL),
+        pda: Pubkey::find_program_address(&[PLAYER_SEED], &PLAYER_PROGRAM_ID).0,
+    };
+    challenge.setup(&mut env, &player)?;
+    challenge.victim(&mut env, &player)?;
+
+    let mut accounts = vec![
+        AccountMeta::new(player.wallet.pubkey(), true),
+        AccountMeta::new(player.pda, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(challenge.program().id, false),
+    ];
+    accounts.extend(challenge.accounts(&player));
+    let solve = Instruction::new_with_bytes(PLAYER_PROGRAM_ID, &[SOLVE], accounts);
+    let error = env
+        .send(&[solve], &[&player.wallet])
+        .err()
+        .map(|failed| failed.err.to_string());
+
+    Ok((challenge.captured(&env, &player), error))
+}
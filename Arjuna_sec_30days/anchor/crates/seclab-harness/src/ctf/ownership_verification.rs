@@ -0,0 +1,55 @@
+use solana_sdk::{
+    account::Account, instruction::AccountMeta, native_token::LAMPORTS_PER_SOL, pubkey::Pubkey,
+};
+
+use crate::{
+    ctf::{Challenge, Player},
+    Env, Program, Result, OWNERSHIP_VERIFICATION,
+};
+
+const FLAG_LAMPORTS: u64 = 25 * LAMPORTS_PER_SOL;
+
+/// Flag: the 25 SOL in the admin vault. Captured once the vault is empty.
+///
+/// Challenge accounts: `4.` vault (writable).
+#[derive(Default)]
+pub struct AdminVault {
+    vault: Pubkey,
+}
+
+impl Challenge for AdminVault {
+    fn name(&self) -> &'static str {
+        "ownership-verification"
+    }
+
+    fn program(&self) -> Program {
+        OWNERSHIP_VERIFICATION
+    }
+
+    fn points(&self) -> u32 {
+        200
+    }
+
+    fn setup(&mut self, env: &mut Env, _player: &Player) -> Result<()> {
+        self.vault = Pubkey::new_unique();
+        env.set_account(
+            self.vault,
+            Account {
+                lamports: FLAG_LAMPORTS,
+                data: vec![],
+                owner: OWNERSHIP_VERIFICATION.id,
+                executable: false,
+                rent_epoch: 0,
+            },
+        );
+        Ok(())
+    }
+
+    fn accounts(&self, _player: &Player) -> Vec<AccountMeta> {
+        vec![AccountMeta::new(self.vault, false)]
+    }
+
+    fn captured(&self, env: &Env, _player: &Player) -> bool {
+        env.lamports(&self.vault) == 0
+    }
+}
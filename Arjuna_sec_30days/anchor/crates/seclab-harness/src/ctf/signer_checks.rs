@@ -0,0 +1,57 @@
+use solana_sdk::{account::Account, instruction::AccountMeta, pubkey::Pubkey, signer::Signer};
+
+use crate::{
+    ctf::{Challenge, Player},
+    Env, Program, Result, SIGNER_CHECKS,
+};
+
+/// Flag: admin rights over the vault. Captured once the vault's admin is the player wallet.
+///
+/// Challenge accounts: `4.` vault (writable), `5.` current admin.
+#[derive(Default)]
+pub struct AdminTakeover {
+    vault: Pubkey,
+    admin: Pubkey,
+}
+
+impl Challenge for AdminTakeover {
+    fn name(&self) -> &'static str {
+        "signer-checks"
+    }
+
+    fn program(&self) -> Program {
+        SIGNER_CHECKS
+    }
+
+    fn points(&self) -> u32 {
+        100
+    }
+
+    fn setup(&mut self, env: &mut Env, _player: &Player) -> Result<()> {
+        self.admin = Pubkey::new_unique();
+        self.vault = Pubkey::new_unique();
+        env.set_account(
+            self.vault,
+            Account {
+                lamports: env.rent_exempt(32),
+                data: self.admin.to_bytes().to_vec(),
+                owner: SIGNER_CHECKS.id,
+                executable: false,
+                rent_epoch: 0,
+            },
+        );
+        Ok(())
+    }
+
+    fn accounts(&self, _player: &Player) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new(self.vault, false),
+            AccountMeta::new_readonly(self.admin, false),
+        ]
+    }
+
+    fn captured(&self, env: &Env, player: &Player) -> bool {
+        env.account(&self.vault)
+            .is_some_and(|vault| vault.data == player.wallet.pubkey().to_bytes())
+    }
+}
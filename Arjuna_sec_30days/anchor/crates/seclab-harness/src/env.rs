@@ -0,0 +1,252 @@
+use std::path::{Path, PathBuf};
+
+use litesvm::{
+    types::{FailedTransactionMetadata, TransactionResult},
+    LiteSVM,
+};
+use solana_sdk::{
+    account::Account,
+    hash::hash,
+    instruction::Instruction,
+    native_token::LAMPORTS_PER_SOL,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+    system_instruction,
+    transaction::{Transaction, TransactionError},
+};
+use spl_associated_token_account::{
+    get_associated_token_address, instruction::create_associated_token_account,
+};
+
+use crate::{fixture::Recording, Program, Variant};
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("{} is not built, run scripts/build-variants.sh", .0.display())]
+    MissingArtifact(PathBuf),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("transaction failed: {}", .0.err)]
+    Transaction(Box<FailedTransactionMetadata>),
+    #[error("not compromised: {0}")]
+    NotCompromised(String),
+    #[error("{}: {message}", .path.display())]
+    Manifest { path: PathBuf, message: String },
+    #[error("invalid fixture: {0}")]
+    Fixture(String),
+}
+
+impl Error {
+    /// The `ProgramError` behind a failed transaction, if it failed inside an instruction.
+    pub fn program_error(&self) -> Option<ProgramError> {
+        match self {
+            Error::Transaction(failed) => instruction_error(&failed.err),
+            _ => None,
+        }
+    }
+}
+
+impl From<FailedTransactionMetadata> for Error {
+    fn from(failed: FailedTransactionMetadata) -> Self {
+        Error::Transaction(Box::new(failed))
+    }
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// A fresh LiteSVM instance with the SPL programs, a funded payer and the
+/// `variant` builds of whichever lab programs get [`Env::load`]ed into it.
+pub struct Env {
+    pub svm: LiteSVM,
+    pub variant: Variant,
+    pub payer: Keypair,
+    /// The result of every [`Env::send`], oldest first.
+    pub history: Vec<TransactionResult>,
+    /// Set by [`Env::start_recording`].
+    pub(crate) recording: Option<Recording>,
+}
+
+impl Env {
+    pub fn new(variant: Variant) -> Self {
+        Env::with_svm(LiteSVM::new(), variant)
+    }
+
+    /// Like [`Env::new`], but transactions need neither valid signatures nor a
+    /// recent blockhash, and may repeat. For replaying recorded transactions.
+    pub fn unchecked(variant: Variant) -> Self {
+        let svm = LiteSVM::new()
+            .with_sigverify(false)
+            .with_blockhash_check(false)
+            .with_transaction_history(0);
+        Env::with_svm(svm, variant)
+    }
+
+    fn with_svm(mut svm: LiteSVM, variant: Variant) -> Self {
+        let payer = Keypair::new();
+        svm.airdrop(&payer.pubkey(), 100 * LAMPORTS_PER_SOL)
+            .expect("airdrop to payer");
+
+        Env {
+            svm,
+            variant,
+            payer,
+            history: Vec::new(),
+            recording: None,
+        }
+    }
+
+    /// Records every transaction sent from now on, and the accounts they touch as
+    /// they were before, for a [`Fixture`](crate::fixture::Fixture).
+    pub fn start_recording(&mut self) {
+        self.recording = Some(Recording::default());
+    }
+
+    /// Deploys this env's build of `program` at its declared program id.
+    pub fn load(&mut self, program: &Program) -> Result<()> {
+        self.deploy(program.id, &program.artifact(self.variant))
+    }
+
+    /// Deploys an attacker program, which has a single build, at its declared program id.
+    pub fn load_attacker(&mut self, program: &Program) -> Result<()> {
+        self.deploy(program.id, &program.attacker_artifact())
+    }
+
+    /// Deploys the `.so` at `path` as program `id`.
+    pub fn deploy(&mut self, id: Pubkey, path: &Path) -> Result<()> {
+        if !path.exists() {
+            return Err(Error::MissingArtifact(path.to_path_buf()));
+        }
+        self.svm.add_program_from_file(id, path)?;
+        Ok(())
+    }
+
+    /// Sends `instructions` in one transaction paid for by [`Env::payer`].
+    /// `signers` are the extra keypairs the instructions need, not the payer.
+    // LiteSVM's own result type, kept as is so callers can read logs and errors.
+    #[allow(clippy::result_large_err)]
+    pub fn send(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> TransactionResult {
+        let mut keypairs = vec![&self.payer];
+        keypairs.extend_from_slice(signers);
+        let tx = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.payer.pubkey()),
+            &keypairs,
+            self.svm.latest_blockhash(),
+        );
+        if let Some(recording) = &mut self.recording {
+            recording.observe(&self.svm, &tx);
+        }
+        let result = self.svm.send_transaction(tx);
+        // Identical transactions would otherwise be rejected as already processed.
+        self.svm.expire_blockhash();
+        self.history.push(result.clone());
+        result
+    }
+
+    pub fn account(&self, address: &Pubkey) -> Option<Account> {
+        self.svm.get_account(address)
+    }
+
+    pub fn set_account(&mut self, address: Pubkey, account: Account) {
+        self.svm.set_account(address, account).expect("set_account");
+    }
+
+    pub fn lamports(&self, address: &Pubkey) -> u64 {
+        self.svm.get_balance(address).unwrap_or(0)
+    }
+
+    pub fn rent_exempt(&self, data_len: usize) -> u64 {
+        self.svm.minimum_balance_for_rent_exemption(data_len)
+    }
+
+    /// A new keypair holding `lamports`.
+    pub fn funded_keypair(&mut self, lamports: u64) -> Keypair {
+        let keypair = Keypair::new();
+        self.svm
+            .airdrop(&keypair.pubkey(), lamports)
+            .expect("airdrop");
+        keypair
+    }
+
+    /// Creates an SPL Token mint with [`Env::payer`] as mint authority.
+    pub fn create_mint(&mut self, decimals: u8) -> Pubkey {
+        let mint = Keypair::new();
+        let space = spl_token::state::Mint::LEN;
+        let create = system_instruction::create_account(
+            &self.payer.pubkey(),
+            &mint.pubkey(),
+            self.rent_exempt(space),
+            space as u64,
+            &spl_token::id(),
+        );
+        let init = spl_token::instruction::initialize_mint2(
+            &spl_token::id(),
+            &mint.pubkey(),
+            &self.payer.pubkey(),
+            None,
+            decimals,
+        )
+        .expect("initialize_mint2");
+        self.send(&[create, init], &[&mint]).expect("create mint");
+        mint.pubkey()
+    }
+
+    /// Creates `owner`'s associated token account for `mint`, paid for by [`Env::payer`].
+    pub fn create_ata(&mut self, owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+        let ix =
+            create_associated_token_account(&self.payer.pubkey(), owner, mint, &spl_token::id());
+        self.send(&[ix], &[])
+            .expect("create associated token account");
+        get_associated_token_address(owner, mint)
+    }
+
+    /// Mints `amount` of `mint` into `destination`; the mint must come from [`Env::create_mint`].
+    pub fn mint_to(&mut self, mint: &Pubkey, destination: &Pubkey, amount: u64) {
+        let ix = spl_token::instruction::mint_to(
+            &spl_token::id(),
+            mint,
+            destination,
+            &self.payer.pubkey(),
+            &[],
+            amount,
+        )
+        .expect("mint_to");
+        self.send(&[ix], &[]).expect("mint tokens");
+    }
+
+    pub fn token_balance(&self, token_account: &Pubkey) -> u64 {
+        self.account(token_account)
+            .and_then(|account| spl_token::state::Account::unpack(&account.data).ok())
+            .map_or(0, |account| account.amount)
+    }
+}
+
+/// The `ProgramError` a transaction failed with, if it failed inside an instruction.
+pub fn program_error(result: &TransactionResult) -> Option<ProgramError> {
+    match result {
+        Err(failed) => instruction_error(&failed.err),
+        Ok(_) => None,
+    }
+}
+
+fn instruction_error(err: &TransactionError) -> Option<ProgramError> {
+    match err {
+        TransactionError::InstructionError(_, err) => ProgramError::try_from(err.clone()).ok(),
+        _ => None,
+    }
+}
+
+/// The 8-byte Anchor instruction discriminator for `name`.
+pub fn anchor_discriminator(name: &str) -> [u8; 8] {
+    let digest = hash(format!("global:{name}").as_bytes());
+    let mut discriminator = [0; 8];
+    discriminator.copy_from_slice(&digest.to_bytes()[..8]);
+    discriminator
+}
@@ -0,0 +1,173 @@
+use std::fmt;
+
+use litesvm::types::TransactionResult;
+use serde::Serialize;
+use solana_sdk::program_error::ProgramError;
+
+use crate::{Env, Error, Program, Result, Variant};
+
+/// An attack on one of the lab programs, written against an [`Env`].
+///
+/// [`run`] deploys [`Exploit::program`] and then calls `setup`, `attack` and
+/// `assert_compromised` in that order on the same value, so state found during
+/// setup (accounts, keypairs, bumps) can be kept on `self`.
+pub trait Exploit {
+    /// Kebab-case name used to pick the exploit, e.g. `arbitrary-cpi`.
+    fn name(&self) -> &'static str;
+
+    /// The program under attack.
+    fn program(&self) -> Program;
+
+    /// One line on what the attacker gets out of it.
+    fn summary(&self) -> &'static str;
+
+    /// How bad it is for the program's users when the attack goes through.
+    fn severity(&self) -> Severity;
+
+    /// Where the insecure build goes wrong.
+    fn location(&self) -> Location;
+
+    /// Creates the victim's accounts and loads any attacker programs.
+    fn setup(&mut self, env: &mut Env) -> Result<()>;
+
+    /// Sends the attacker's transactions.
+    fn attack(&mut self, env: &mut Env) -> Result<()>;
+
+    /// Checks the on-chain state the attack was after, failing with
+    /// [`Error::NotCompromised`] when it is not there.
+    fn assert_compromised(&self, env: &Env) -> Result<()>;
+
+    /// The error the secure build rejects [`Exploit::attack`] with, or `None`
+    /// when the secure build lets every transaction through and the attack is
+    /// only made harmless.
+    fn expected_error(&self) -> Option<ProgramError>;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Critical,
+    High,
+    Medium,
+    Low,
+}
+
+impl Severity {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Severity::Critical => "critical",
+            Severity::High => "high",
+            Severity::Medium => "medium",
+            Severity::Low => "low",
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The vulnerable code an exploit goes through.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct Location {
+    /// `module::instruction` as a client would call it, e.g.
+    /// `insecure_program::insecure_transfer`.
+    pub instruction: &'static str,
+    /// Source of the insecure build, relative to the workspace.
+    pub file: &'static str,
+    /// The `fn` or `struct` in `file` that has the bug.
+    pub symbol: &'static str,
+}
+
+#[derive(Debug)]
+pub enum Outcome {
+    /// The attack went through and [`Exploit::assert_compromised`] holds.
+    Compromised,
+    /// The attack was stopped, either by a failed transaction or because the
+    /// state it was after never materialised.
+    Blocked(Error),
+}
+
+impl Outcome {
+    /// Whether this is what `exploit` should do to the `variant` build: compromise
+    /// the insecure one, be blocked by the secure one with its expected error, or
+    /// go through without compromising it when no error is expected.
+    pub fn is_expected(&self, exploit: &dyn Exploit, variant: Variant) -> bool {
+        match (variant, self) {
+            (Variant::Insecure, Outcome::Compromised) => true,
+            (Variant::Secure, Outcome::Blocked(err)) => match exploit.expected_error() {
+                Some(expected) => err.program_error() == Some(expected),
+                None => matches!(err, Error::NotCompromised(_)),
+            },
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Compromised => f.write_str("compromised"),
+            Outcome::Blocked(err) => match err.program_error() {
+                Some(program_error) => write!(f, "blocked ({program_error})"),
+                None => write!(f, "blocked ({err})"),
+            },
+        }
+    }
+}
+
+/// What [`run`] saw.
+#[derive(Debug)]
+pub struct Report {
+    pub outcome: Outcome,
+    /// Every transaction [`Exploit::attack`] sent, oldest first.
+    pub attack: Vec<TransactionResult>,
+}
+
+impl Report {
+    /// Compute units used by the attack's transactions, failed ones included.
+    pub fn compute_units(&self) -> u64 {
+        self.attack
+            .iter()
+            .map(|result| match result {
+                Ok(meta) => meta.compute_units_consumed,
+                Err(failed) => failed.meta.compute_units_consumed,
+            })
+            .sum()
+    }
+
+    /// The program log of the attack's transactions.
+    pub fn logs(&self) -> impl Iterator<Item = &str> {
+        self.attack
+            .iter()
+            .flat_map(|result| match result {
+                Ok(meta) => &meta.logs,
+                Err(failed) => &failed.meta.logs,
+            })
+            .map(String::as_str)
+    }
+}
+
+/// Deploys `exploit`'s target into `env` and runs the exploit against it.
+///
+/// Only errors from deployment and [`Exploit::setup`] are returned as `Err`,
+/// anything after that is part of the [`Outcome`].
+pub fn run(exploit: &mut dyn Exploit, env: &mut Env) -> Result<Report> {
+    env.load(&exploit.program())?;
+    exploit.setup(env)?;
+
+    let attack_start = env.history.len();
+    let outcome = match exploit.attack(env) {
+        Err(err) => Outcome::Blocked(err),
+        Ok(()) => match exploit.assert_compromised(env) {
+            Ok(()) => Outcome::Compromised,
+            Err(err) => Outcome::Blocked(err),
+        },
+    };
+    Ok(Report {
+        outcome,
+        attack: env.history[attack_start..].to_vec(),
+    })
+}
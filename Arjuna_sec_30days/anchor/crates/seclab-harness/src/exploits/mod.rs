@@ -0,0 +1,35 @@
+//! Every registered [`Exploit`], one module per vulnerability.
+//!
+//! To add an attack, implement [`Exploit`] in the module for its vulnerability
+//! (or a new one), derive `Default` for it and add it to [`REGISTRY`].
+
+use crate::Exploit;
+
+mod arbitary_cpi;
+mod ata;
+mod bump_seed_canonicalization;
+mod ownership_verification;
+mod signer_checks;
+
+pub const REGISTRY: &[fn() -> Box<dyn Exploit>] = &[
+    boxed::<arbitary_cpi::DrainVault>,
+    boxed::<arbitary_cpi::MetadataCpi>,
+    boxed::<ata::FrontRunInit>,
+    boxed::<bump_seed_canonicalization::NonCanonicalBump>,
+    boxed::<ownership_verification::ForgedAdmin>,
+    boxed::<signer_checks::AdminTakeover>,
+];
+
+fn boxed<E: Exploit + Default + 'static>() -> Box<dyn Exploit> {
+    Box::<E>::default()
+}
+
+/// A fresh instance of every registered exploit.
+pub fn all() -> Vec<Box<dyn Exploit>> {
+    REGISTRY.iter().map(|new| new()).collect()
+}
+
+/// A fresh instance of the exploit called `name`.
+pub fn find(name: &str) -> Option<Box<dyn Exploit>> {
+    all().into_iter().find(|exploit| exploit.name() == name)
+}
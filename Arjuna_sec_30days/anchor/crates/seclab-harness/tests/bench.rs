@@ -0,0 +1,116 @@
+//! Every benchmark's honest call has to go through on both builds, cost no
+//! more than the checked-in baseline allows, and the baseline comparison has to
+//! flag builds that got more expensive.
+//!
+//! These need the `.so` files from `scripts/build-variants.sh`, so they are
+//! `#[ignore]`d by default; `anchor run test` builds them and runs the ignored
+//! tests too. A missing build fails the test.
+
+use seclab_harness::{
+    bench::{self, Baseline, Measurement, Regression, THRESHOLD_PERCENT},
+    Variant,
+};
+
+#[test]
+#[ignore = "needs the SBF builds from scripts/build-variants.sh"]
+fn benchmarks_succeed_on_both_builds() {
+    for variant in Variant::ALL {
+        for mut benchmark in bench::all() {
+            match bench::measure(benchmark.as_mut(), variant) {
+                Ok(units) => assert!(units > 0, "{} ({variant})", benchmark.name()),
+                Err(err) => panic!("{} ({variant}): {err}", benchmark.name()),
+            }
+        }
+    }
+}
+
+#[test]
+#[ignore = "needs the SBF builds from scripts/build-variants.sh"]
+fn benchmarks_stay_within_the_baseline() {
+    let path = bench::baseline_path();
+    assert!(
+        path.is_file(),
+        "{} is missing, record it with `anchor run bench -- --save`",
+        path.display()
+    );
+    let baseline = Baseline::load(&path).unwrap_or_else(|err| panic!("{err}"));
+    let mut current = Baseline::default();
+    for mut benchmark in bench::all() {
+        let measurement = bench::measure_both(benchmark.as_mut())
+            .unwrap_or_else(|err| panic!("{}: {err}", benchmark.name()));
+        current
+            .measurements
+            .insert(benchmark.name().into(), measurement);
+    }
+    assert_eq!(baseline.missing(&current), Vec::<&str>::new());
+    assert_eq!(
+        baseline.regressions(&current),
+        [],
+        "over {THRESHOLD_PERCENT}% of {}",
+        path.display()
+    );
+}
+
+#[test]
+fn benchmark_names_are_unique() {
+    let mut names: Vec<_> = bench::all()
+        .iter()
+        .map(|benchmark| benchmark.name())
+        .collect();
+    names.sort_unstable();
+    names.dedup();
+    assert_eq!(names.len(), bench::REGISTRY.len());
+}
+
+#[test]
+fn regressions_are_increases_over_the_baseline() {
+    let baseline: Baseline = toml::from_str(
+        r#"
+        ["signer_checks::set_admin"]
+        insecure = 1000
+        secure = 1200
+
+        ["ata::create_ata"]
+        insecure = 9000
+        secure = 9500
+        "#,
+    )
+    .unwrap();
+    let mut current = Baseline::default();
+    current.measurements.insert(
+        "signer_checks::set_admin".into(),
+        Measurement {
+            insecure: 900,
+            secure: 1300,
+        },
+    );
+    // Within the threshold
+    current.measurements.insert(
+        "ata::create_ata".into(),
+        Measurement {
+            insecure: 9000,
+            secure: 9500 + 9500 * THRESHOLD_PERCENT / 100,
+        },
+    );
+    current.measurements.insert(
+        "bump_seed_canonicalization::create".into(),
+        Measurement {
+            insecure: 1,
+            secure: 2,
+        },
+    );
+
+    assert_eq!(
+        baseline.regressions(&current),
+        vec![Regression {
+            benchmark: "signer_checks::set_admin".into(),
+            variant: Variant::Secure,
+            baseline: 1200,
+            current: 1300,
+        }]
+    );
+    assert_eq!(
+        baseline.missing(&current),
+        ["bump_seed_canonicalization::create"]
+    );
+}
@@ -0,0 +1,22 @@
+use seclab_harness::checklist::{manifest_path, parse_checklist, Manifest};
+
+#[test]
+fn manifest_matches_checklist_tree_and_registry() {
+    let manifest = Manifest::load(&manifest_path()).unwrap();
+    let problems = manifest.problems().unwrap();
+    assert!(problems.is_empty(), "{problems:#?}");
+}
+
+#[test]
+fn parses_table_of_contents_entries_only() {
+    let markdown = "\
+  * [Common pitfalls](#common-pitfalls)
+    + [Integer overflow or underflow](#integer-overflow-or-underflow)
+    + [Arbitrary cpi](#arbitrary-cpi)  
+### Arbitrary cpi
+";
+    let items = parse_checklist(markdown);
+    let anchors: Vec<_> = items.iter().map(|item| item.anchor.as_str()).collect();
+    assert_eq!(anchors, ["integer-overflow-or-underflow", "arbitrary-cpi"]);
+    assert_eq!(items[1].title, "Arbitrary cpi");
+}
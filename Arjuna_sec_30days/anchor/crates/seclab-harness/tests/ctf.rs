@@ -0,0 +1,37 @@
+//! The reference attacker program has to capture its challenge's flag.
+//!
+//! These need the `.so` files from `scripts/build-variants.sh`, so they are
+//! `#[ignore]`d by default; `anchor run test` builds them and runs the ignored
+//! tests too. A missing build fails the test.
+
+use seclab_harness::{ctf, ARBITARY_CPI_HACK};
+
+#[test]
+#[ignore = "needs the SBF builds from scripts/build-variants.sh"]
+fn arbitary_cpi_hack_captures_the_vault() {
+    let submission = ARBITARY_CPI_HACK.attacker_artifact();
+    let mut challenge = ctf::all()
+        .into_iter()
+        .find(|challenge| challenge.name() == "arbitrary-cpi")
+        .expect("arbitrary-cpi challenge");
+
+    let capture = ctf::play(challenge.as_mut(), &submission, "test");
+    assert!(capture.captured, "{capture:?}");
+    assert_eq!(capture.flag, Some(ctf::flag("test", "arbitrary-cpi")));
+}
+
+#[test]
+fn flags_differ_per_challenge_and_secret() {
+    assert_eq!(
+        ctf::flag("a", "signer-checks"),
+        ctf::flag("a", "signer-checks")
+    );
+    assert_ne!(
+        ctf::flag("a", "signer-checks"),
+        ctf::flag("b", "signer-checks")
+    );
+    assert_ne!(
+        ctf::flag("a", "signer-checks"),
+        ctf::flag("a", "arbitrary-cpi")
+    );
+}
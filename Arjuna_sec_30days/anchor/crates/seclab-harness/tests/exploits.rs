@@ -0,0 +1,38 @@
+//! Runs every registered exploit against both builds of its program: it has to
+//! compromise the insecure build and be blocked by the secure one.
+//!
+//! These need the `.so` files from `scripts/build-variants.sh`, so they are
+//! `#[ignore]`d by default; `anchor run test` builds them and runs the ignored
+//! tests too. A missing build fails the test.
+
+use seclab_harness::{exploits, run, Env, Variant};
+
+#[test]
+#[ignore = "needs the SBF builds from scripts/build-variants.sh"]
+fn exploits_compromise_insecure_and_are_blocked_by_secure() {
+    for variant in Variant::ALL {
+        for mut exploit in exploits::all() {
+            let mut env = Env::new(variant);
+            let outcome = match run(exploit.as_mut(), &mut env) {
+                Ok(report) => report.outcome,
+                Err(err) => panic!("{} ({variant}) setup failed: {err}", exploit.name()),
+            };
+            assert!(
+                outcome.is_expected(exploit.as_ref(), variant),
+                "{} ({variant}): {outcome:?}",
+                exploit.name()
+            );
+        }
+    }
+}
+
+#[test]
+fn exploit_names_are_unique() {
+    let mut names: Vec<_> = exploits::all()
+        .iter()
+        .map(|exploit| exploit.name())
+        .collect();
+    names.sort_unstable();
+    names.dedup();
+    assert_eq!(names.len(), exploits::REGISTRY.len());
+}
@@ -0,0 +1,102 @@
+//! Recorded runs replay exactly as recorded, and every fixture checked into
+//! `fixtures/` still does against its build.
+//!
+//! These need the `.so` files from `scripts/build-variants.sh`, so they are
+//! `#[ignore]`d by default; `anchor run test` builds them and runs the ignored
+//! tests too. A missing build fails the test.
+
+use std::fs;
+
+use seclab_harness::{
+    exploits,
+    fixture::{self, Fixture},
+    workspace_dir, Env, Variant,
+};
+use solana_sdk::{pubkey::Pubkey, signer::Signer};
+
+/// Exploits whose recording is checked in, so they can be shared and replayed
+/// without the exploit's code.
+const CHECKED_IN: &[&str] = &["ownership-verification", "signer-checks"];
+
+#[test]
+fn recording_replays_as_recorded() {
+    // Only LiteSVM's own SPL programs, so this runs without any lab build
+    let mut env = Env::new(Variant::Insecure);
+    let mint = env.create_mint(6);
+    env.start_recording();
+    let owner = Pubkey::new_unique();
+    let token_account = env.create_ata(&owner, &mint);
+    env.mint_to(&mint, &token_account, 42);
+    let recorded = Fixture::finish(&mut env, "mint-to");
+
+    assert_eq!(recorded.transactions.len(), 2);
+    assert!(recorded
+        .pre
+        .iter()
+        .any(|state| state.address == env.payer.pubkey().to_string()));
+    assert!(!recorded
+        .pre
+        .iter()
+        .any(|state| state.address == token_account.to_string()));
+
+    let json = serde_json::to_string(&recorded).unwrap();
+    let fixture: Fixture = serde_json::from_str(&json).unwrap();
+    assert_eq!(fixture, recorded);
+    assert_eq!(
+        fixture.replay(Variant::Insecure).unwrap(),
+        Vec::<String>::new()
+    );
+
+    let mut tampered = fixture.clone();
+    let state = tampered
+        .post
+        .iter_mut()
+        .find(|state| state.address == token_account.to_string())
+        .unwrap();
+    state.account.as_mut().unwrap().lamports += 1;
+    tampered.transactions[1].logs.pop();
+    let mismatches = tampered.replay(Variant::Insecure).unwrap();
+    assert_eq!(mismatches.len(), 2, "{mismatches:#?}");
+}
+
+#[test]
+#[ignore = "needs the SBF builds from scripts/build-variants.sh"]
+fn exploits_replay_as_recorded() {
+    for variant in Variant::ALL {
+        for mut exploit in exploits::all() {
+            let recorded = fixture::record(exploit.as_mut(), variant)
+                .unwrap_or_else(|err| panic!("{} ({variant}): {err}", exploit.name()));
+            let mismatches = recorded.replay(variant).unwrap();
+            assert!(
+                mismatches.is_empty(),
+                "{} ({variant}): {mismatches:#?}",
+                exploit.name()
+            );
+        }
+    }
+}
+
+#[test]
+#[ignore = "needs the SBF builds from scripts/build-variants.sh"]
+fn checked_in_fixtures_replay() {
+    let dir = workspace_dir().join("fixtures");
+    for name in CHECKED_IN {
+        let path = dir.join(format!("{name}.json"));
+        assert!(
+            path.is_file(),
+            "{} is missing, record it with `anchor run fixtures`",
+            path.display()
+        );
+    }
+    let entries = fs::read_dir(&dir).unwrap_or_else(|err| panic!("{}: {err}", dir.display()));
+    for path in entries.map(|entry| entry.unwrap().path()) {
+        if path.extension().is_none_or(|ext| ext != "json") {
+            continue;
+        }
+        let fixture = Fixture::load(&path).unwrap();
+        let mismatches = fixture
+            .replay(fixture.variant)
+            .unwrap_or_else(|err| panic!("{}: {err}", path.display()));
+        assert!(mismatches.is_empty(), "{}: {mismatches:#?}", path.display());
+    }
+}
@@ -0,0 +1,34 @@
+//! The report points at real code and renders every finding in each format.
+//! Exploits whose program is not built still get a finding, marked "not run".
+
+use seclab_harness::{exploits, report};
+
+#[test]
+fn report_covers_every_exploit() {
+    let findings = report::findings();
+    assert_eq!(findings.len(), exploits::REGISTRY.len());
+
+    for finding in &findings {
+        assert!(
+            finding.line.is_some(),
+            "{}: `{}` not found in {}",
+            finding.exploit,
+            finding.location.symbol,
+            finding.location.file
+        );
+    }
+
+    let markdown = report::markdown(&findings);
+    let sarif = report::sarif(&findings);
+    let json = report::json(&findings);
+    assert_eq!(sarif["version"], "2.1.0");
+    assert_eq!(
+        sarif["runs"][0]["results"].as_array().map(Vec::len),
+        Some(findings.len())
+    );
+    assert_eq!(json["summary"]["findings"], findings.len());
+    for finding in &findings {
+        assert!(markdown.contains(&format!("## {}\n", finding.class)));
+        assert!(markdown.contains(finding.location.instruction));
+    }
+}
@@ -0,0 +1,79 @@
+//! The lab's own workspace is consistent, and a broken one (the mistakes this
+//! workspace used to have) is reported.
+
+use std::{fs, path::Path};
+
+use seclab_harness::{workspace, workspace_dir, PROGRAMS};
+
+#[test]
+fn lab_workspace_is_consistent() {
+    let problems = workspace::problems(&workspace_dir(), &PROGRAMS).unwrap();
+    assert!(problems.is_empty(), "{problems:#?}");
+}
+
+fn write(root: &Path, path: &str, contents: &str) {
+    let path = root.join(path);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, contents).unwrap();
+}
+
+#[test]
+fn broken_workspace_is_reported() {
+    let root = std::env::temp_dir().join(format!("seclab-workspace-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    write(
+        &root,
+        "Anchor.toml",
+        r#"
+        [programs.localnet]
+        Signer_checks = "4fNG6uKsseMBMd8xuUAfhorfPZGPf5m2DULWssrkRFzh"
+        signer_checks = "4fNG6uKsseMBMd8xuUAfhorfPZGPf5m2DULWssrkRFzh"
+        ownership_verification = "FYXiSEMnQjzR1YSTrwS9VgCGv16YUDmVDBW5JjCjz6G2"
+        "#,
+    );
+    write(
+        &root,
+        "programs/signer_checks/Cargo.toml",
+        "[package]\nname = \"signer_checks\"\n",
+    );
+    write(
+        &root,
+        "programs/signer_checks/src/lib.rs",
+        r#"
+        declare_id!("4fNG6uKsseMBMd8xuUAfhorfPZGPf5m2DULWssrkRFzh");
+        entrypoint!(process_instruction);
+
+        #[program]
+        pub mod signer_checks {}
+        "#,
+    );
+    write(
+        &root,
+        "programs/ownership_verification/Cargo.toml",
+        "[package]\nname = \"ownership-verification\"\n",
+    );
+    write(
+        &root,
+        "programs/ownership_verification/src/insecure.rs",
+        "pub fn process_instruction() {}\n",
+    );
+
+    let problems = workspace::problems(&root, &[]).unwrap();
+    fs::remove_dir_all(&root).unwrap();
+
+    let expected = [
+        "programs/ownership_verification: no declare_id!",
+        "programs/ownership_verification: no entrypoint, neither #[program] nor entrypoint!",
+        "programs/signer_checks: both #[program] and entrypoint! define the `entrypoint` symbol",
+        "programs/signer_checks/src/lib.rs: entrypoint! is not behind #[cfg(not(feature = \"no-entrypoint\"))]",
+        "Anchor.toml [programs.localnet]: `Signer_checks` is not the [lib] name of any program, did you mean `signer_checks`?",
+        "Anchor.toml [programs.localnet]: `Signer_checks` and `signer_checks` are the same program",
+    ];
+    for message in expected {
+        assert!(
+            problems.iter().any(|problem| problem == message),
+            "missing {message:?} in {problems:#?}"
+        );
+    }
+    assert_eq!(problems.len(), expected.len(), "{problems:#?}");
+}
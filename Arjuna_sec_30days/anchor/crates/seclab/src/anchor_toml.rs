@@ -0,0 +1,45 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use solana_sdk::pubkey::Pubkey;
+
+/// The nearest `Anchor.toml` in the current directory or one of its parents.
+pub fn find() -> Option<PathBuf> {
+    find_file("Anchor.toml")
+}
+
+/// The nearest file called `name` in the current directory or one of its parents.
+pub fn find_file(name: &str) -> Option<PathBuf> {
+    let cwd = env::current_dir().ok()?;
+    cwd.ancestors()
+        .map(|dir| dir.join(name))
+        .find(|path| path.is_file())
+}
+
+/// The `[programs.localnet]` entries of the `Anchor.toml` at `path`, in file order.
+pub fn localnet_programs(path: &Path) -> Result<Vec<(String, Pubkey)>, String> {
+    let source = fs::read_to_string(path).map_err(|err| format!("{}: {err}", path.display()))?;
+    let manifest: toml::Table = source
+        .parse()
+        .map_err(|err| format!("{}: {err}", path.display()))?;
+
+    let localnet = manifest
+        .get("programs")
+        .and_then(|programs| programs.get("localnet"))
+        .and_then(toml::Value::as_table)
+        .ok_or_else(|| format!("{}: no [programs.localnet] table", path.display()))?;
+
+    localnet
+        .iter()
+        .map(|(name, id)| {
+            let id = id
+                .as_str()
+                .and_then(|id| Pubkey::from_str(id).ok())
+                .ok_or_else(|| format!("{}: `{name}` is not a program id", path.display()))?;
+            Ok((name.clone(), id))
+        })
+        .collect()
+}
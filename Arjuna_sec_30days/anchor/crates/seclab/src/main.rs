@@ -0,0 +1,516 @@
+//! `seclab`: lists the lab programs and runs their exploits.
+//!
+//! ```text
+//! seclab list
+//! seclab run arbitrary-cpi --variant secure
+//! seclab run                      # every exploit against both builds
+//! seclab ctf submissions/ --out results.json
+//! seclab checklist                # which checklist items the lab covers
+//! seclab report --out-dir reports/ # report.md, report.sarif and report.json
+//! seclab bench --save              # compute units per instruction, per build
+//! seclab check                     # Anchor.toml, declare_id! and keypairs agree
+//! seclab record signer-checks       # fixtures/signer-checks.json
+//! seclab replay fixtures/*.json --variant secure
+//! ```
+
+use std::{fs, path::PathBuf, process::ExitCode};
+
+use clap::{Parser, Subcommand};
+use seclab_harness::{
+    bench::{self, Baseline},
+    checklist::{Coverage, Manifest},
+    ctf, exploits,
+    fixture::{self, Fixture},
+    report, run, workspace, Env, Exploit, Program, Variant, PROGRAMS,
+};
+use serde_json::json;
+use solana_sdk::signature::{Keypair, Signer};
+
+mod anchor_toml;
+
+#[derive(Parser)]
+#[command(name = "seclab", about = "Solana security lab runner")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// List the programs in Anchor.toml with their vulnerability class and exploits
+    List {
+        /// Defaults to the nearest Anchor.toml above the current directory
+        #[arg(long)]
+        anchor_toml: Option<PathBuf>,
+    },
+    /// Run an exploit, or all of them, and check it against the expected outcome
+    Run {
+        /// Exploit name as shown by `seclab list`
+        exploit: Option<String>,
+        /// Only run against this build, `insecure` or `secure`
+        #[arg(long)]
+        variant: Option<Variant>,
+        /// Don't print the program log
+        #[arg(long, short)]
+        quiet: bool,
+    },
+    /// Score a directory of player programs, `<challenge>.so` each, against the CTF challenges
+    Ctf {
+        submissions: PathBuf,
+        /// Write the results as JSON to this file
+        #[arg(long)]
+        out: Option<PathBuf>,
+        /// Event secret the flags are derived from, random if not set
+        #[arg(long, env = "SECLAB_CTF_SECRET")]
+        secret: Option<String>,
+    },
+    /// Show which items of the security checklist have executable coverage
+    Checklist {
+        /// Defaults to the nearest checklist.toml above the current directory
+        #[arg(long)]
+        manifest: Option<PathBuf>,
+    },
+    /// Run every exploit against both builds and write up the findings
+    Report {
+        /// Write report.md, report.sarif and report.json here instead of
+        /// printing the Markdown report
+        #[arg(long)]
+        out_dir: Option<PathBuf>,
+    },
+    /// Measure the compute units of each instruction on both builds against a baseline
+    Bench {
+        /// Defaults to compute-units.toml next to the nearest Anchor.toml
+        #[arg(long)]
+        baseline: Option<PathBuf>,
+        /// Store these measurements as the new baseline
+        #[arg(long)]
+        save: bool,
+    },
+    /// Cross-check Anchor.toml, [lib] names, declare_id! and target/deploy keypairs
+    Check {
+        /// Defaults to the directory of the nearest Anchor.toml
+        #[arg(long)]
+        workspace: Option<PathBuf>,
+    },
+    /// Record an exploit run, or all of them, as replayable JSON fixtures
+    Record {
+        /// Exploit name as shown by `seclab list`
+        exploit: Option<String>,
+        #[arg(long, default_value = "insecure")]
+        variant: Variant,
+        /// Defaults to fixtures/ next to the nearest Anchor.toml
+        #[arg(long)]
+        out_dir: Option<PathBuf>,
+    },
+    /// Replay fixtures and report where a build differs from the recording
+    Replay {
+        #[arg(required = true)]
+        fixtures: Vec<PathBuf>,
+        /// Defaults to the build each fixture was recorded against
+        #[arg(long)]
+        variant: Option<Variant>,
+    },
+}
+
+fn main() -> ExitCode {
+    let result = match Cli::parse().command {
+        Command::List { anchor_toml } => list(anchor_toml),
+        Command::Run {
+            exploit,
+            variant,
+            quiet,
+        } => run_exploits(exploit.as_deref(), variant, quiet),
+        Command::Ctf {
+            submissions,
+            out,
+            secret,
+        } => score_ctf(submissions, out, secret),
+        Command::Checklist { manifest } => checklist(manifest),
+        Command::Report { out_dir } => write_report(out_dir),
+        Command::Bench { baseline, save } => benchmark(baseline, save),
+        Command::Check { workspace } => check(workspace),
+        Command::Record {
+            exploit,
+            variant,
+            out_dir,
+        } => record(exploit.as_deref(), variant, out_dir),
+        Command::Replay { fixtures, variant } => replay(&fixtures, variant),
+    };
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn list(anchor_toml: Option<PathBuf>) -> Result<bool, String> {
+    let path = anchor_toml
+        .or_else(anchor_toml::find)
+        .ok_or("no Anchor.toml found, pass --anchor-toml")?;
+    let exploits = exploits::all();
+
+    println!("{:<28} {:<46} {:<34} EXPLOITS", "PROGRAM", "ID", "CLASS");
+    for (name, id) in anchor_toml::localnet_programs(&path)? {
+        let program = Program::by_id(&id);
+        let names: Vec<_> = exploits
+            .iter()
+            .filter(|exploit| Some(exploit.program()) == program)
+            .map(|exploit| exploit.name())
+            .collect();
+        println!(
+            "{:<28} {:<46} {:<34} {}",
+            name,
+            id.to_string(),
+            program.map_or("-", |program| program.class),
+            names.join(", ")
+        );
+    }
+    Ok(true)
+}
+
+fn run_exploits(name: Option<&str>, variant: Option<Variant>, quiet: bool) -> Result<bool, String> {
+    let known: Vec<_> = exploits::all()
+        .iter()
+        .map(|exploit| exploit.name())
+        .collect();
+    let selected = match name {
+        Some(name) if known.contains(&name) => vec![name],
+        Some(name) => {
+            return Err(format!(
+                "no exploit `{name}`, expected one of: {}",
+                known.join(", ")
+            ))
+        }
+        None => known,
+    };
+    let variants = match variant {
+        Some(variant) => vec![variant],
+        None => Variant::ALL.to_vec(),
+    };
+
+    let (mut passed, mut total) = (0, 0);
+    for variant in variants {
+        for name in &selected {
+            // A fresh instance per run, so nothing from a previous variant leaks in
+            let mut exploit = exploits::find(name).expect("registered exploit");
+            total += 1;
+            if run_one(exploit.as_mut(), variant, quiet) {
+                passed += 1;
+            }
+        }
+    }
+
+    if total > 1 {
+        println!("score: {passed}/{total} passed");
+    }
+    Ok(passed == total)
+}
+
+fn run_one(exploit: &mut dyn Exploit, variant: Variant, quiet: bool) -> bool {
+    let name = exploit.name();
+    let mut env = Env::new(variant);
+    let report = match run(exploit, &mut env) {
+        Ok(report) => report,
+        Err(err) => {
+            println!("{name} [{variant}]: ERROR {err}");
+            return false;
+        }
+    };
+
+    let pass = report.outcome.is_expected(exploit, variant);
+    println!(
+        "{name} [{variant}]: {} {}, {} compute units",
+        if pass { "PASS" } else { "FAIL" },
+        report.outcome,
+        report.compute_units()
+    );
+    if !quiet {
+        for line in report.logs() {
+            println!("    {line}");
+        }
+    }
+    pass
+}
+
+fn score_ctf(
+    submissions: PathBuf,
+    out: Option<PathBuf>,
+    secret: Option<String>,
+) -> Result<bool, String> {
+    if !submissions.is_dir() {
+        return Err(format!("{} is not a directory", submissions.display()));
+    }
+    let secret = secret.unwrap_or_else(|| Keypair::new().pubkey().to_string());
+
+    let mut results = Vec::new();
+    let (mut score, mut max_score) = (0, 0);
+    for mut challenge in ctf::all() {
+        let submission = submissions.join(format!("{}.so", challenge.name()));
+        max_score += challenge.points();
+        if !submission.is_file() {
+            println!("{}: no submission", challenge.name());
+            results.push(json!({
+                "challenge": challenge.name(),
+                "points": challenge.points(),
+                "captured": false,
+                "score": 0,
+                "flag": null,
+                "error": "no submission",
+            }));
+            continue;
+        }
+
+        let capture = ctf::play(challenge.as_mut(), &submission, &secret);
+        score += capture.score();
+        match (&capture.flag, &capture.error) {
+            (Some(flag), _) => println!("{}: captured {flag}", capture.challenge),
+            (None, Some(err)) => println!("{}: not captured ({err})", capture.challenge),
+            (None, None) => println!("{}: not captured", capture.challenge),
+        }
+        results.push(json!({
+            "challenge": capture.challenge,
+            "points": capture.points,
+            "captured": capture.captured,
+            "score": capture.score(),
+            "flag": capture.flag,
+            "error": capture.error,
+        }));
+    }
+    println!("score: {score}/{max_score}");
+
+    if let Some(out) = out {
+        let report = json!({
+            "score": score,
+            "max_score": max_score,
+            "challenges": results,
+        });
+        let report = serde_json::to_string_pretty(&report).expect("JSON value");
+        fs::write(&out, report + "\n").map_err(|err| format!("{}: {err}", out.display()))?;
+    }
+    Ok(true)
+}
+
+fn checklist(manifest: Option<PathBuf>) -> Result<bool, String> {
+    let path = manifest
+        .or_else(|| anchor_toml::find_file("checklist.toml"))
+        .ok_or("no checklist.toml found, pass --manifest")?;
+    let manifest = Manifest::load(&path).map_err(|err| err.to_string())?;
+    let titles = manifest.checklist_items().map_err(|err| err.to_string())?;
+
+    let mut items: Vec<_> = manifest.items.iter().collect();
+    items.sort_by_key(|item| item.coverage());
+    println!("{:<9} {:<46} EXPLOITS", "COVERAGE", "ITEM");
+    for item in &items {
+        let title = titles
+            .iter()
+            .find(|entry| entry.anchor == item.anchor)
+            .map_or(item.anchor.as_str(), |entry| entry.title.as_str());
+        let line = format!(
+            "{:<9} {:<46} {}",
+            item.coverage().as_str(),
+            title,
+            item.exploits.join(", ")
+        );
+        println!("{}", line.trim_end());
+    }
+
+    let count = |coverage| {
+        items
+            .iter()
+            .filter(|item| item.coverage() == coverage)
+            .count()
+    };
+    println!(
+        "{}/{} items have executable coverage, {} program only, {} prose only",
+        count(Coverage::Exploit),
+        items.len(),
+        count(Coverage::Program),
+        count(Coverage::Prose)
+    );
+
+    let untied: Vec<_> = exploits::all()
+        .iter()
+        .map(|exploit| exploit.name())
+        .filter(|name| {
+            !items
+                .iter()
+                .any(|item| item.exploits.iter().any(|e| e == name))
+        })
+        .collect();
+    if !untied.is_empty() {
+        println!(
+            "exploits not tied to a checklist item: {}",
+            untied.join(", ")
+        );
+    }
+
+    let problems = manifest.problems().map_err(|err| err.to_string())?;
+    for problem in &problems {
+        eprintln!("{}: {problem}", path.display());
+    }
+    Ok(problems.is_empty())
+}
+
+fn write_report(out_dir: Option<PathBuf>) -> Result<bool, String> {
+    let findings = report::findings();
+    let expected = findings.iter().all(|finding| finding.is_expected());
+
+    let Some(dir) = out_dir else {
+        print!("{}", report::markdown(&findings));
+        return Ok(expected);
+    };
+    fs::create_dir_all(&dir).map_err(|err| format!("{}: {err}", dir.display()))?;
+    let pretty = |value| serde_json::to_string_pretty(&value).expect("JSON value") + "\n";
+    for (file, contents) in [
+        ("report.md", report::markdown(&findings)),
+        ("report.sarif", pretty(report::sarif(&findings))),
+        ("report.json", pretty(report::json(&findings))),
+    ] {
+        let path = dir.join(file);
+        fs::write(&path, contents).map_err(|err| format!("{}: {err}", path.display()))?;
+        println!("wrote {}", path.display());
+    }
+    Ok(expected)
+}
+
+fn benchmark(baseline: Option<PathBuf>, save: bool) -> Result<bool, String> {
+    let path = baseline
+        .or_else(|| anchor_toml::find().map(|path| path.with_file_name(bench::BASELINE)))
+        .ok_or("no Anchor.toml found, pass --baseline")?;
+    let mut baseline = if path.is_file() {
+        Baseline::load(&path).map_err(|err| err.to_string())?
+    } else if save {
+        Baseline::default()
+    } else {
+        return Err(format!(
+            "no baseline at {}, record it with `anchor run bench -- --save`",
+            path.display()
+        ));
+    };
+
+    let mut ok = true;
+    let mut current = Baseline::default();
+    println!(
+        "{:<40} {:>9} {:>9} {:>17}",
+        "BENCHMARK", "INSECURE", "SECURE", "FIX COST"
+    );
+    for mut benchmark in bench::all() {
+        let name = benchmark.name();
+        match bench::measure_both(benchmark.as_mut()) {
+            Ok(measurement) => {
+                let percent = measurement.fix_cost() as f64 * 100.0 / measurement.insecure as f64;
+                println!(
+                    "{name:<40} {:>9} {:>9} {:>17}",
+                    measurement.insecure,
+                    measurement.secure,
+                    format!("{:+} ({percent:+.1}%)", measurement.fix_cost())
+                );
+                current.measurements.insert(name.to_string(), measurement);
+            }
+            Err(err) => {
+                println!("{name:<40} ERROR {err}");
+                ok = false;
+            }
+        }
+    }
+
+    let missing = baseline.missing(&current);
+    for name in &missing {
+        println!("missing: {name} has no baseline");
+    }
+    let regressions = baseline.regressions(&current);
+    for regression in &regressions {
+        println!(
+            "regression: {} [{}]: {} -> {} compute units (over {}%)",
+            regression.benchmark,
+            regression.variant,
+            regression.baseline,
+            regression.current,
+            bench::THRESHOLD_PERCENT
+        );
+    }
+
+    if save {
+        baseline.measurements.extend(current.measurements);
+        baseline.save(&path).map_err(|err| err.to_string())?;
+        println!("saved baseline to {}", path.display());
+        return Ok(ok);
+    }
+    Ok(ok && missing.is_empty() && regressions.is_empty())
+}
+
+fn check(root: Option<PathBuf>) -> Result<bool, String> {
+    let root = root
+        .or_else(|| anchor_toml::find().and_then(|path| Some(path.parent()?.to_path_buf())))
+        .ok_or("no Anchor.toml found, pass --workspace")?;
+    let problems = workspace::problems(&root, &PROGRAMS).map_err(|err| err.to_string())?;
+    for problem in &problems {
+        println!("{problem}");
+    }
+    match problems.len() {
+        0 => println!("no problems found"),
+        1 => println!("1 problem"),
+        count => println!("{count} problems"),
+    }
+    Ok(problems.is_empty())
+}
+
+fn record(name: Option<&str>, variant: Variant, out_dir: Option<PathBuf>) -> Result<bool, String> {
+    let dir = out_dir
+        .or_else(|| anchor_toml::find().map(|path| path.with_file_name("fixtures")))
+        .ok_or("no Anchor.toml found, pass --out-dir")?;
+    let mut selected = exploits::all();
+    if let Some(name) = name {
+        selected.retain(|exploit| exploit.name() == name);
+        if selected.is_empty() {
+            return Err(format!("no exploit `{name}`"));
+        }
+    }
+    fs::create_dir_all(&dir).map_err(|err| format!("{}: {err}", dir.display()))?;
+
+    let mut ok = true;
+    for mut exploit in selected {
+        let path = dir.join(format!("{}.json", exploit.name()));
+        match fixture::record(exploit.as_mut(), variant).and_then(|fixture| fixture.save(&path)) {
+            Ok(()) => println!("{} [{variant}]: wrote {}", exploit.name(), path.display()),
+            Err(err) => {
+                println!("{} [{variant}]: ERROR {err}", exploit.name());
+                ok = false;
+            }
+        }
+    }
+    Ok(ok)
+}
+
+fn replay(paths: &[PathBuf], variant: Option<Variant>) -> Result<bool, String> {
+    let mut ok = true;
+    for path in paths {
+        let fixture = Fixture::load(path).map_err(|err| err.to_string())?;
+        let variant = variant.unwrap_or(fixture.variant);
+        match fixture.replay(variant) {
+            Ok(mismatches) if mismatches.is_empty() => {
+                println!("{} [{variant}]: PASS as recorded", path.display())
+            }
+            Ok(mismatches) => {
+                println!(
+                    "{} [{variant}]: FAIL {} differences from the {} recording",
+                    path.display(),
+                    mismatches.len(),
+                    fixture.variant
+                );
+                for mismatch in mismatches {
+                    println!("    {mismatch}");
+                }
+                ok = false;
+            }
+            Err(err) => {
+                println!("{} [{variant}]: ERROR {err}", path.display());
+                ok = false;
+            }
+        }
+    }
+    Ok(ok)
+}
@@ -1,13 +1,115 @@
 // INSECURE CODE : {Vulnerable to Arbitrary CPI}
 
+use anchor_lang::prelude::*;
+
+use crate::ID;
+
+use anchor_lang::solana_program::{
+    account_info::{next_account_info,AccountInfo},
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::spl_token::{self, instruction::TokenInstruction};
+
+// Native vault: sends `amount` tokens from the user's vault ATA back to the user.
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let token_program = next_account_info(accounts_iter)?;
+    let mint = next_account_info(accounts_iter)?;
+    let user_authority = next_account_info(accounts_iter)?;
+    let vault_authority = next_account_info(accounts_iter)?;
+    let vault_token_account = next_account_info(accounts_iter)?;
+    let user_token_account = next_account_info(accounts_iter)?;
+
+    if instruction_data.len() < 8  {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    let amount = u64::from_le_bytes(instruction_data[0..8].try_into().unwrap());
+
+    if !user_authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // Derive vault authority PDA
+    let (expected_vault_authority, bump) = Pubkey::find_program_address(
+        &[b"vault", user_authority.key.as_ref()],
+        program_id,
+    );
+
+    // Validate vault authority account
+    if expected_vault_authority != *vault_authority.key {
+        msg!("Invalid Vault Authority PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    // Derive expected vault token account ATA
+    let expected_vault_ata = get_associated_token_address(
+        vault_authority.key,
+        mint.key,
+    );
+
+    // Validate Vault Token account
+    if expected_vault_ata != *vault_token_account.key {
+        msg!("Invalid Vault Token Account");
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    msg!("Transfering {} tokens", amount);
+
+    // Hand-rolled SPL Token `Transfer`, sent to whatever `token_program` the caller passed
+    let transfer_ix = Instruction {
+        program_id: *token_program.key,  // @audit never compared against spl_token::id()
+        accounts: vec![
+            AccountMeta::new(*vault_token_account.key, false),
+            AccountMeta::new(*user_token_account.key, false),
+            AccountMeta::new_readonly(*vault_authority.key, true),
+        ],
+        data: TokenInstruction::Transfer { amount }.pack(),
+    };
+
+    // Create PDA seeds
+    let seeds: &[&[u8]] = &[
+        b"vault",
+        user_authority.key.as_ref(),
+        &[bump],
+    ];
+
+    // Sign with PDA and execute transfer
+    invoke_signed(
+        &transfer_ix,
+        &[
+            vault_token_account.clone(),
+            user_token_account.clone(),
+            vault_authority.clone(),
+            token_program.clone(),
+        ],
+        &[seeds],
+    )?;
+
+    Ok(())
+}
+
 #[program]
 pub mod insecure_program {
     use super::*;
 
+    #[allow(unused_must_use)]
     pub fn insecure_transfer(ctx: Context<InsecureAccounts>, amount: u64) -> Result<()> {
         // vulnerability 1: Raw invoke without program ID checks
         let transfer_ix = spl_token::instruction::transfer(
-            &ctx.accounts.token_program.key(),  // No verification of actual program ID 
+            &ctx.accounts.token_program.key(),  // No verification of actual program ID
             &ctx.accounts.source.key(),
             &ctx.accounts.destination.key(),
             &ctx.accounts.authority.key(),
@@ -31,18 +133,40 @@ pub mod insecure_program {
         Ok(())
 
     }
+
+    // Anything that is not `insecure_transfer` is handled by the native vault
+    pub fn fallback<'info>(
+        program_id: &Pubkey,
+        accounts: &'info [AccountInfo<'info>],
+        data: &[u8],
+    ) -> Result<()> {
+        Ok(process_instruction(program_id, accounts, data)?)
+    }
+}
+
+// Calls into whichever program was passed as `metadata_program`
+fn process_metadata(metadata_program: &UncheckedAccount) -> Result<()> {
+    let ix = Instruction::new_with_bytes(metadata_program.key(), &[], vec![]);
+    invoke(&ix, &[])?;
+
+    Ok(())
 }
 
 
 #[derive(Accounts)]
 pub struct InsecureAccounts<'info> {
     // vUlnerability: 4 --> Using UncheckedAccount instead of program type
+    /// CHECK: not checked, this is the bug
     token_program: UncheckedAccount<'info>,  // No program validation
+    /// CHECK: not checked
     source: UncheckedAccount<'info>,
+    /// CHECK: not checked
     destination: UncheckedAccount<'info>,
+    /// CHECK: not checked
     authority: UncheckedAccount<'info>,
 
     // Vulnerability 5 : Allowing untrusted program input
+    /// CHECK: not checked, this is the bug
     #[account(mut)]
     metadata_program: UncheckedAccount<'info>, // could be Malicious
-}
\ No newline at end of file
+}
@@ -0,0 +1,121 @@
+use anchor_lang::solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    pubkey::Pubkey,
+    system_instruction,
+    program::invoke_signed,
+    program_error::ProgramError,
+    sysvar::{rent::Rent, Sysvar},
+};
+
+use crate::{PDA_SEED, PDA_SPACE};
+
+// Instruction data is `[tag, bump]`, the PDA is always derived from `[PDA_SEED, payer]`:
+//    - 0 : create the PDA account with the caller's bump
+//    - 1 : validate that the second account is the PDA for the caller's bump
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let payer = next_account_info(accounts_iter)?;
+    let pda_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    let (tag, bump) = match instruction_data {
+        [tag, bump] => (*tag, *bump),
+        _ => return Err(ProgramError::InvalidInstructionData),
+    };
+    let seeds: &[&[u8]] = &[PDA_SEED, payer.key.as_ref()];
+
+    match tag {
+        0 => {
+            let lamports = Rent::get()?.minimum_balance(PDA_SPACE as usize);
+            create_pda_account(payer, pda_account, system_program, program_id, seeds, lamports, PDA_SPACE, bump)
+        }
+        1 => valiadate_pda_usage(pda_account.key, program_id, seeds, bump),
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn create_pda_account<'a>(
+    payer: &AccountInfo<'a>,
+    pda_account: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    program_id: &Pubkey,
+    seeds: &[&[u8]],
+    lamports: u64,
+    space: u64,
+    bump: u8,
+) -> ProgramResult {
+    // Create the `create account` instruction.
+    let ix = system_instruction::create_account(
+        payer.key,
+        pda_account.key,
+        lamports,
+        space,
+        program_id,
+    );
+
+    // Combine teh Provided seeds with the bumps seed
+    // First, create a slice holding the bump as a one_element byte slice
+
+    let bump_slice = &[bump]; // it derived from teh upper bump that is not safe
+    // Then, build a vector of all seed slices (copies of teh provided seeds plus the bump)
+    let mut seeds_with_bump: Vec<&[u8]> = seeds.to_vec();
+    seeds_with_bump.push(bump_slice);
+
+    // The invoke_signed call require a slice seeds slices
+    invoke_signed(
+        &ix,
+        &[payer.clone(),pda_account.clone(), system_program.clone()],
+        // We pass our combined seeds as a  signal signer seeds array,
+        &[seeds_with_bump.as_slice()],
+    )
+}
+
+
+// Validates that a provided PDA matches the one computed from the given seeds and bump
+
+// # Arguments
+// * `provided_pda` - The PDA account passed into the instruction
+// * `program_id` - The current program's ID
+// `seeds` - A slice of seed byte slice used to derive the PDA
+// `bump` - The bump seed that should have been used
+
+// # Returns
+// * `Ok(())` if the PDA matches the provided PDA
+// * `Err(ProgramError::InvalidArgument)` if the computed PDA doest not matches
+
+#[allow(unused_must_use, clippy::no_effect)]
+pub fn valiadate_pda_usage(
+    provided_pda: &Pubkey,
+    program_id: &Pubkey,
+    seeds: &[&[u8]],
+    bump: u8,
+) -> ProgramResult {
+    // Build the seeds array by appending the bump seed.
+
+    let bump_slice = &[bump];
+    let mut seeds_with_bump: Vec<&[u8]> = seeds.to_vec();
+
+    seeds_with_bump.push(bump_slice);
+
+    // Compute the PDA using the given seeds and program_id.
+    let computed_pda = Pubkey::create_program_address(seeds_with_bump.as_slice(), program_id)?;  // @audit here is the issue
+
+    // compare teh computed PDA  with the provided PDA
+    if computed_pda == *provided_pda {
+        msg!("PDA validation successful.");
+        Ok(())
+    }
+
+    else{
+        msg!("PDA validation failed, Expected: {}, Provided: {}", computed_pda,provided_pda);
+        Err::<(), _>(ProgramError::InvalidArgument); // @audit the error is dropped, validation never fails
+        Ok(())
+    }
+}
@@ -1,21 +1,50 @@
-use anchor_lang::{prelude::*, system_program::CreateNonceAccountWithSeedBumps};
-
-use solana_program::{
-    account_info::AccountInfo,
-    entry_point::ProgramResult,
+use anchor_lang::solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
     msg,
     pubkey::Pubkey,
     system_instruction,
-    program::invoked_signed,
+    program::invoke_signed,
     program_error::ProgramError,
+    sysvar::{rent::Rent, Sysvar},
 };
 
-declare_id!("FnGsJt3T2HAykbM24JbahuJjr9WVieJgPJJxdfgn6MsS");
+use crate::{PDA_SEED, PDA_SPACE};
 
-pub fn create_pda_account(
-    payer: &AccountInfo,
-    pda_account: &AccountInfo,
-    system_program: &AccountInfo,
+// Instruction data is `[tag, bump]`, the PDA is always derived from `[PDA_SEED, payer]`.
+// The bump byte is accepted for layout compatibility but ignored, the canonical bump is derived instead:
+//    - 0 : create the PDA account
+//    - 1 : validate that the second account is the canonical PDA
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let payer = next_account_info(accounts_iter)?;
+    let pda_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    let tag = match instruction_data {
+        [tag, _bump] => *tag,
+        _ => return Err(ProgramError::InvalidInstructionData),
+    };
+    let seeds: &[&[u8]] = &[PDA_SEED, payer.key.as_ref()];
+
+    match tag {
+        0 => {
+            let lamports = Rent::get()?.minimum_balance(PDA_SPACE as usize);
+            create_pda_account(payer, pda_account, system_program, program_id, seeds, lamports, PDA_SPACE)
+        }
+        1 => valiadate_pda_usage(pda_account.key, program_id, seeds),
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+pub fn create_pda_account<'a>(
+    payer: &AccountInfo<'a>,
+    pda_account: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
     program_id: &Pubkey,
     seeds: &[&[u8]],
     lamports: u64,
@@ -43,8 +72,9 @@ pub fn create_pda_account(
 
 
     // Prepare the seeds vector for invoke_signed by appending the canonical bump
+    let bump_slice = &[canonical_bump];
     let mut seeds_with_bump: Vec<&[u8]> = seeds.to_vec();
-    seeds_with_bump.push(&[canonical_bump]);
+    seeds_with_bump.push(bump_slice);
 
     // The invoke_signed call require a slice seeds slices
     invoke_signed(
@@ -85,7 +115,6 @@ pub fn valiadate_pda_usage(
 
     else{
         msg!("PDA validation failed, Expected: {}, Provided: {}",expected_pda,provided_pda);
-        Err(ProgramError::InvalidArgument);
-        Ok(())
+        Err(ProgramError::InvalidArgument)
     }
 }
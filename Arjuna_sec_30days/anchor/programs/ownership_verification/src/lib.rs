@@ -0,0 +1,58 @@
+use anchor_lang::solana_program::{program_error::ProgramError, pubkey::Pubkey};
+
+anchor_lang::declare_id!("FYXiSEMnQjzR1YSTrwS9VgCGv16YUDmVDBW5JjCjz6G2");
+
+// Both versions of the exercise live in this crate and are picked with a cargo feature:
+//    - `insecure` (default) : `insecure.rs`, reads the admin from any account it is handed
+//    - `secure`             : `secure.rs`, only trusts state stored in an account it owns
+//
+// Build the fixed program with `--no-default-features --features secure`.
+
+#[cfg(all(feature = "insecure", feature = "secure"))]
+compile_error!("the `insecure` and `secure` features are mutually exclusive");
+
+#[cfg(not(any(feature = "insecure", feature = "secure")))]
+compile_error!("enable exactly one of the `insecure` or `secure` features");
+
+#[cfg(feature = "insecure")]
+mod insecure;
+#[cfg(feature = "insecure")]
+pub use insecure::*;
+
+#[cfg(feature = "secure")]
+mod secure;
+#[cfg(feature = "secure")]
+pub use secure::*;
+
+#[cfg(not(feature = "no-entrypoint"))]
+anchor_lang::solana_program::entrypoint!(process_instruction);
+
+// Program State Definition
+
+#[derive(Debug,PartialEq)]
+pub struct ProgramState {
+    pub admin: Pubkey,
+    pub vault_amount: u64,
+}
+
+impl ProgramState {
+    pub const LEN : usize = 32 + 8 ; // Pubkey + u64
+
+    #[allow(deprecated)]
+    pub fn unpack(input: &[u8]) -> Result<Self,ProgramError> {
+        if input.len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let admin  = Pubkey::new(&input[0..32]);
+        let vault_amount = u64::from_le_bytes(input[32..40].try_into().unwrap());
+
+        Ok(ProgramState{admin,vault_amount})
+    }
+
+    pub fn pack(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(Self::LEN);
+        data.extend_from_slice(self.admin.as_ref());
+        data.extend_from_slice(&self.vault_amount.to_le_bytes());
+        data
+    }
+}
@@ -1,28 +1,17 @@
-// Program State Definition
-
-impl ProgramState {
-    pub const LEN : usize = 32 + 8 ; // Pubkey + u64
-
-    pub fn unpack(inout: &[u8]) -> Result<Self,ProgramError> {
-        if input.len() != Self::LEN {
-            return Err(ProgramError::InvalidAccountData);
-        }
-        let admin  = Pubkey::new(&input[0..32]);
-        let vault_amount = u64::from_le_bytes(input[332..40].try_into().unwrap());
-
-        Ok(ProgramState{admin,vault_amount})
-    }
-}
-
-entrypoint!(process_instruction);
-
-#[derive(Debug,PartialEq)]
-pub struct ProgramState {
-    pub admin: Pubkey,
-    pub vault_amount: u64,
-}
-
-fn process_instruction(
+use anchor_lang::solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_instruction,
+    sysvar::{rent::Rent, Sysvar},
+};
+
+use crate::ProgramState;
+
+pub fn process_instruction(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
     instruction_data: &[u8],
@@ -37,12 +26,17 @@ fn process_instruction(
         return Err(ProgramError::MissingRequiredSignature);
     }
 
-    // Vulnerable Admin checks
+    // Only trust admin state stored in an account this program owns
+    if admin_account.owner != program_id {
+        msg!("Admin account is not owned by this program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
     let program_state = ProgramState::unpack(&admin_account.data.borrow())?;
 
     if !instruction_data.is_empty() && instruction_data[0] == 0 {
         // Admin command : withdraw from the vault
-        let amount = u64::from_le_bytes(instruction_data[1..9].try_into().unwarp());
+        let amount = u64::from_le_bytes(instruction_data[1..9].try_into().unwrap());
 
         **vault_account.try_borrow_mut_lamports()? -= amount;
         **admin_account.try_borrow_mut_lamports()? += amount;
@@ -53,7 +47,7 @@ fn process_instruction(
     else {
         // Initialize program (should be admin-only)
         let rent = Rent::get()?;
-        let required_lamports = rent.minimum_balance(ProgramState::LEN as u64);
+        let required_lamports = rent.minimum_balance(ProgramState::LEN);
 
         invoke(
             &system_instruction::transfer (
@@ -65,9 +59,9 @@ fn process_instruction(
         )?;
 
 
-        let mut data = program_state.pack();
+        let data = program_state.pack();
         admin_account.data.borrow_mut().copy_from_slice(&data);
     }
 
     Ok(())
-}
\ No newline at end of file
+}
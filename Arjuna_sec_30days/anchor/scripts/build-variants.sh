@@ -0,0 +1,22 @@
+#!/usr/bin/env bash
+# Builds every program under programs/ twice, once per variant feature:
+#   target/deploy/insecure/<name>.so  (default features)
+#   target/deploy/secure/<name>.so    (--no-default-features --features secure)
+# and every attacker program under attackers/ once, into target/deploy/attacker/.
+# These are the artifacts crates/seclab-harness loads.
+set -euo pipefail
+
+cd "$(dirname "$0")/.."
+
+for manifest in programs/*/Cargo.toml; do
+    cargo build-sbf --manifest-path "$manifest" \
+        --sbf-out-dir target/deploy/insecure
+    cargo build-sbf --manifest-path "$manifest" \
+        --sbf-out-dir target/deploy/secure \
+        --no-default-features --features secure
+done
+
+for manifest in attackers/*/Cargo.toml; do
+    cargo build-sbf --manifest-path "$manifest" \
+        --sbf-out-dir target/deploy/attacker
+done